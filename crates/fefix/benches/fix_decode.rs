@@ -1,6 +1,7 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use fefix::prelude::*;
 use fefix::tagvalue::{Config, Decoder};
+use std::fmt::Write;
 
 const FIX_MESSAGE: &[u8] = b"8=FIX.4.4|9=122|35=D|34=215|49=CLIENT12|52=20100225-19:41:57.316|56=B|1=Marcel|11=13346|21=1|40=2|44=5|54=1|59=0|60=20100225-19:39:52.020|10=072|";
 
@@ -8,6 +9,26 @@ fn decode_fix_message(fix_decoder: &mut Decoder<Config>, msg: &[u8]) {
     fix_decoder.decode(msg).expect("Invalid FIX message");
 }
 
+/// Builds a `MarketDataSnapshotFullRefresh <W>` with `num_entries` entries in
+/// `NoMDEntries <268>`, e.g. what a market data feed would send.
+fn market_data_snapshot(num_entries: usize) -> Vec<u8> {
+    let mut body = format!(
+        "35=W|34=215|49=FEED|52=20100225-19:41:57.316|56=CLIENT12|55=EUR/USD|268={}|",
+        num_entries
+    );
+    for i in 0..num_entries {
+        write!(
+            body,
+            "269={}|270=1.{:05}|271={}|272=20100225|273=19:41:57.316|",
+            i % 2,
+            10000 + i,
+            1000 * (i + 1)
+        )
+        .unwrap();
+    }
+    format!("8=FIX.4.4|9={}|{}10=000|", body.len(), body).into_bytes()
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     let fix_dictionary = Dictionary::fix44();
     let fix_decoder = &mut Decoder::<Config>::new(fix_dictionary);
@@ -16,6 +37,16 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("FIX tag-value decoding", |b| {
         b.iter(|| decode_fix_message(black_box(fix_decoder), black_box(FIX_MESSAGE)))
     });
+
+    let mut group = c.benchmark_group("FIX tag-value decoding by message size");
+    for num_entries in [1, 10, 100, 1000] {
+        let msg = market_data_snapshot(num_entries);
+        group.throughput(Throughput::Bytes(msg.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(msg.len()), &msg, |b, msg| {
+            b.iter(|| decode_fix_message(black_box(fix_decoder), black_box(msg)))
+        });
+    }
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,49 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fefix::prelude::*;
+use fefix::tagvalue::{Config, Encoder};
+
+/// Encodes a `NewOrderSingle <D>` with `text` as `Text <58>`.
+fn encode_new_order_single(encoder: &mut Encoder<Config>, buffer: &mut Vec<u8>, text: &str) {
+    buffer.clear();
+    let mut msg = encoder.start_message(b"FIX.4.4", buffer, b"D");
+    msg.set(34, 215u64);
+    msg.set(49, "CLIENT12");
+    msg.set(52, "20100225-19:41:57.316");
+    msg.set(56, "B");
+    msg.set(1, "Marcel");
+    msg.set(11, "13346");
+    msg.set(21, "1");
+    msg.set(40, "2");
+    msg.set(44, "5");
+    msg.set(54, "1");
+    msg.set(58, text);
+    msg.set(59, "0");
+    msg.set(60, "20100225-19:39:52.020");
+    msg.done();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("FIX tag-value encoding by BodyLength mode");
+    for text_len in [10, 100, 1000, 10000] {
+        let text = "x".repeat(text_len);
+        group.throughput(Throughput::Bytes(text_len as u64));
+        for zero_padded in [true, false] {
+            let mut config = Config::default();
+            config.set_zero_padded_body_length(zero_padded);
+            let encoder = &mut Encoder::new(config);
+            let buffer = &mut Vec::new();
+            let mode = if zero_padded {
+                "zero-padded"
+            } else {
+                "canonical"
+            };
+            group.bench_with_input(BenchmarkId::new(mode, text_len), &text, |b, text| {
+                b.iter(|| encode_new_order_single(black_box(encoder), buffer, black_box(text)))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
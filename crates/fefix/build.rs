@@ -14,36 +14,41 @@ use std::path::PathBuf;
 
 fn main() -> io::Result<()> {
     println!("cargo:rerun-if-changed=src/fefix_core");
+    println!("cargo:rerun-if-changed=resources/repositories");
     #[cfg(feature = "fix40")]
-    codegen(Dictionary::fix40(), "fix40.rs")?;
+    build(Dictionary::fix40(), "FIX-4.0", "fix40.rs")?;
     #[cfg(feature = "fix41")]
-    codegen(Dictionary::fix41(), "fix41.rs")?;
+    build(Dictionary::fix41(), "FIX-4.1", "fix41.rs")?;
     #[cfg(feature = "fix42")]
-    codegen(Dictionary::fix42(), "fix42.rs")?;
+    build(Dictionary::fix42(), "FIX-4.2", "fix42.rs")?;
     #[cfg(feature = "fix43")]
-    codegen(Dictionary::fix43(), "fix43.rs")?;
+    build(Dictionary::fix43(), "FIX-4.3", "fix43.rs")?;
     // FIX 4.4 is always available.
-    codegen(Dictionary::fix44(), "fix44.rs")?;
+    build(Dictionary::fix44(), "FIX-4.4", "fix44.rs")?;
     #[cfg(feature = "fix50")]
-    codegen(Dictionary::fix50(), "fix50.rs")?;
+    build(Dictionary::fix50(), "FIX-5.0", "fix50.rs")?;
     #[cfg(feature = "fix50sp1")]
-    codegen(Dictionary::fix50sp1(), "fix50sp1.rs")?;
+    build(Dictionary::fix50sp1(), "FIX-5.0-SP1", "fix50sp1.rs")?;
     #[cfg(feature = "fix50sp2")]
-    codegen(Dictionary::fix50sp2(), "fix50sp2.rs")?;
+    build(Dictionary::fix50sp2(), "FIX-5.0-SP2", "fix50sp2.rs")?;
     #[cfg(feature = "fixt11")]
-    codegen(Dictionary::fixt11(), "fixt11.rs")?;
+    build(Dictionary::fixt11(), "FIXT-1.1", "fixt11.rs")?;
     Ok(())
 }
 
-fn codegen(fix_dictionary: Dictionary, filename: &str) -> io::Result<()> {
+fn build(fix_dictionary: Dictionary, name: &str, codegen_filename: &str) -> io::Result<()> {
     // All generated code must go in `OUT_DIR`. We avoid writing directly to
     // `src/` to avoid compilation issues on `crates.io`, which disallows
     // writing.
     let dir = PathBuf::from(var("OUT_DIR").unwrap());
+    // Built-in dictionaries are loaded from their binary form at runtime,
+    // which is much faster than parsing XML.
+    let binary_path = dir.join(format!("{}.bin", name));
+    File::create(binary_path)?.write_all(&fix_dictionary.to_bytes())?;
     let codegen_settings = &mut codegen::Settings::default();
     codegen_settings.fefix_crate_name = "crate".to_string();
     let code = codegen::gen_definitions(fix_dictionary, codegen_settings);
-    let path = dir.join(filename);
+    let path = dir.join(codegen_filename);
     let file = &mut File::create(path)?;
     file.write_all(code.as_bytes())?;
     Ok(())
@@ -1,3 +1,5 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
 /// Operations on a growable in-memory buffer.
 ///
 /// This trait is intented to be used as a thin compatibility layer between
@@ -131,11 +133,115 @@ where
     }
 }
 
+/// A pool of reusable values (typically [`Buffer`]s), to avoid heap
+/// allocations in hot paths.
+///
+/// [`Pool`] hands out values with [`Pool::take`] and gets them back with
+/// [`Pool::put`], so that the memory they own can be reused over and over.
+/// Values are handed out as they were given back: it's up to the caller to
+/// clear them. Clones of a [`Pool`] share the same values, so that values can
+/// be given back from other threads.
+///
+/// # Examples
+///
+/// ```
+/// use fefix::Pool;
+///
+/// let pool = Pool::<Vec<u8>>::new(16);
+/// let mut buffer = pool.take();
+/// buffer.extend_from_slice(b"8=FIX.4.4|");
+/// let capacity = buffer.capacity();
+/// buffer.clear();
+/// pool.put(buffer);
+///
+/// // No allocation takes place.
+/// let buffer = pool.take();
+/// assert_eq!(buffer.capacity(), capacity);
+/// ```
+#[derive(Debug)]
+pub struct Pool<T> {
+    values: Arc<Mutex<Vec<T>>>,
+    max_len: usize,
+}
+
+impl<T> Pool<T>
+where
+    T: Default,
+{
+    /// Creates a new, empty [`Pool`] that holds at most `max_len` values.
+    /// Values given back to a full [`Pool`] are dropped.
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            // Reserve all space right away, so that giving values back never
+            // allocates.
+            values: Arc::new(Mutex::new(Vec::with_capacity(max_len))),
+            max_len,
+        }
+    }
+
+    /// Takes a value out of `self`, or creates a new one with [`Default`] if
+    /// `self` is empty.
+    pub fn take(&self) -> T {
+        self.values().pop().unwrap_or_default()
+    }
+
+    /// Gives `value` back to `self`, so that it can be reused by
+    /// [`Pool::take`].
+    pub fn put(&self, value: T) {
+        let mut values = self.values();
+        if values.len() < self.max_len {
+            values.push(value);
+        }
+    }
+
+    /// Returns the number of values currently available in `self`.
+    pub fn len(&self) -> usize {
+        self.values().len()
+    }
+
+    /// Returns `true` if `self` has no values available.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn values(&self) -> MutexGuard<Vec<T>> {
+        // A panic while holding the lock can't leave the values in an
+        // inconsistent state.
+        self.values
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<T> Clone for Pool<T> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            max_len: self.max_len,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use quickcheck_macros::quickcheck;
 
+    #[test]
+    fn pool_reuses_values() {
+        let pool = Pool::<Vec<u8>>::new(1);
+        assert!(pool.is_empty());
+        let mut buffer = pool.take();
+        buffer.extend_from_slice(b"foobar");
+        let ptr = buffer.as_ptr();
+        let clone = pool.clone();
+        clone.put(buffer);
+        pool.put(Vec::new());
+        assert_eq!(clone.len(), 1);
+        assert_eq!(pool.take().as_ptr(), ptr);
+        assert!(pool.is_empty());
+    }
+
     #[quickcheck]
     fn vec_slicing_is_consistent(mut vec: Vec<u8>) -> bool {
         let buf_as_slice = Vec::from(Buffer::as_mut_slice(&mut vec));
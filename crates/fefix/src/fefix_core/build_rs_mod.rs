@@ -1,3 +1,14 @@
+// `build.rs` produces the binary forms of built-in dictionaries, so it must
+// parse their QuickFIX specification files instead.
+macro_rules! builtin_dictionary {
+    ($name:literal, $repository_version:literal) => {
+        Dictionary::from_builtin_spec(
+            include_str!(concat!("resources/quickfix/", $name, ".xml")),
+            $repository_version,
+        )
+    };
+}
+
 pub mod codegen;
 pub mod dict;
 
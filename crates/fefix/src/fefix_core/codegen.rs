@@ -1,7 +1,6 @@
 //! Code generation utilities.
 
-use super::{dict, TagU16};
-use fnv::FnvHashSet;
+use super::dict::{self, IsFieldDefinition};
 use heck::{ToShoutySnakeCase, ToUpperCamelCase};
 use indoc::indoc;
 use std::marker::PhantomData;
@@ -67,13 +66,12 @@ fn gen_enum_variant_of_allowed_value(
     allowed_value: dict::FieldEnum,
     settings: &Settings,
 ) -> String {
-    let mut identifier = allowed_value.description().to_upper_camel_case();
-    let identifier_needs_prefix = !allowed_value
-        .description()
-        .chars()
-        .next()
-        .unwrap_or('_')
-        .is_ascii_alphabetic();
+    // QuickFIX descriptions are symbolic names already, e.g. `LIMIT`.
+    let name = allowed_value
+        .symbolic_name()
+        .unwrap_or_else(|| allowed_value.description());
+    let mut identifier = name.to_upper_camel_case();
+    let identifier_needs_prefix = !name.chars().next().unwrap_or('_').is_ascii_alphabetic();
     if identifier_needs_prefix {
         identifier = format!("_{}", identifier);
     }
@@ -158,27 +156,42 @@ impl Default for Settings {
 
 /// Generates the Rust code for a FIX field definition.
 pub fn gen_field_definition(fix_dictionary: dict::Dictionary, field: dict::Field) -> String {
-    let mut header = FnvHashSet::default();
-    let mut trailer = FnvHashSet::default();
-    for item in fix_dictionary
-        .component_by_name("StandardHeader")
-        .unwrap()
-        .items()
-    {
-        if let dict::LayoutItemKind::Field(f) = item.kind() {
-            header.insert(f.tag());
-        }
-    }
-    for item in fix_dictionary
-        .component_by_name("StandardTrailer")
-        .unwrap()
-        .items()
-    {
-        if let dict::LayoutItemKind::Field(f) = item.kind() {
-            trailer.insert(f.tag());
-        }
-    }
-    gen_field_definition_with_hashsets(fix_dictionary, &header, &trailer, field)
+    let name = field.name().to_shouty_snake_case();
+    let tag = field.tag().to_string();
+    let field_location = match field.location() {
+        dict::FieldLocation::Header => "Header",
+        dict::FieldLocation::Body => "Body",
+        dict::FieldLocation::Trailer => "Trailer",
+    };
+    let doc_link = onixs_link_to_field(fix_dictionary.get_version(), field);
+    let doc = if let Some(doc_link) = doc_link {
+        format!(
+            "/// Field attributes for [`{} <{}>`]({}).",
+            name, tag, doc_link
+        )
+    } else {
+        format!("/// Field attributes for `{} <{}>`.", name, tag)
+    };
+    format!(
+        indoc!(
+            r#"
+                {doc}
+                pub const {identifier}: &HardCodedFixFieldDefinition = &HardCodedFixFieldDefinition {{
+                    name: "{name}",
+                    tag: {tag},
+                    is_group_leader: {group},
+                    data_type: FixDatatype::{data_type},
+                    location: FieldLocation::{field_location},
+                }};"#
+        ),
+        doc = doc,
+        identifier = name,
+        name = field.name(),
+        tag = tag,
+        group = field.name().ends_with("Len"),
+        field_location = field_location,
+        data_type = <&'static str as From<dict::FixDatatype>>::from(field.data_type().basetype()),
+    )
 }
 
 /// Generates `const` implementors of
@@ -283,52 +296,6 @@ fn onixs_dictionary_id(fix_version: &str) -> Option<&str> {
     })
 }
 
-fn gen_field_definition_with_hashsets(
-    fix_dictionary: dict::Dictionary,
-    header_tags: &FnvHashSet<TagU16>,
-    trailer_tags: &FnvHashSet<TagU16>,
-    field: dict::Field,
-) -> String {
-    let name = field.name().to_shouty_snake_case();
-    let tag = field.tag().to_string();
-    let field_location = if header_tags.contains(&field.tag()) {
-        "Header"
-    } else if trailer_tags.contains(&field.tag()) {
-        "Trailer"
-    } else {
-        "Body"
-    };
-    let doc_link = onixs_link_to_field(fix_dictionary.get_version(), field);
-    let doc = if let Some(doc_link) = doc_link {
-        format!(
-            "/// Field attributes for [`{} <{}>`]({}).",
-            name, tag, doc_link
-        )
-    } else {
-        format!("/// Field attributes for `{} <{}>`.", name, tag)
-    };
-    format!(
-        indoc!(
-            r#"
-                {doc}
-                pub const {identifier}: &HardCodedFixFieldDefinition = &HardCodedFixFieldDefinition {{
-                    name: "{name}",
-                    tag: {tag},
-                    is_group_leader: {group},
-                    data_type: FixDatatype::{data_type},
-                    location: FieldLocation::{field_location},
-                }};"#
-        ),
-        doc = doc,
-        identifier = name,
-        name = field.name(),
-        tag = tag,
-        group = field.name().ends_with("Len"),
-        field_location = field_location,
-        data_type = <&'static str as From<dict::FixDatatype>>::from(field.data_type().basetype()),
-    )
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
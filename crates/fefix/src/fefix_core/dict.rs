@@ -5,11 +5,16 @@
 use self::symbol_table::{Key, KeyRef, SymbolTable, SymbolTableIndex};
 use super::TagU16;
 use fnv::FnvHashMap;
+use once_cell::sync::OnceCell;
 use quickfix::{ParseDictionaryError, QuickFixReader};
 use std::fmt;
+use std::path::Path;
 use std::sync::Arc;
 
+pub use condition::Condition;
 pub use datatype::FixDatatype;
+pub use diff::{Change, DictionaryDiff, FieldChange, FieldDiff, LayoutChange, LayoutDiff};
+pub use lookup::{FlatGroup, FlatLayout, FlatLayoutItem, FlatPresenceRule, LookupTables, TagSet};
 
 pub trait DataFieldLookup<F> {
     fn field_is_data(&self, field: F) -> bool;
@@ -53,6 +58,10 @@ pub enum FieldLocation {
 
 type InternalId = u32;
 
+/// The scenario of messages and components which don't specify one. Only
+/// Orchestra specifications define other scenarios.
+pub const BASE_SCENARIO: &str = "base";
+
 /// Specifies business semantics for application-level entities within the FIX
 /// Protocol.
 ///
@@ -85,82 +94,18 @@ struct DictionaryData {
     messages: Vec<MessageData>,
     //layout_items: Vec<LayoutItemData>,
     categories: Vec<CategoryData>,
+    sections: Vec<SectionData>,
     header: Vec<FieldData>,
+    // Computed on first use, see `Dictionary::lookup_tables`.
+    lookup_tables: OnceCell<LookupTables>,
 }
 
 impl fmt::Display for Dictionary {
+    /// Writes `self` as a QuickFIX-style specification file. See
+    /// [`Dictionary::to_quickfix_spec`].
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "<fix type='FIX' version='{}'>", self.inner.version)?;
-        {
-            writeln!(f, " <header>")?;
-            let std_header = self.component_by_name("StandardHeader").unwrap();
-            for item in std_header.items() {
-                display_layout_item(2, item, f)?;
-            }
-            writeln!(f, " </header>")?;
-        }
-        {
-            writeln!(f, " <messages>")?;
-            for message in self.iter_messages() {
-                // FIXME: msgcat's value
-                writeln!(
-                    f,
-                    "  <message name='{}' msgtype='{}' msgcat='TODO'>",
-                    message.name(),
-                    message.msg_type(),
-                )?;
-                for item in message.layout() {
-                    display_layout_item(2, item, f)?;
-                }
-                writeln!(f, "  </message>")?;
-            }
-            writeln!(f, " </messages>")?;
-        }
-        {
-            writeln!(f, " <header>")?;
-            let std_header = self.component_by_name("StandardTrailer").unwrap();
-            for item in std_header.items() {
-                display_layout_item(2, item, f)?;
-            }
-            writeln!(f, " </header>")?;
-        }
-        Ok(())
-    }
-}
-
-fn display_layout_item(indent: u32, item: LayoutItem, f: &mut fmt::Formatter) -> fmt::Result {
-    for _ in 0..indent {
-        write!(f, " ")?;
-    }
-    match item.kind() {
-        LayoutItemKind::Field(_) => {
-            writeln!(
-                f,
-                "<field name='{}' required='{}' />",
-                item.tag_text(),
-                item.required(),
-            )?;
-        }
-        LayoutItemKind::Group(_, _fields) => {
-            writeln!(
-                f,
-                "<group name='{}' required='{}' />",
-                item.tag_text(),
-                item.required(),
-            )?;
-            writeln!(f, "</group>")?;
-        }
-        LayoutItemKind::Component(_c) => {
-            writeln!(
-                f,
-                "<component name='{}' required='{}' />",
-                item.tag_text(),
-                item.required(),
-            )?;
-            writeln!(f, "</component>")?;
-        }
+        quickfix::write_spec(self, f)
     }
-    Ok(())
 }
 
 impl DictionaryData {
@@ -183,7 +128,9 @@ impl Dictionary {
                 messages: Vec::new(),
                 //layout_items: Vec::new(),
                 categories: Vec::new(),
+                sections: Vec::new(),
                 header: Vec::new(),
+                lookup_tables: OnceCell::new(),
             }),
         }
     }
@@ -196,6 +143,160 @@ impl Dictionary {
         QuickFixReader::new_dict_from_xml(&xml_document)
     }
 
+    /// Converts `self` into a QuickFIX-style specification file, which
+    /// [`Dictionary::from_quickfix_spec`] reads back into an equivalent
+    /// [`Dictionary`]. The `Display` implementation of [`Dictionary`] writes
+    /// the same document.
+    ///
+    /// QuickFIX specification files can't represent everything, so some
+    /// information is lost along the way: abbreviations, categories and
+    /// sections, descriptions, pedigrees, presence rules, and messages and
+    /// components of scenarios other than [`BASE_SCENARIO`].
+    ///
+    /// ```
+    /// use fefix::Dictionary;
+    ///
+    /// let dict = Dictionary::fix44();
+    /// let spec = dict.to_quickfix_spec();
+    /// assert!(spec.starts_with("<fix type='FIX' major='4' minor='4' servicepack='0'>"));
+    ///
+    /// let dict = Dictionary::from_quickfix_spec(spec).unwrap();
+    /// assert_eq!(dict.get_version(), "FIX.4.4");
+    /// ```
+    pub fn to_quickfix_spec(&self) -> String {
+        self.to_string()
+    }
+
+    /// Converts `self` into a compact binary form, which
+    /// [`Dictionary::from_bytes`] reads back into an identical [`Dictionary`].
+    /// Unlike QuickFIX specification files, no information is lost along the
+    /// way.
+    ///
+    /// Reading the binary form is much faster than parsing XML, which makes it
+    /// well suited for dictionaries that are produced ahead of time (e.g. by
+    /// build scripts) and loaded at startup. Built-in dictionaries such as
+    /// [`Dictionary::fix44`] are stored this way.
+    ///
+    /// ```
+    /// use fefix::Dictionary;
+    ///
+    /// let dict = Dictionary::fix44();
+    /// let bytes = dict.to_bytes();
+    /// let copy = Dictionary::from_bytes(&bytes).unwrap();
+    /// assert_eq!(copy.get_version(), "FIX.4.4");
+    /// assert!(dict.diff(&copy).is_empty());
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        binary::write(self)
+    }
+
+    /// Reads the binary form of a [`Dictionary`] produced by
+    /// [`Dictionary::to_bytes`]. `bytes` may as well come from a
+    /// memory-mapped file.
+    ///
+    /// The binary form is tied to the version of this crate which wrote it, so
+    /// unknown formats are rejected just like truncated or corrupted input.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseDictionaryError> {
+        binary::read(bytes)
+    }
+
+    /// Returns a [`DictionaryEditor`] which starts from a copy of `self`.
+    /// `self` is left untouched.
+    pub fn edit(&self) -> DictionaryEditor {
+        DictionaryEditor {
+            builder: DictionaryBuilder::from_dictionary(self),
+        }
+    }
+
+    /// Creates a copy of `self` with the overlay file `overlay` applied on top
+    /// of it. See [`DictionaryEditor::apply_overlay`] for the file format.
+    pub fn with_overlay<S: AsRef<str>>(&self, overlay: S) -> Result<Self, ParseDictionaryError> {
+        let mut editor = self.edit();
+        editor.apply_overlay(overlay)?;
+        Ok(editor.build())
+    }
+
+    /// Compares `self` (the old version) with `other` (the new version) and
+    /// returns all differences between the two, e.g. to review the changes
+    /// in a counterparty's specification.
+    ///
+    /// ```
+    /// use fefix::dict::{FieldChange, FixDatatype};
+    /// use fefix::Dictionary;
+    ///
+    /// let old = Dictionary::fix44();
+    /// let new = old
+    ///     .with_overlay("<fix><fields><field number='5001' name='VenueOrderTag' type='STRING' /></fields></fix>")
+    ///     .unwrap();
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.fields.len(), 1);
+    /// assert_eq!(diff.fields[0].tag, 5001);
+    /// assert_eq!(
+    ///     diff.fields[0].changes,
+    ///     vec![FieldChange::Added(FixDatatype::String)]
+    /// );
+    /// println!("{}", diff.to_markdown());
+    /// ```
+    pub fn diff(&self, other: &Dictionary) -> DictionaryDiff {
+        diff::diff(self, other)
+    }
+
+    /// Attempts to read a FIX Orchestra repository (e.g. a venue's rules of
+    /// engagement) and convert it into a [`Dictionary`].
+    ///
+    /// Besides the usual fields, components and messages, Orchestra
+    /// repositories can define message scenarios (see
+    /// [`Dictionary::message_by_scenario`]) and conditional presence rules
+    /// (see [`LayoutItem::presence_rules`]).
+    pub fn from_orchestra_spec<S: AsRef<str>>(input: S) -> Result<Self, ParseDictionaryError> {
+        let xml_document = roxmltree::Document::parse(input.as_ref())
+            .map_err(|_| ParseDictionaryError::InvalidFormat)?;
+        orchestra::OrchestraReader::new_dict_from_xml(&xml_document)
+    }
+
+    /// Attempts to read the FIX Repository (2010 Edition) files for a single
+    /// FIX version and convert them into a [`Dictionary`]. `dir` is either the
+    /// version directory (e.g. `FIX.4.4`) or its `Base` subdirectory.
+    ///
+    /// Unlike QuickFIX specification files, the FIX Repository also carries
+    /// abbreviations, categories, sections, enum descriptions and
+    /// added/deprecated version information.
+    ///
+    /// ```no_run
+    /// use fefix::Dictionary;
+    ///
+    /// let dict = Dictionary::from_fix_repository(
+    ///     "resources/repositories/fix_repository_2010_edition_20140507/FIX.4.4",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(dict.get_version(), "FIX.4.4");
+    /// ```
+    pub fn from_fix_repository<P: AsRef<Path>>(dir: P) -> Result<Self, ParseDictionaryError> {
+        fix_repository::FixRepositoryReader::new_dict_from_dir(dir.as_ref())
+    }
+
+    /// Reads the bundled QuickFIX specification file `spec` and fills in the
+    /// metadata which it lacks (see [`DictionaryEditor::import_metadata`]) from
+    /// the bundled FIX Repository, if available. `repository_version` is the
+    /// name of the FIX Repository directory, e.g. `FIX.5.0SP2`.
+    fn from_builtin_spec(spec: &str, repository_version: &str) -> Self {
+        let dict = Dictionary::from_quickfix_spec(spec).unwrap();
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("resources/repositories/fix_repository_2010_edition_20140507")
+            .join(repository_version);
+        // The FIX Repository is not needed for building `fefix`, so it may be
+        // missing. The vendored FIX 4.3 one can't be read either, as it lacks
+        // many fields.
+        match Dictionary::from_fix_repository(dir) {
+            Ok(repository) => {
+                let mut editor = dict.edit();
+                editor.import_metadata(&repository);
+                editor.build()
+            }
+            Err(_) => dict,
+        }
+    }
+
     /// Creates a new empty FIX Dictionary with `FIX.???` as its version string.
     pub fn empty() -> Self {
         Self::new("FIX.???")
@@ -218,70 +319,61 @@ impl Dictionary {
     #[cfg(feature = "fix40")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "fix40")))]
     pub fn fix40() -> Self {
-        let spec = include_str!("resources/quickfix/FIX-4.0.xml");
-        Dictionary::from_quickfix_spec(spec).unwrap()
+        builtin_dictionary!("FIX-4.0", "FIX.4.0")
     }
 
     /// Creates a new [`Dictionary`] for FIX 4.1.
     #[cfg(feature = "fix41")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "fix41")))]
     pub fn fix41() -> Self {
-        let spec = include_str!("resources/quickfix/FIX-4.1.xml");
-        Dictionary::from_quickfix_spec(spec).unwrap()
+        builtin_dictionary!("FIX-4.1", "FIX.4.1")
     }
 
     /// Creates a new [`Dictionary`] for FIX 4.2.
     #[cfg(feature = "fix42")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "fix42")))]
     pub fn fix42() -> Self {
-        let spec = include_str!("resources/quickfix/FIX-4.2.xml");
-        Dictionary::from_quickfix_spec(spec).unwrap()
+        builtin_dictionary!("FIX-4.2", "FIX.4.2")
     }
 
     /// Creates a new [`Dictionary`] for FIX 4.3.
     #[cfg(feature = "fix43")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "fix43")))]
     pub fn fix43() -> Self {
-        let spec = include_str!("resources/quickfix/FIX-4.3.xml");
-        Dictionary::from_quickfix_spec(spec).unwrap()
+        builtin_dictionary!("FIX-4.3", "FIX.4.3")
     }
 
     /// Creates a new [`Dictionary`] for FIX 4.4.
     pub fn fix44() -> Self {
-        let spec = include_str!("resources/quickfix/FIX-4.4.xml");
-        Dictionary::from_quickfix_spec(spec).unwrap()
+        builtin_dictionary!("FIX-4.4", "FIX.4.4")
     }
 
     /// Creates a new [`Dictionary`] for FIX 5.0.
     #[cfg(feature = "fix50")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "fix50")))]
     pub fn fix50() -> Self {
-        let spec = include_str!("resources/quickfix/FIX-5.0.xml");
-        Dictionary::from_quickfix_spec(spec).unwrap()
+        builtin_dictionary!("FIX-5.0", "FIX.5.0")
     }
 
     /// Creates a new [`Dictionary`] for FIX 5.0 SP1.
     #[cfg(feature = "fix50sp1")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "fix50sp1")))]
     pub fn fix50sp1() -> Self {
-        let spec = include_str!("resources/quickfix/FIX-5.0-SP1.xml");
-        Dictionary::from_quickfix_spec(spec).unwrap()
+        builtin_dictionary!("FIX-5.0-SP1", "FIX.5.0SP1")
     }
 
     /// Creates a new [`Dictionary`] for FIX 5.0 SP2.
     #[cfg(feature = "fix50sp2")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "fix50sp1")))]
     pub fn fix50sp2() -> Self {
-        let spec = include_str!("resources/quickfix/FIX-5.0-SP2.xml");
-        Dictionary::from_quickfix_spec(spec).unwrap()
+        builtin_dictionary!("FIX-5.0-SP2", "FIX.5.0SP2")
     }
 
     /// Creates a new [`Dictionary`] for FIXT 1.1.
     #[cfg(feature = "fixt11")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "fixt11")))]
     pub fn fixt11() -> Self {
-        let spec = include_str!("resources/quickfix/FIXT-1.1.xml");
-        Dictionary::from_quickfix_spec(spec).unwrap()
+        builtin_dictionary!("FIXT-1.1", "FIXT.1.1")
     }
 
     #[cfg(test)]
@@ -353,6 +445,21 @@ impl Dictionary {
             .map(|data| Message(self, data))
     }
 
+    /// Returns the [`Message`] that has the given `msgtype` and `scenario`, if
+    /// any. [`Dictionary::message_by_msgtype`] always returns the
+    /// [`BASE_SCENARIO`].
+    pub fn message_by_scenario<S, T>(&self, msgtype: S, scenario: T) -> Option<Message>
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+    {
+        self.inner
+            .messages
+            .iter()
+            .find(|data| data.msg_type == msgtype.as_ref() && data.scenario == scenario.as_ref())
+            .map(|data| Message(self, data))
+    }
+
     /// Returns the [`Component`] named `name`, if any.
     pub fn component_by_name<S: AsRef<str>>(&self, name: S) -> Option<Component> {
         self.symbol(KeyRef::ComponentByName(name.as_ref()))
@@ -375,6 +482,27 @@ impl Dictionary {
             .map(|data| Datatype(self, data))
     }
 
+    /// Returns the precomputed [`LookupTables`] of `self`. They are computed on
+    /// first use and then shared by all clones of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::Dictionary;
+    ///
+    /// let dict = Dictionary::fix44();
+    /// let tables = dict.lookup_tables();
+    /// let new_order_single = tables.message_layout("D").unwrap();
+    /// assert!(new_order_single.required_tags().contains(54));
+    /// assert!(tables.header_tags().contains(49));
+    /// assert_eq!(tables.group(453).unwrap().delimiter_tag().get(), 448);
+    /// ```
+    pub fn lookup_tables(&self) -> &LookupTables {
+        self.inner
+            .lookup_tables
+            .get_or_init(|| LookupTables::new(self))
+    }
+
     /// Returns the [`Field`] associated with `tag`, if any.
     ///
     /// ```
@@ -386,9 +514,9 @@ impl Dictionary {
     /// assert_eq!(field1.name(), field2.name());
     /// ```
     pub fn field_by_tag(&self, tag: u32) -> Option<Field> {
-        self.symbol(KeyRef::FieldByTag(tag))
-            .map(|iid| self.inner.fields.get(*iid as usize).unwrap())
-            .map(|data| Field(self, data))
+        self.lookup_tables()
+            .field(tag)
+            .map(|(iid, _)| Field(self, &self.inner.fields[iid as usize]))
     }
 
     /// Returns the [`Field`] named `name`, if any.
@@ -455,259 +583,1305 @@ impl Dictionary {
             .iter()
             .map(move |data| Component(self, data))
     }
+
+    /// Returns an [`Iterator`] over this [`Dictionary`]'s abbreviations. Items
+    /// are in no particular order.
+    pub fn iter_abbreviations(&self) -> impl Iterator<Item = Abbreviation> {
+        self.inner
+            .abbreviations
+            .iter()
+            .map(move |data| Abbreviation(self, data))
+    }
+
+    /// Returns the [`Category`] named `name`, if any.
+    pub fn category_by_name<S: AsRef<str>>(&self, name: S) -> Option<Category> {
+        self.symbol(KeyRef::CategoryByName(name.as_ref()))
+            .map(|iid| self.inner.categories.get(*iid as usize).unwrap())
+            .map(|data| Category(self, data))
+    }
+
+    /// Returns the [`Section`] identified by `id` (e.g. `PreTrade`), if any.
+    pub fn section_by_id<S: AsRef<str>>(&self, id: S) -> Option<Section> {
+        self.symbol(KeyRef::SectionById(id.as_ref()))
+            .map(|iid| self.inner.sections.get(*iid as usize).unwrap())
+            .map(|data| Section(self, data))
+    }
+
+    /// Returns an [`Iterator`] over this [`Dictionary`]'s sections. Items are
+    /// in no particular order.
+    pub fn iter_sections(&self) -> impl Iterator<Item = Section> {
+        self.inner
+            .sections
+            .iter()
+            .map(move |data| Section(self, data))
+    }
 }
 
-struct DictionaryBuilder {
-    version: String,
-    symbol_table: FnvHashMap<Key, InternalId>,
-    abbreviations: Vec<AbbreviationData>,
-    data_types: Vec<DatatypeData>,
-    fields: Vec<FieldData>,
-    components: Vec<ComponentData>,
-    messages: Vec<MessageData>,
-    //layout_items: Vec<LayoutItemData>,
-    categories: Vec<CategoryData>,
-    header: Vec<FieldData>,
+/// Creates customized versions of a [`Dictionary`], e.g. with the user-defined
+/// fields and messages of a venue. Get one with [`Dictionary::edit`].
+///
+/// ```
+/// use fefix::dict::{FixDatatype, LayoutItemSpec, LayoutTarget};
+/// use fefix::Dictionary;
+///
+/// let mut editor = Dictionary::fix44().edit();
+/// editor
+///     .add_field(5001, "VenueOrderTag", FixDatatype::String)?
+///     .push_layout_item(
+///         LayoutTarget::Message("D"),
+///         LayoutItemSpec::field("VenueOrderTag", false),
+///     )?;
+/// let dict = editor.build();
+/// let new_order_single = dict.message_by_msgtype("D").unwrap();
+/// assert!(new_order_single
+///     .layout()
+///     .any(|item| item.tag_text() == "VenueOrderTag"));
+/// # Ok::<(), fefix::dict::EditDictionaryError>(())
+/// ```
+#[derive(Debug)]
+pub struct DictionaryEditor {
+    builder: DictionaryBuilder,
 }
 
-impl DictionaryBuilder {
-    pub fn new(version: String) -> Self {
-        Self {
-            version,
-            symbol_table: FnvHashMap::default(),
-            abbreviations: Vec::new(),
-            data_types: Vec::new(),
-            fields: Vec::new(),
-            components: Vec::new(),
-            messages: Vec::new(),
-            //layout_items: Vec::new(),
-            categories: Vec::new(),
-            header: Vec::new(),
-        }
-    }
+/// A layout that can be modified by [`DictionaryEditor`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LayoutTarget<'a> {
+    /// The layout of the message with this `MsgType <35>`.
+    Message(&'a str),
+    /// The layout of the component with this name. `StandardHeader` and
+    /// `StandardTrailer` are components, too.
+    Component(&'a str),
+    /// The items of all repeating groups counted by the `NumInGroup` field with
+    /// this name, wherever they appear.
+    Group(&'a str),
+}
 
-    pub fn symbol(&self, pkey: KeyRef) -> Option<&InternalId> {
-        self.symbol_table.get(&pkey as &dyn SymbolTableIndex)
+/// A name-based definition of a [`LayoutItem`], for use with
+/// [`DictionaryEditor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutItemSpec {
+    /// A field with this name.
+    Field { name: String, required: bool },
+    /// A component with this name.
+    Component { name: String, required: bool },
+    /// A repeating group, counted by the `NumInGroup` field with this name.
+    Group {
+        name: String,
+        required: bool,
+        items: Vec<LayoutItemSpec>,
+    },
+}
+
+impl LayoutItemSpec {
+    /// Creates a [`LayoutItemSpec::Field`].
+    pub fn field<S: ToString>(name: S, required: bool) -> Self {
+        Self::Field {
+            name: name.to_string(),
+            required,
+        }
     }
 
-    pub fn add_field(&mut self, field: FieldData) -> InternalId {
-        let iid = self.fields.len() as InternalId;
-        self.symbol_table
-            .insert(Key::FieldByName(field.name.clone()), iid);
-        self.symbol_table
-            .insert(Key::FieldByTag(field.tag as u32), iid);
-        self.fields.push(field);
-        iid
+    /// Creates a [`LayoutItemSpec::Component`].
+    pub fn component<S: ToString>(name: S, required: bool) -> Self {
+        Self::Component {
+            name: name.to_string(),
+            required,
+        }
     }
 
-    pub fn add_message(&mut self, message: MessageData) -> InternalId {
-        let iid = self.messages.len() as InternalId;
-        self.symbol_table
-            .insert(Key::MessageByName(message.name.clone()), iid);
-        self.symbol_table
-            .insert(Key::MessageByMsgType(message.msg_type.to_string()), iid);
-        self.messages.push(message);
-        iid
+    /// Creates a [`LayoutItemSpec::Group`].
+    pub fn group<S: ToString>(name: S, required: bool, items: Vec<LayoutItemSpec>) -> Self {
+        Self::Group {
+            name: name.to_string(),
+            required,
+            items,
+        }
     }
 
-    pub fn add_component(&mut self, component: ComponentData) -> InternalId {
-        let iid = self.components.len() as InternalId;
-        self.symbol_table
-            .insert(Key::ComponentByName(component.name.to_string()), iid);
-        self.components.push(component);
-        iid
+    /// Returns the name of the field, component, or `NumInGroup` field of
+    /// `self`.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Field { name, .. } => name,
+            Self::Component { name, .. } => name,
+            Self::Group { name, .. } => name,
+        }
     }
 
-    pub fn build(self) -> Dictionary {
-        Dictionary {
-            inner: Arc::new(DictionaryData {
-                version: self.version,
-                symbol_table: self.symbol_table,
-                abbreviations: self.abbreviations,
-                data_types: self.data_types,
-                fields: self.fields,
-                components: self.components,
-                messages: self.messages,
-                //layout_items: self.layout_items,
-                categories: self.categories,
-                header: self.header,
-            }),
+    /// Returns `true` if `self` is required within its layout.
+    pub fn required(&self) -> bool {
+        match self {
+            Self::Field { required, .. } => *required,
+            Self::Component { required, .. } => *required,
+            Self::Group { required, .. } => *required,
         }
     }
 }
 
-#[derive(Clone, Debug)]
-struct AbbreviationData {
-    abbreviation: String,
-    is_last: bool,
+/// A definition of a [`PresenceRule`], for use with [`DictionaryEditor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresenceRuleSpec {
+    name: Option<String>,
+    presence: Presence,
+    condition: String,
 }
 
-/// An [`Abbreviation`] is a standardized abbreviated form for a specific word,
-/// pattern, or name. Abbreviation data is mostly meant for documentation
-/// purposes, but in general it can have other uses as well, e.g. FIXML field
-/// naming.
-#[derive(Debug)]
-pub struct Abbreviation<'a>(&'a Dictionary, &'a AbbreviationData);
+impl PresenceRuleSpec {
+    /// Creates a rule which changes the presence of an item to `presence` when
+    /// `condition` holds. See [`Condition`] for the syntax.
+    pub fn new<S: ToString>(presence: Presence, condition: S) -> Self {
+        Self {
+            name: None,
+            presence,
+            condition: condition.to_string(),
+        }
+    }
 
-impl<'a> Abbreviation<'a> {
-    /// Returns the full term (non-abbreviated) associated with `self`.
-    pub fn term(&self) -> &str {
-        self.1.abbreviation.as_str()
+    /// Sets the name of `self`.
+    pub fn with_name<S: ToString>(mut self, name: S) -> Self {
+        self.name = Some(name.to_string());
+        self
     }
 }
 
-#[derive(Clone, Debug)]
-struct CategoryData {
-    /// **Primary key**. A string uniquely identifying this category.
-    name: String,
-    /// The FIXML file name for a Category.
-    fixml_filename: String,
+/// The error type returned by [`DictionaryEditor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditDictionaryError {
+    /// An entity with the same identifier already exists.
+    AlreadyExists(String),
+    /// The referenced entity doesn't exist.
+    NotFound(String),
+    /// The data is not valid.
+    InvalidData(String),
 }
 
-/// A [`Category`] is a collection of loosely related FIX messages or components
-/// all belonging to the same [`Section`].
-#[derive(Clone, Debug)]
-pub struct Category<'a>(&'a Dictionary, &'a CategoryData);
-
-#[derive(Clone, Debug)]
-struct ComponentData {
-    /// **Primary key.** The unique integer identifier of this component
-    /// type.
-    id: usize,
-    component_type: FixmlComponentAttributes,
-    layout_items: Vec<LayoutItemData>,
-    category_iid: InternalId,
-    /// The human readable name of the component.
-    name: String,
-    /// The name for this component when used in an XML context.
-    abbr_name: Option<String>,
+impl fmt::Display for EditDictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AlreadyExists(what) => write!(f, "{} already exists.", what),
+            Self::NotFound(what) => write!(f, "{} doesn't exist.", what),
+            Self::InvalidData(msg) => write!(f, "{}", msg),
+        }
+    }
 }
 
-/// A [`Component`] is an ordered collection of fields and/or other components.
-/// There are two kinds of components: (1) common blocks and (2) repeating
-/// groups. Common blocks are merely commonly reused sequences of the same
-/// fields/components
-/// which are given names for simplicity, i.e. they serve as "macros". Repeating
-/// groups, on the other hand, are components which can appear zero or more times
-/// inside FIX messages (or other components, for that matter).
-#[derive(Clone, Debug)]
-pub struct Component<'a>(&'a Dictionary, &'a ComponentData);
+impl std::error::Error for EditDictionaryError {}
 
-impl<'a> Component<'a> {
-    /// Returns the unique numberic ID of `self`.
-    pub fn id(&self) -> u32 {
-        self.1.id as u32
-    }
+type EditResult<T> = Result<T, EditDictionaryError>;
 
-    /// Returns the name of `self`. The name of every [`Component`] is unique
-    /// across a [`Dictionary`].
-    pub fn name(&self) -> &str {
-        self.1.name.as_str()
+impl DictionaryEditor {
+    /// Sets the version string of the resulting [`Dictionary`].
+    pub fn set_version<S: ToString>(&mut self, version: S) -> &mut Self {
+        self.builder.version = version.to_string();
+        self
     }
 
-    /// Returns `true` if and only if `self` is a "group" component; `false`
-    /// otherwise.
-    pub fn is_group(&self) -> bool {
-        match self.1.component_type {
-            FixmlComponentAttributes::Block { is_repeating, .. } => is_repeating,
-            _ => false,
+    /// Adds a new field without any enumerated values.
+    pub fn add_field<S: ToString>(
+        &mut self,
+        tag: u32,
+        name: S,
+        datatype: FixDatatype,
+    ) -> EditResult<&mut Self> {
+        let name = name.to_string();
+        if tag == 0 || tag > u16::MAX as u32 {
+            return Err(EditDictionaryError::InvalidData(format!(
+                "Invalid tag {}.",
+                tag
+            )));
+        }
+        if self.builder.symbol(KeyRef::FieldByTag(tag)).is_some() {
+            return Err(EditDictionaryError::AlreadyExists(format!(
+                "Field <{}>",
+                tag
+            )));
         }
+        if self.builder.symbol(KeyRef::FieldByName(&name)).is_some() {
+            return Err(EditDictionaryError::AlreadyExists(format!(
+                "Field '{}'",
+                name
+            )));
+        }
+        let data_type_iid = self.datatype_iid(datatype);
+        self.builder.add_field(FieldData {
+            name,
+            tag,
+            data_type_iid,
+            associated_data_tag: None,
+            value_restrictions: None,
+            required: true,
+            abbr_name: None,
+            base_category_abbr_name: None,
+            base_category_id: None,
+            description: None,
+            pedigree: PedigreeData::default(),
+            constraints: FieldConstraints::default(),
+        });
+        Ok(self)
     }
 
-    /// Returns the [`Category`] to which `self` belongs.
-    pub fn category(&self) -> Category {
-        let data = self
-            .0
-            .inner
-            .categories
-            .get(self.1.category_iid as usize)
-            .unwrap();
-        Category(self.0, data)
+    /// Changes the datatype of the field with tag `tag`.
+    pub fn set_field_datatype(&mut self, tag: u32, datatype: FixDatatype) -> EditResult<&mut Self> {
+        let iid = self.field_iid_by_tag(tag)?;
+        self.builder.fields[iid as usize].data_type_iid = self.datatype_iid(datatype);
+        Ok(self)
     }
 
-    /// Returns an [`Iterator`] over all items that are part of `self`.
-    pub fn items(&self) -> impl Iterator<Item = LayoutItem> {
-        self.1
-            .layout_items
-            .iter()
-            .map(move |data| LayoutItem(self.0, data))
+    /// Replaces the [`FieldConstraints`] of the field with tag `tag`. Fails
+    /// if [`FieldConstraints::pattern`] is not a valid regular expression.
+    pub fn set_field_constraints(
+        &mut self,
+        tag: u32,
+        constraints: FieldConstraints,
+    ) -> EditResult<&mut Self> {
+        if let (Some(min), Some(max)) = (constraints.min, constraints.max) {
+            if min > max {
+                return Err(EditDictionaryError::InvalidData(format!(
+                    "Field <{}> has a minimum ({}) above its maximum ({})",
+                    tag, min, max
+                )));
+            }
+        }
+        if let Some(Err(err)) = constraints.pattern_regex() {
+            return Err(EditDictionaryError::InvalidData(format!(
+                "Field <{}> has an invalid pattern: {}",
+                tag, err
+            )));
+        }
+        let iid = self.field_iid_by_tag(tag)?;
+        self.builder.fields[iid as usize].constraints = constraints;
+        Ok(self)
     }
 
-    /// Checks whether `field` appears in the definition of `self` and returns
-    /// `true` if it does, `false` otherwise.
-    pub fn contains_field(&self, field: &Field) -> bool {
-        self.items().any(|layout_item| {
-            if let LayoutItemKind::Field(f) = layout_item.kind() {
-                f.tag() == field.tag()
+    /// Adds an enumerated value to the field with tag `tag`. If `value` is
+    /// already there, its description is replaced.
+    pub fn add_field_enum<S, T>(
+        &mut self,
+        tag: u32,
+        value: S,
+        description: T,
+    ) -> EditResult<&mut Self>
+    where
+        S: ToString,
+        T: ToString,
+    {
+        let iid = self.field_iid_by_tag(tag)?;
+        let (value, description) = (value.to_string(), description.to_string());
+        let enums = self.builder.fields[iid as usize]
+            .value_restrictions
+            .get_or_insert_with(Vec::new);
+        match enums.iter_mut().find(|e| e.value == value) {
+            Some(field_enum) => field_enum.description = description,
+            None => enums.push(FieldEnumData {
+                value,
+                description,
+                symbolic_name: None,
+                pedigree: PedigreeData::default(),
+            }),
+        }
+        Ok(self)
+    }
+
+    /// Adds a new component, which belongs to the category named `category`.
+    /// Components which only contain a repeating group are group components
+    /// (see [`Component::is_group`]).
+    pub fn add_component<S, U>(
+        &mut self,
+        name: S,
+        category: U,
+        items: Vec<LayoutItemSpec>,
+    ) -> EditResult<&mut Self>
+    where
+        S: ToString,
+        U: AsRef<str>,
+    {
+        let name = name.to_string();
+        if self
+            .builder
+            .symbol(KeyRef::ComponentByName(&name))
+            .is_some()
+        {
+            return Err(EditDictionaryError::AlreadyExists(format!(
+                "Component '{}'",
+                name
+            )));
+        }
+        let layout_items = self.layout_items(&items)?;
+        let category_iid = fix_repository::category_iid(&mut self.builder, category.as_ref());
+        self.builder.add_component(ComponentData {
+            id: 0,
+            component_type: block_component_type(&layout_items),
+            layout_items,
+            category_iid,
+            name,
+            abbr_name: None,
+            description: None,
+            pedigree: PedigreeData::default(),
+            scenario: BASE_SCENARIO.to_string(),
+        });
+        Ok(self)
+    }
+
+    /// Adds a new message, which belongs to the category named `category`
+    /// (e.g. `app`).
+    pub fn add_message<S, T, U>(
+        &mut self,
+        name: S,
+        msg_type: T,
+        category: U,
+        items: Vec<LayoutItemSpec>,
+    ) -> EditResult<&mut Self>
+    where
+        S: ToString,
+        T: ToString,
+        U: AsRef<str>,
+    {
+        let (name, msg_type) = (name.to_string(), msg_type.to_string());
+        if self
+            .builder
+            .symbol(KeyRef::MessageByMsgType(&msg_type))
+            .is_some()
+        {
+            return Err(EditDictionaryError::AlreadyExists(format!(
+                "Message '{}'",
+                msg_type
+            )));
+        }
+        if self.builder.symbol(KeyRef::MessageByName(&name)).is_some() {
+            return Err(EditDictionaryError::AlreadyExists(format!(
+                "Message '{}'",
+                name
+            )));
+        }
+        let layout_items = self.layout_items(&items)?;
+        let category_iid = fix_repository::category_iid(&mut self.builder, category.as_ref());
+        self.builder.add_message(MessageData {
+            component_id: 0,
+            msg_type,
+            name,
+            category_iid,
+            section_id: String::new(),
+            layout_items,
+            abbr_name: None,
+            required: true,
+            description: String::new(),
+            elaboration: None,
+            pedigree: PedigreeData::default(),
+            scenario: BASE_SCENARIO.to_string(),
+        });
+        Ok(self)
+    }
+
+    /// Replaces the layout of `target` with `items`.
+    pub fn set_layout(
+        &mut self,
+        target: LayoutTarget,
+        items: Vec<LayoutItemSpec>,
+    ) -> EditResult<&mut Self> {
+        let items = self.layout_items(&items)?;
+        self.for_each_layout(target, |layout| {
+            *layout = items.clone();
+            Ok(())
+        })?;
+        if let LayoutTarget::Component(name) = target {
+            let iid = self.component_iid(name)?;
+            self.builder.components[iid as usize].component_type = block_component_type(&items);
+        }
+        Ok(self)
+    }
+
+    /// Appends `item` to the layout of `target`. If an item with the same name
+    /// is already part of the layout, only its `required` flag is updated.
+    pub fn push_layout_item(
+        &mut self,
+        target: LayoutTarget,
+        item: LayoutItemSpec,
+    ) -> EditResult<&mut Self> {
+        let item = self.layout_item(&item)?;
+        let matcher = self.item_matcher(self.layout_item_name(&item))?;
+        self.for_each_layout(target, |layout| {
+            match layout.iter_mut().find(|existing| matcher.matches(existing)) {
+                Some(existing) => existing.required = item.required,
+                None => layout.push(item.clone()),
+            }
+            Ok(())
+        })?;
+        Ok(self)
+    }
+
+    /// Removes the item named `name` from the layout of `target`.
+    pub fn remove_layout_item(
+        &mut self,
+        target: LayoutTarget,
+        name: &str,
+    ) -> EditResult<&mut Self> {
+        let matcher = self.item_matcher(name)?;
+        self.for_each_layout(target, |layout| {
+            let len = layout.len();
+            layout.retain(|item| !matcher.matches(item));
+            if layout.len() == len {
+                Err(EditDictionaryError::NotFound(format!(
+                    "Layout item '{}'",
+                    name
+                )))
             } else {
-                false
+                Ok(())
+            }
+        })?;
+        Ok(self)
+    }
+
+    /// Adds `rule` to the presence rules of the item named `item` within the
+    /// layout of `target`. Rules are evaluated in order, and the first one
+    /// whose condition holds determines the presence of the item.
+    ///
+    /// ```
+    /// use fefix::dict::{LayoutTarget, Presence, PresenceRuleSpec};
+    /// use fefix::Dictionary;
+    ///
+    /// let mut editor = Dictionary::fix44().edit();
+    /// editor.add_presence_rule(
+    ///     LayoutTarget::Message("D"),
+    ///     "Price",
+    ///     PresenceRuleSpec::new(Presence::Required, "OrdType == ^Limit"),
+    /// )?;
+    /// let dict = editor.build();
+    /// let layout = dict.lookup_tables().message_layout("D").unwrap();
+    /// let price = layout.items().iter().find(|i| i.tag().get() == 44).unwrap();
+    /// assert_eq!(price.presence_rules().len(), 1);
+    /// # Ok::<(), fefix::dict::EditDictionaryError>(())
+    /// ```
+    pub fn add_presence_rule(
+        &mut self,
+        target: LayoutTarget,
+        item: &str,
+        rule: PresenceRuleSpec,
+    ) -> EditResult<&mut Self> {
+        condition::parse(&rule.condition).map_err(EditDictionaryError::InvalidData)?;
+        let matcher = self.item_matcher(item)?;
+        let rule = PresenceRuleData {
+            name: rule.name,
+            presence: rule.presence,
+            condition: rule.condition,
+        };
+        self.for_each_layout(target, |layout| {
+            match layout.iter_mut().find(|existing| matcher.matches(existing)) {
+                Some(existing) => {
+                    existing.presence_rules.push(rule.clone());
+                    Ok(())
+                }
+                None => Err(EditDictionaryError::NotFound(format!(
+                    "Layout item '{}'",
+                    item
+                ))),
+            }
+        })?;
+        Ok(self)
+    }
+
+    /// Merges `other` into `self`. Fields, enumerated values, components and
+    /// messages which are missing from `self` are added, while those which
+    /// exist in both take their definitions from `other`. The version of
+    /// `self` is kept.
+    pub fn merge(&mut self, other: &Dictionary) -> Result<&mut Self, ParseDictionaryError> {
+        let version = self.builder.version.clone();
+        self.apply_overlay(other.to_quickfix_spec())?;
+        self.builder.version = version;
+        Ok(self)
+    }
+
+    /// Copies descriptive metadata from `source` into the entities of `self`
+    /// which `source` defines as well: categories and sections of messages and
+    /// components, descriptions, and pedigrees (i.e. the FIX versions in which
+    /// entities were added, updated or deprecated). Fields are matched by tag,
+    /// messages by `MsgType <35>`, and components by name. Layouts, datatypes
+    /// and enumerated values are left untouched.
+    ///
+    /// QuickFIX specification files lack all of this metadata, but the FIX
+    /// Repository of the same version has it (see
+    /// [`Dictionary::from_fix_repository`]).
+    pub fn import_metadata(&mut self, source: &Dictionary) -> &mut Self {
+        let builder = &mut self.builder;
+        for section in source.iter_sections() {
+            if builder.symbol(KeyRef::SectionById(section.id())).is_none() {
+                builder.add_section(section.1.clone());
+            }
+        }
+        for field in builder.fields.iter_mut() {
+            if let Some(source_field) = source.field_by_tag(field.tag) {
+                if field.description.is_none() {
+                    field.description = source_field.1.description.clone();
+                }
+                if field.pedigree == PedigreeData::default() {
+                    field.pedigree = source_field.1.pedigree.clone();
+                }
             }
+        }
+        for i in 0..builder.components.len() {
+            let name = builder.components[i].name.as_str();
+            if let Some(source_component) = source.component_by_name(name) {
+                let category_iid = import_category(builder, source_component.category());
+                let component = &mut builder.components[i];
+                component.category_iid = category_iid;
+                if component.description.is_none() {
+                    component.description = source_component.1.description.clone();
+                }
+                if component.pedigree == PedigreeData::default() {
+                    component.pedigree = source_component.1.pedigree.clone();
+                }
+            }
+        }
+        for i in 0..builder.messages.len() {
+            let message = &builder.messages[i];
+            let source_message = source.message_by_scenario(&message.msg_type, &message.scenario);
+            if let Some(source_message) = source_message {
+                let category_iid = import_category(builder, source_message.category());
+                let message = &mut builder.messages[i];
+                message.category_iid = category_iid;
+                message.section_id = source_message.1.section_id.clone();
+                if message.description.is_empty() {
+                    message.description = source_message.1.description.clone();
+                }
+                if message.elaboration.is_none() {
+                    message.elaboration = source_message.1.elaboration.clone();
+                }
+                if message.pedigree == PedigreeData::default() {
+                    message.pedigree = source_message.1.pedigree.clone();
+                }
+            }
+        }
+        self
+    }
+
+    /// Applies the changes described by an overlay file.
+    ///
+    /// Overlay files use the same syntax as QuickFIX specification files (see
+    /// [`Dictionary::from_quickfix_spec`]), but all of their sections are
+    /// optional and they only describe differences:
+    ///
+    /// - `<fix>` attributes, if present, change the version.
+    /// - `<fields>` add new fields. Existing fields (by `number`) get their
+    ///   `type` changed (if given) and their `<value>`s added or updated.
+    ///   [`FieldConstraints`] are given by the `min`, `max`, `maxlength` and
+    ///   `pattern` attributes.
+    /// - `<components>` and `<messages>` add new components and messages, or
+    ///   replace the layouts of existing ones (by `name` and `msgtype`
+    ///   respectively). With `extend='Y'`, items are instead appended (or
+    ///   updated) and `<remove name='...' />` removes them. New components
+    ///   belong to the `category` attribute's category (`app` by default).
+    /// - `<header>` and `<trailer>` edit `StandardHeader` and
+    ///   `StandardTrailer`, like components.
+    /// - `<groups>` edit all repeating groups counted by the `<group>`'s
+    ///   `name`, like components.
+    /// - `<rules>` add conditional presence rules (see
+    ///   [`DictionaryEditor::add_presence_rule`]) to items of `<message>`s,
+    ///   `<component>`s, `<group>`s, `<header>` and `<trailer>`. Rules follow
+    ///   the syntax of FIX Orchestra, with an additional `item` attribute:
+    ///   `<rule item='Price' presence='required'><when>OrdType ==
+    ///   ^Limit</when></rule>`.
+    ///
+    /// If an error occurs, `self` might have been partially modified. Use
+    /// [`Dictionary::with_overlay`] to avoid that.
+    ///
+    /// ```
+    /// use fefix::Dictionary;
+    ///
+    /// let overlay = r#"
+    ///     <fix>
+    ///         <fields>
+    ///             <field number='5001' name='VenueOrderTag' type='STRING' />
+    ///             <field number='40'>
+    ///                 <value enum='Z' description='VENUE_PEGGED' />
+    ///             </field>
+    ///         </fields>
+    ///         <messages>
+    ///             <message msgtype='D' extend='Y'>
+    ///                 <field name='VenueOrderTag' required='N' />
+    ///                 <field name='Account' required='Y' />
+    ///             </message>
+    ///         </messages>
+    ///     </fix>
+    /// "#;
+    /// let dict = Dictionary::fix44().with_overlay(overlay).unwrap();
+    /// assert_eq!(dict.field_by_tag(5001).unwrap().name(), "VenueOrderTag");
+    /// ```
+    pub fn apply_overlay<S: AsRef<str>>(
+        &mut self,
+        overlay: S,
+    ) -> Result<&mut Self, ParseDictionaryError> {
+        let xml_document = roxmltree::Document::parse(overlay.as_ref())
+            .map_err(|_| ParseDictionaryError::InvalidFormat)?;
+        overlay::apply(self, &xml_document)?;
+        Ok(self)
+    }
+
+    /// Builds a new [`Dictionary`] with all changes.
+    pub fn build(self) -> Dictionary {
+        self.builder.build()
+    }
+
+    fn datatype_iid(&mut self, datatype: FixDatatype) -> InternalId {
+        fix_repository::datatype_iid(&mut self.builder, datatype, &Default::default())
+    }
+
+    fn field_iid_by_tag(&self, tag: u32) -> EditResult<InternalId> {
+        self.builder
+            .symbol(KeyRef::FieldByTag(tag))
+            .copied()
+            .ok_or_else(|| EditDictionaryError::NotFound(format!("Field <{}>", tag)))
+    }
+
+    fn field_iid_by_name(&self, name: &str) -> EditResult<InternalId> {
+        self.builder
+            .symbol(KeyRef::FieldByName(name))
+            .copied()
+            .ok_or_else(|| EditDictionaryError::NotFound(format!("Field '{}'", name)))
+    }
+
+    fn component_iid(&self, name: &str) -> EditResult<InternalId> {
+        self.builder
+            .symbol(KeyRef::ComponentByName(name))
+            .copied()
+            .ok_or_else(|| EditDictionaryError::NotFound(format!("Component '{}'", name)))
+    }
+
+    fn layout_items(&self, items: &[LayoutItemSpec]) -> EditResult<LayoutItems> {
+        items.iter().map(|item| self.layout_item(item)).collect()
+    }
+
+    fn layout_item(&self, item: &LayoutItemSpec) -> EditResult<LayoutItemData> {
+        let kind = match item {
+            LayoutItemSpec::Field { name, .. } => LayoutItemKindData::Field {
+                iid: self.field_iid_by_name(name)?,
+            },
+            LayoutItemSpec::Component { name, .. } => LayoutItemKindData::Component {
+                iid: self.component_iid(name)?,
+            },
+            LayoutItemSpec::Group { name, items, .. } => LayoutItemKindData::Group {
+                len_field_iid: self.field_iid_by_name(name)?,
+                items: self.layout_items(items)?,
+            },
+        };
+        Ok(LayoutItemData {
+            required: item.required(),
+            kind,
+            presence_rules: Vec::new(),
         })
     }
+
+    fn layout_item_name(&self, item: &LayoutItemData) -> &str {
+        match item.kind {
+            LayoutItemKindData::Component { iid } => &self.builder.components[iid as usize].name,
+            LayoutItemKindData::Group { len_field_iid, .. } => {
+                &self.builder.fields[len_field_iid as usize].name
+            }
+            LayoutItemKindData::Field { iid } => &self.builder.fields[iid as usize].name,
+        }
+    }
+
+    fn item_matcher(&self, name: &str) -> EditResult<ItemMatcher> {
+        let matcher = ItemMatcher {
+            field_iid: self.builder.symbol(KeyRef::FieldByName(name)).copied(),
+            component_iid: self.builder.symbol(KeyRef::ComponentByName(name)).copied(),
+        };
+        if matcher.field_iid.is_none() && matcher.component_iid.is_none() {
+            Err(EditDictionaryError::NotFound(format!(
+                "Layout item '{}'",
+                name
+            )))
+        } else {
+            Ok(matcher)
+        }
+    }
+
+    /// Calls `f` on every layout of `target`.
+    fn for_each_layout<F>(&mut self, target: LayoutTarget, mut f: F) -> EditResult<()>
+    where
+        F: FnMut(&mut LayoutItems) -> EditResult<()>,
+    {
+        match target {
+            LayoutTarget::Message(msg_type) => {
+                let iid = self
+                    .builder
+                    .symbol(KeyRef::MessageByMsgType(msg_type))
+                    .copied()
+                    .ok_or_else(|| {
+                        EditDictionaryError::NotFound(format!("Message '{}'", msg_type))
+                    })?;
+                f(&mut self.builder.messages[iid as usize].layout_items)
+            }
+            LayoutTarget::Component(name) => {
+                let iid = self.component_iid(name)?;
+                f(&mut self.builder.components[iid as usize].layout_items)
+            }
+            LayoutTarget::Group(name) => {
+                let len_field_iid = self.field_iid_by_name(name)?;
+                let mut found = false;
+                let builder = &mut self.builder;
+                let layouts = builder
+                    .components
+                    .iter_mut()
+                    .map(|c| &mut c.layout_items)
+                    .chain(builder.messages.iter_mut().map(|m| &mut m.layout_items));
+                for items in layouts {
+                    for_each_group(items, len_field_iid, &mut found, &mut f)?;
+                }
+                if found {
+                    Ok(())
+                } else {
+                    Err(EditDictionaryError::NotFound(format!("Group '{}'", name)))
+                }
+            }
+        }
+    }
 }
 
-/// Component type (FIXML-specific information).
-#[derive(Clone, Debug, PartialEq, Eq)]
-#[allow(dead_code)]
-pub enum FixmlComponentAttributes {
-    Xml,
-    Block {
-        is_repeating: bool,
-        is_implicit: bool,
-        is_optimized: bool,
-    },
-    Message,
+fn block_component_type(items: &[LayoutItemData]) -> FixmlComponentAttributes {
+    FixmlComponentAttributes::Block {
+        is_repeating: matches!(
+            items,
+            [LayoutItemData {
+                kind: LayoutItemKindData::Group { .. },
+                ..
+            }]
+        ),
+        is_implicit: false,
+        is_optimized: false,
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct DatatypeData {
-    /// **Primary key.** Identifier of the datatype.
-    datatype: FixDatatype,
-    /// Human readable description of this Datatype.
-    description: String,
-    /// A string that contains examples values for a datatype
-    examples: Vec<String>,
-    // TODO: 'XML'.
+fn for_each_group<F>(
+    items: &mut LayoutItems,
+    len_field_iid: InternalId,
+    found: &mut bool,
+    f: &mut F,
+) -> EditResult<()>
+where
+    F: FnMut(&mut LayoutItems) -> EditResult<()>,
+{
+    for item in items.iter_mut() {
+        if let LayoutItemKindData::Group {
+            len_field_iid: iid,
+            items,
+        } = &mut item.kind
+        {
+            if *iid == len_field_iid {
+                *found = true;
+                f(items)?;
+            } else {
+                for_each_group(items, len_field_iid, found, f)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the [`InternalId`] of the category named like `category`, which is
+/// copied into `builder` if missing.
+fn import_category(builder: &mut DictionaryBuilder, category: Category) -> InternalId {
+    match builder.symbol(KeyRef::CategoryByName(category.name())) {
+        Some(iid) => {
+            let iid = *iid;
+            let data = &mut builder.categories[iid as usize];
+            if data.section_id.is_none() {
+                data.section_id = category.1.section_id.clone();
+            }
+            iid
+        }
+        None => builder.add_category(category.1.clone()),
+    }
+}
+
+/// Finds layout items by name, regardless of their kind.
+struct ItemMatcher {
+    field_iid: Option<InternalId>,
+    component_iid: Option<InternalId>,
+}
+
+impl ItemMatcher {
+    fn matches(&self, item: &LayoutItemData) -> bool {
+        match item.kind {
+            LayoutItemKindData::Component { iid } => Some(iid) == self.component_iid,
+            LayoutItemKindData::Group { len_field_iid, .. } => {
+                Some(len_field_iid) == self.field_iid
+            }
+            LayoutItemKindData::Field { iid } => Some(iid) == self.field_iid,
+        }
+    }
 }
 
-/// A FIX data type defined as part of a [`Dictionary`].
 #[derive(Debug)]
-pub struct Datatype<'a>(&'a Dictionary, &'a DatatypeData);
+struct DictionaryBuilder {
+    version: String,
+    symbol_table: FnvHashMap<Key, InternalId>,
+    abbreviations: Vec<AbbreviationData>,
+    data_types: Vec<DatatypeData>,
+    fields: Vec<FieldData>,
+    components: Vec<ComponentData>,
+    messages: Vec<MessageData>,
+    //layout_items: Vec<LayoutItemData>,
+    categories: Vec<CategoryData>,
+    sections: Vec<SectionData>,
+    header: Vec<FieldData>,
+}
 
-impl<'a> Datatype<'a> {
-    /// Returns the name of `self`.  This is also guaranteed to be a valid Rust
-    /// identifier.
-    pub fn name(&self) -> &str {
-        self.1.datatype.name()
+impl DictionaryBuilder {
+    pub fn new(version: String) -> Self {
+        Self {
+            version,
+            symbol_table: FnvHashMap::default(),
+            abbreviations: Vec::new(),
+            data_types: Vec::new(),
+            fields: Vec::new(),
+            components: Vec::new(),
+            messages: Vec::new(),
+            //layout_items: Vec::new(),
+            categories: Vec::new(),
+            sections: Vec::new(),
+            header: Vec::new(),
+        }
     }
 
-    /// Returns `self` as an `enum`.
-    pub fn basetype(&self) -> FixDatatype {
-        self.1.datatype
+    /// Starts from a copy of all of `dict`'s data.
+    pub fn from_dictionary(dict: &Dictionary) -> Self {
+        let data = dict.inner.as_ref().clone();
+        Self {
+            version: data.version,
+            symbol_table: data.symbol_table,
+            abbreviations: data.abbreviations,
+            data_types: data.data_types,
+            fields: data.fields,
+            components: data.components,
+            messages: data.messages,
+            categories: data.categories,
+            sections: data.sections,
+            header: data.header,
+        }
     }
-}
 
-mod datatype {
-    use strum::IntoEnumIterator;
-    use strum_macros::{EnumIter, IntoStaticStr};
+    pub fn symbol(&self, pkey: KeyRef) -> Option<&InternalId> {
+        self.symbol_table.get(&pkey as &dyn SymbolTableIndex)
+    }
 
-    /// Sum type for all possible FIX data types ever defined across all FIX
-    /// application versions.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumIter, IntoStaticStr)]
-    #[repr(u8)]
-    #[non_exhaustive]
-    pub enum FixDatatype {
-        /// Single character value, can include any alphanumeric character or
-        /// punctuation except the delimiter. All char fields are case sensitive
-        /// (i.e. m != M). The following fields are based on char.
-        Char,
-        /// char field containing one of two values: 'Y' = True/Yes 'N' = False/No.
-        Boolean,
-        /// Sequence of digits with optional decimal point and sign character (ASCII
-        /// characters "-", "0" - "9" and "."); the absence of the decimal point
-        /// within the string will be interpreted as the float representation of an
-        /// integer value. All float fields must accommodate up to fifteen
-        /// significant digits. The number of decimal places used should be a factor
+    pub fn add_field(&mut self, field: FieldData) -> InternalId {
+        let iid = self.fields.len() as InternalId;
+        self.symbol_table
+            .insert(Key::FieldByName(field.name.clone()), iid);
+        self.symbol_table
+            .insert(Key::FieldByTag(field.tag as u32), iid);
+        self.fields.push(field);
+        iid
+    }
+
+    /// Lookups by name and message type return the base scenario, or
+    /// whichever scenario was added first if there's none.
+    pub fn add_message(&mut self, message: MessageData) -> InternalId {
+        let iid = self.messages.len() as InternalId;
+        let is_base = message.scenario == BASE_SCENARIO;
+        let keys = [
+            Key::MessageByName(message.name.clone()),
+            Key::MessageByMsgType(message.msg_type.to_string()),
+        ];
+        for key in keys {
+            if is_base || !self.symbol_table.contains_key(&key) {
+                self.symbol_table.insert(key, iid);
+            }
+        }
+        self.messages.push(message);
+        iid
+    }
+
+    pub fn add_component(&mut self, component: ComponentData) -> InternalId {
+        let iid = self.components.len() as InternalId;
+        let key = Key::ComponentByName(component.name.to_string());
+        if component.scenario == BASE_SCENARIO || !self.symbol_table.contains_key(&key) {
+            self.symbol_table.insert(key, iid);
+        }
+        self.components.push(component);
+        iid
+    }
+
+    pub fn add_category(&mut self, category: CategoryData) -> InternalId {
+        let iid = self.categories.len() as InternalId;
+        self.symbol_table
+            .insert(Key::CategoryByName(category.name.clone()), iid);
+        self.categories.push(category);
+        iid
+    }
+
+    pub fn add_section(&mut self, section: SectionData) -> InternalId {
+        let iid = self.sections.len() as InternalId;
+        self.symbol_table
+            .insert(Key::SectionById(section.id.clone()), iid);
+        self.sections.push(section);
+        iid
+    }
+
+    pub fn add_abbreviation(&mut self, abbreviation: AbbreviationData) -> InternalId {
+        let iid = self.abbreviations.len() as InternalId;
+        self.symbol_table
+            .insert(Key::Abbreviation(abbreviation.term.clone()), iid);
+        self.abbreviations.push(abbreviation);
+        iid
+    }
+
+    pub fn build(mut self) -> Dictionary {
+        self.link_data_fields();
+        Dictionary {
+            inner: Arc::new(DictionaryData {
+                version: self.version,
+                symbol_table: self.symbol_table,
+                abbreviations: self.abbreviations,
+                data_types: self.data_types,
+                fields: self.fields,
+                components: self.components,
+                messages: self.messages,
+                //layout_items: self.layout_items,
+                categories: self.categories,
+                sections: self.sections,
+                header: self.header,
+                lookup_tables: OnceCell::new(),
+            }),
+        }
+    }
+
+    /// Data fields are always immediately preceded by their `Length` field
+    /// (e.g. `RawDataLength <95>` and `RawData <96>`), so we can infer all
+    /// `associated_data_tag`s from message and component layouts.
+    fn link_data_fields(&mut self) {
+        let mut links = Vec::new();
+        let layouts = self
+            .components
+            .iter()
+            .map(|c| &c.layout_items)
+            .chain(self.messages.iter().map(|m| &m.layout_items));
+        for items in layouts {
+            self.collect_data_field_links(items, &mut links);
+        }
+        for (len_field_iid, data_tag) in links {
+            self.fields[len_field_iid as usize].associated_data_tag = Some(data_tag as usize);
+        }
+    }
+
+    fn collect_data_field_links(
+        &self,
+        items: &[LayoutItemData],
+        links: &mut Vec<(InternalId, u32)>,
+    ) {
+        let datatype = |iid: InternalId| {
+            let field = &self.fields[iid as usize];
+            self.data_types[field.data_type_iid as usize].datatype
+        };
+        for (item, next_item) in items.iter().zip(items.iter().skip(1)) {
+            if let (
+                LayoutItemKindData::Field { iid },
+                LayoutItemKindData::Field { iid: next_iid },
+            ) = (&item.kind, &next_item.kind)
+            {
+                let is_data = matches!(
+                    datatype(*next_iid),
+                    FixDatatype::Data | FixDatatype::XmlData
+                );
+                if datatype(*iid) == FixDatatype::Length && is_data {
+                    links.push((*iid, self.fields[*next_iid as usize].tag));
+                }
+            }
+        }
+        for item in items {
+            if let LayoutItemKindData::Group { items, .. } = &item.kind {
+                self.collect_data_field_links(items, links);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct PedigreeData {
+    /// The FIX version in which the entity was introduced, e.g. `FIX.4.2`.
+    added: Option<String>,
+    /// The Extension Pack in which the entity was introduced, if any.
+    added_ep: Option<i32>,
+    /// The FIX version in which the entity was last updated.
+    updated: Option<String>,
+    /// The Extension Pack in which the entity was last updated, if any.
+    updated_ep: Option<i32>,
+    /// The FIX version in which the entity was deprecated.
+    deprecated: Option<String>,
+    /// The Extension Pack in which the entity was deprecated, if any.
+    deprecated_ep: Option<i32>,
+}
+
+/// The history of an entity (e.g. a [`Field`] or a [`Message`]) across FIX
+/// versions and Extension Packs. QuickFIX specification files don't carry this
+/// information, so it's only available for built-in dictionaries and for
+/// dictionaries built from the FIX Repository or FIX Orchestra.
+#[derive(Debug, Copy, Clone)]
+pub struct Pedigree<'a>(&'a PedigreeData);
+
+impl<'a> Pedigree<'a> {
+    /// Returns the FIX version in which the entity was introduced, if known.
+    pub fn added(&self) -> Option<&'a str> {
+        self.0.added.as_deref()
+    }
+
+    /// Returns the Extension Pack in which the entity was introduced, if any.
+    pub fn added_ep(&self) -> Option<i32> {
+        self.0.added_ep
+    }
+
+    /// Returns the FIX version in which the entity was last updated, if any.
+    pub fn updated(&self) -> Option<&'a str> {
+        self.0.updated.as_deref()
+    }
+
+    /// Returns the Extension Pack in which the entity was last updated, if any.
+    pub fn updated_ep(&self) -> Option<i32> {
+        self.0.updated_ep
+    }
+
+    /// Returns the FIX version in which the entity was deprecated, if any.
+    pub fn deprecated(&self) -> Option<&'a str> {
+        self.0.deprecated.as_deref()
+    }
+
+    /// Returns the Extension Pack in which the entity was deprecated, if any.
+    pub fn deprecated_ep(&self) -> Option<i32> {
+        self.0.deprecated_ep
+    }
+
+    /// Returns `true` if the entity has been deprecated; `false` otherwise.
+    pub fn is_deprecated(&self) -> bool {
+        self.0.deprecated.is_some() || self.0.deprecated_ep.is_some()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AbbreviationData {
+    /// **Primary key.** The full term, e.g. `Account`.
+    term: String,
+    /// The abbreviated form of `term`, e.g. `Acct`.
+    abbreviation: String,
+    is_last: bool,
+    pedigree: PedigreeData,
+}
+
+/// An [`Abbreviation`] is a standardized abbreviated form for a specific word,
+/// pattern, or name. Abbreviation data is mostly meant for documentation
+/// purposes, but in general it can have other uses as well, e.g. FIXML field
+/// naming.
+#[derive(Debug)]
+pub struct Abbreviation<'a>(&'a Dictionary, &'a AbbreviationData);
+
+impl<'a> Abbreviation<'a> {
+    /// Returns the full term (non-abbreviated) associated with `self`.
+    pub fn term(&self) -> &str {
+        self.1.term.as_str()
+    }
+
+    /// Returns the abbreviated form of [`Abbreviation::term`].
+    pub fn abbreviation(&self) -> &str {
+        self.1.abbreviation.as_str()
+    }
+
+    /// Returns the [`Pedigree`] of `self`.
+    pub fn pedigree(&self) -> Pedigree<'a> {
+        Pedigree(&self.1.pedigree)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct CategoryData {
+    /// **Primary key**. A string uniquely identifying this category.
+    name: String,
+    /// The FIXML file name for a Category.
+    fixml_filename: String,
+    /// Identifier of the section to which this category belongs, if any.
+    section_id: Option<String>,
+}
+
+/// A [`Category`] is a collection of loosely related FIX messages or components
+/// all belonging to the same [`Section`].
+#[derive(Clone, Debug)]
+pub struct Category<'a>(&'a Dictionary, &'a CategoryData);
+
+impl<'a> Category<'a> {
+    /// Returns the name of `self`, e.g. `MarketData`. The name of every
+    /// [`Category`] is unique across a [`Dictionary`].
+    pub fn name(&self) -> &str {
+        self.1.name.as_str()
+    }
+
+    /// Returns the FIXML file name of `self`.
+    pub fn fixml_filename(&self) -> &str {
+        self.1.fixml_filename.as_str()
+    }
+
+    /// Returns the [`Section`] to which `self` belongs, if known.
+    pub fn section(&self) -> Option<Section<'a>> {
+        self.1
+            .section_id
+            .as_ref()
+            .and_then(|id| self.0.section_by_id(id))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ComponentData {
+    /// **Primary key.** The unique integer identifier of this component
+    /// type.
+    id: usize,
+    component_type: FixmlComponentAttributes,
+    layout_items: Vec<LayoutItemData>,
+    category_iid: InternalId,
+    /// The human readable name of the component.
+    name: String,
+    /// The name for this component when used in an XML context.
+    abbr_name: Option<String>,
+    description: Option<String>,
+    pedigree: PedigreeData,
+    scenario: String,
+}
+
+/// A [`Component`] is an ordered collection of fields and/or other components.
+/// There are two kinds of components: (1) common blocks and (2) repeating
+/// groups. Common blocks are merely commonly reused sequences of the same
+/// fields/components
+/// which are given names for simplicity, i.e. they serve as "macros". Repeating
+/// groups, on the other hand, are components which can appear zero or more times
+/// inside FIX messages (or other components, for that matter).
+#[derive(Clone, Debug)]
+pub struct Component<'a>(&'a Dictionary, &'a ComponentData);
+
+impl<'a> Component<'a> {
+    /// Returns the unique numberic ID of `self`.
+    pub fn id(&self) -> u32 {
+        self.1.id as u32
+    }
+
+    /// Returns the name of `self`. The name of every [`Component`] is unique
+    /// across a [`Dictionary`].
+    pub fn name(&self) -> &str {
+        self.1.name.as_str()
+    }
+
+    /// Returns `true` if and only if `self` is a "group" component; `false`
+    /// otherwise.
+    pub fn is_group(&self) -> bool {
+        match self.1.component_type {
+            FixmlComponentAttributes::Block { is_repeating, .. } => is_repeating,
+            _ => false,
+        }
+    }
+
+    /// Returns the description associated with `self`, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.1.description.as_deref()
+    }
+
+    /// Returns the [`Pedigree`] of `self`.
+    pub fn pedigree(&self) -> Pedigree<'a> {
+        Pedigree(&self.1.pedigree)
+    }
+
+    /// Returns the scenario of `self`, usually [`BASE_SCENARIO`].
+    pub fn scenario(&self) -> &str {
+        self.1.scenario.as_str()
+    }
+
+    /// Returns the [`Category`] to which `self` belongs.
+    pub fn category(&self) -> Category {
+        let data = self
+            .0
+            .inner
+            .categories
+            .get(self.1.category_iid as usize)
+            .unwrap();
+        Category(self.0, data)
+    }
+
+    /// Returns an [`Iterator`] over all items that are part of `self`.
+    pub fn items(&self) -> impl Iterator<Item = LayoutItem> {
+        self.1
+            .layout_items
+            .iter()
+            .map(move |data| LayoutItem(self.0, data))
+    }
+
+    /// Checks whether `field` appears in the definition of `self` and returns
+    /// `true` if it does, `false` otherwise.
+    pub fn contains_field(&self, field: &Field) -> bool {
+        self.items().any(|layout_item| {
+            if let LayoutItemKind::Field(f) = layout_item.kind() {
+                f.tag() == field.tag()
+            } else {
+                false
+            }
+        })
+    }
+}
+
+/// Component type (FIXML-specific information).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum FixmlComponentAttributes {
+    Xml,
+    Block {
+        is_repeating: bool,
+        is_implicit: bool,
+        is_optimized: bool,
+    },
+    Message,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DatatypeData {
+    /// **Primary key.** Identifier of the datatype.
+    datatype: FixDatatype,
+    /// Human readable description of this Datatype.
+    description: String,
+    /// A string that contains examples values for a datatype
+    examples: Vec<String>,
+    // TODO: 'XML'.
+}
+
+/// A FIX data type defined as part of a [`Dictionary`].
+#[derive(Debug)]
+pub struct Datatype<'a>(&'a Dictionary, &'a DatatypeData);
+
+impl<'a> Datatype<'a> {
+    /// Returns the name of `self`.  This is also guaranteed to be a valid Rust
+    /// identifier.
+    pub fn name(&self) -> &str {
+        self.1.datatype.name()
+    }
+
+    /// Returns `self` as an `enum`.
+    pub fn basetype(&self) -> FixDatatype {
+        self.1.datatype
+    }
+}
+
+mod datatype {
+    use strum::IntoEnumIterator;
+    use strum_macros::{EnumIter, IntoStaticStr};
+
+    /// Sum type for all possible FIX data types ever defined across all FIX
+    /// application versions.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumIter, IntoStaticStr)]
+    #[repr(u8)]
+    #[non_exhaustive]
+    pub enum FixDatatype {
+        /// Single character value, can include any alphanumeric character or
+        /// punctuation except the delimiter. All char fields are case sensitive
+        /// (i.e. m != M). The following fields are based on char.
+        Char,
+        /// char field containing one of two values: 'Y' = True/Yes 'N' = False/No.
+        Boolean,
+        /// Sequence of digits with optional decimal point and sign character (ASCII
+        /// characters "-", "0" - "9" and "."); the absence of the decimal point
+        /// within the string will be interpreted as the float representation of an
+        /// integer value. All float fields must accommodate up to fifteen
+        /// significant digits. The number of decimal places used should be a factor
         /// of business/market needs and mutual agreement between counterparties.
         /// Note that float values may contain leading zeros (e.g. "00023.23" =
         /// "23.23") and may contain or omit trailing zeros after the decimal point
@@ -833,987 +2007,4435 @@ mod datatype {
         Country,
     }
 
-    impl FixDatatype {
-        /// Compares `name` to the set of strings commonly used by QuickFIX's custom
-        /// specification format and returns its associated
-        /// [`Datatype`](super::Datatype) if a match
-        /// was found. The query is case-insensitive.
-        ///
-        /// # Examples
-        ///
-        /// ```
-        /// use fefix::dict::FixDatatype;
-        ///
-        /// assert_eq!(FixDatatype::from_quickfix_name("AMT"), Some(FixDatatype::Amt));
-        /// assert_eq!(FixDatatype::from_quickfix_name("Amt"), Some(FixDatatype::Amt));
-        /// assert_eq!(FixDatatype::from_quickfix_name("MONTHYEAR"), Some(FixDatatype::MonthYear));
-        /// assert_eq!(FixDatatype::from_quickfix_name(""), None);
-        /// ```
-        pub fn from_quickfix_name(name: &str) -> Option<Self> {
-            // https://github.com/quickfix/quickfix/blob/b6760f55ac6a46306b4e081bb13b65e6220ab02d/src/C%2B%2B/DataDictionary.cpp#L646-L680
-            Some(match name.to_ascii_uppercase().as_str() {
-                "AMT" => FixDatatype::Amt,
-                "BOOLEAN" => FixDatatype::Boolean,
-                "CHAR" => FixDatatype::Char,
-                "COUNTRY" => FixDatatype::Country,
-                "CURRENCY" => FixDatatype::Currency,
-                "DATA" => FixDatatype::Data,
-                "DATE" => FixDatatype::UtcDateOnly, // FIXME?
-                "DAYOFMONTH" => FixDatatype::DayOfMonth,
-                "EXCHANGE" => FixDatatype::Exchange,
-                "FLOAT" => FixDatatype::Float,
-                "INT" => FixDatatype::Int,
-                "LANGUAGE" => FixDatatype::Language,
-                "LENGTH" => FixDatatype::Length,
-                "LOCALMKTDATE" => FixDatatype::LocalMktDate,
-                "MONTHYEAR" => FixDatatype::MonthYear,
-                "MULTIPLECHARVALUE" | "MULTIPLEVALUESTRING" => FixDatatype::MultipleCharValue,
-                "MULTIPLESTRINGVALUE" => FixDatatype::MultipleStringValue,
-                "NUMINGROUP" => FixDatatype::NumInGroup,
-                "PERCENTAGE" => FixDatatype::Percentage,
-                "PRICE" => FixDatatype::Price,
-                "PRICEOFFSET" => FixDatatype::PriceOffset,
-                "QTY" => FixDatatype::Qty,
-                "STRING" => FixDatatype::String,
-                "TZTIMEONLY" => FixDatatype::UtcTimeOnly, // FIXME
-                "TZTIMESTAMP" => FixDatatype::UtcTimestamp, // FIXME
-                "UTCDATE" => FixDatatype::UtcDateOnly,
-                "UTCDATEONLY" => FixDatatype::UtcDateOnly,
-                "UTCTIMEONLY" => FixDatatype::UtcTimeOnly,
-                "UTCTIMESTAMP" => FixDatatype::UtcTimestamp,
-                "SEQNUM" => FixDatatype::SeqNum,
-                "TIME" => FixDatatype::UtcTimestamp,
-                "XMLDATA" => FixDatatype::XmlData,
-                _ => {
-                    return None;
-                }
+    impl FixDatatype {
+        /// Compares `name` to the set of strings commonly used by QuickFIX's custom
+        /// specification format and returns its associated
+        /// [`Datatype`](super::Datatype) if a match
+        /// was found. The query is case-insensitive.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use fefix::dict::FixDatatype;
+        ///
+        /// assert_eq!(FixDatatype::from_quickfix_name("AMT"), Some(FixDatatype::Amt));
+        /// assert_eq!(FixDatatype::from_quickfix_name("Amt"), Some(FixDatatype::Amt));
+        /// assert_eq!(FixDatatype::from_quickfix_name("MONTHYEAR"), Some(FixDatatype::MonthYear));
+        /// assert_eq!(FixDatatype::from_quickfix_name(""), None);
+        /// ```
+        pub fn from_quickfix_name(name: &str) -> Option<Self> {
+            // https://github.com/quickfix/quickfix/blob/b6760f55ac6a46306b4e081bb13b65e6220ab02d/src/C%2B%2B/DataDictionary.cpp#L646-L680
+            Some(match name.to_ascii_uppercase().as_str() {
+                "AMT" => FixDatatype::Amt,
+                "BOOLEAN" => FixDatatype::Boolean,
+                "CHAR" => FixDatatype::Char,
+                "COUNTRY" => FixDatatype::Country,
+                "CURRENCY" => FixDatatype::Currency,
+                "DATA" => FixDatatype::Data,
+                "DATE" => FixDatatype::UtcDateOnly, // FIXME?
+                "DAYOFMONTH" => FixDatatype::DayOfMonth,
+                "EXCHANGE" => FixDatatype::Exchange,
+                "FLOAT" => FixDatatype::Float,
+                "INT" => FixDatatype::Int,
+                "LANGUAGE" => FixDatatype::Language,
+                "LENGTH" => FixDatatype::Length,
+                "LOCALMKTDATE" => FixDatatype::LocalMktDate,
+                "MONTHYEAR" => FixDatatype::MonthYear,
+                "MULTIPLECHARVALUE" | "MULTIPLEVALUESTRING" => FixDatatype::MultipleCharValue,
+                "MULTIPLESTRINGVALUE" => FixDatatype::MultipleStringValue,
+                "NUMINGROUP" => FixDatatype::NumInGroup,
+                "PERCENTAGE" => FixDatatype::Percentage,
+                "PRICE" => FixDatatype::Price,
+                "PRICEOFFSET" => FixDatatype::PriceOffset,
+                "QTY" => FixDatatype::Qty,
+                "STRING" => FixDatatype::String,
+                "TAGNUM" => FixDatatype::TagNum,
+                "TZTIMEONLY" => FixDatatype::UtcTimeOnly, // FIXME
+                "TZTIMESTAMP" => FixDatatype::UtcTimestamp, // FIXME
+                "UTCDATE" => FixDatatype::UtcDateOnly,
+                "UTCDATEONLY" => FixDatatype::UtcDateOnly,
+                "UTCTIMEONLY" => FixDatatype::UtcTimeOnly,
+                "UTCTIMESTAMP" => FixDatatype::UtcTimestamp,
+                "SEQNUM" => FixDatatype::SeqNum,
+                "TIME" => FixDatatype::UtcTimestamp,
+                "XMLDATA" => FixDatatype::XmlData,
+                _ => {
+                    return None;
+                }
+            })
+        }
+
+        /// Returns the name adopted by QuickFIX for `self`.
+        pub fn to_quickfix_name(&self) -> &str {
+            match self {
+                FixDatatype::Int => "INT",
+                FixDatatype::Length => "LENGTH",
+                FixDatatype::Char => "CHAR",
+                FixDatatype::Boolean => "BOOLEAN",
+                FixDatatype::Float => "FLOAT",
+                FixDatatype::Amt => "AMT",
+                FixDatatype::Price => "PRICE",
+                FixDatatype::PriceOffset => "PRICEOFFSET",
+                FixDatatype::Qty => "QTY",
+                FixDatatype::Percentage => "PERCENTAGE",
+                FixDatatype::DayOfMonth => "DAYOFMONTH",
+                FixDatatype::NumInGroup => "NUMINGROUP",
+                FixDatatype::Language => "LANGUAGE",
+                FixDatatype::SeqNum => "SEQNUM",
+                FixDatatype::TagNum => "TAGNUM",
+                FixDatatype::String => "STRING",
+                FixDatatype::Data => "DATA",
+                FixDatatype::MonthYear => "MONTHYEAR",
+                FixDatatype::Currency => "CURRENCY",
+                FixDatatype::Exchange => "EXCHANGE",
+                FixDatatype::LocalMktDate => "LOCALMKTDATE",
+                FixDatatype::MultipleStringValue => "MULTIPLESTRINGVALUE",
+                FixDatatype::UtcTimeOnly => "UTCTIMEONLY",
+                FixDatatype::UtcTimestamp => "UTCTIMESTAMP",
+                FixDatatype::UtcDateOnly => "UTCDATEONLY",
+                FixDatatype::Country => "COUNTRY",
+                FixDatatype::MultipleCharValue => "MULTIPLECHARVALUE",
+                FixDatatype::XmlData => "XMLDATA",
+            }
+        }
+
+        /// Returns the name of `self`, character by character identical to the name
+        /// that appears in the official guidelines. **Generally** primitive datatypes
+        /// will use `snake_case` and non-primitive ones will have `PascalCase`, but
+        /// that's not true for every [`Datatype`](super::Datatype).
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use fefix::dict::FixDatatype;
+        ///
+        /// assert_eq!(FixDatatype::Qty.name(), "Qty");
+        /// assert_eq!(FixDatatype::Float.name(), "float");
+        /// assert_eq!(FixDatatype::String.name(), "String");
+        /// ```
+        pub fn name(&self) -> &'static str {
+            // 1. Most primitive data types have `snake_case` names.
+            // 2. Most derivative data types have `PascalCase` names.
+            // 3. `data` and `String` ruin the party and mess it up.
+            //    Why, you ask? Oh, you sweet summer child. You'll learn soon enough
+            //    that nothing makes sense in FIX land.
+            match self {
+                FixDatatype::Int => "int",
+                FixDatatype::Length => "Length",
+                FixDatatype::Char => "char",
+                FixDatatype::Boolean => "Boolean",
+                FixDatatype::Float => "float",
+                FixDatatype::Amt => "Amt",
+                FixDatatype::Price => "Price",
+                FixDatatype::PriceOffset => "PriceOffset",
+                FixDatatype::Qty => "Qty",
+                FixDatatype::Percentage => "Percentage",
+                FixDatatype::DayOfMonth => "DayOfMonth",
+                FixDatatype::NumInGroup => "NumInGroup",
+                FixDatatype::Language => "Language",
+                FixDatatype::SeqNum => "SeqNum",
+                FixDatatype::TagNum => "TagNum",
+                FixDatatype::String => "String",
+                FixDatatype::Data => "data",
+                FixDatatype::MonthYear => "MonthYear",
+                FixDatatype::Currency => "Currency",
+                FixDatatype::Exchange => "Exchange",
+                FixDatatype::LocalMktDate => "LocalMktDate",
+                FixDatatype::MultipleStringValue => "MultipleStringValue",
+                FixDatatype::UtcTimeOnly => "UTCTimeOnly",
+                FixDatatype::UtcTimestamp => "UTCTimestamp",
+                FixDatatype::UtcDateOnly => "UTCDateOnly",
+                FixDatatype::Country => "Country",
+                FixDatatype::MultipleCharValue => "MultipleCharValue",
+                FixDatatype::XmlData => "XMLData",
+            }
+        }
+
+        /// Returns `true` if and only if `self` is a "base type", i.e. a primitive;
+        /// returns `false` otherwise.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use fefix::dict::FixDatatype;
+        ///
+        /// assert_eq!(FixDatatype::Float.is_base_type(), true);
+        /// assert_eq!(FixDatatype::Price.is_base_type(), false);
+        /// ```
+        pub fn is_base_type(&self) -> bool {
+            matches!(self, Self::Char | Self::Float | Self::Int | Self::String)
+        }
+
+        /// Returns the primitive [`Datatype`](super::Datatype) from which `self` is derived. If
+        /// `self` is primitive already, returns `self` unchanged.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use fefix::dict::FixDatatype;
+        ///
+        /// assert_eq!(FixDatatype::Float.base_type(), FixDatatype::Float);
+        /// assert_eq!(FixDatatype::Price.base_type(), FixDatatype::Float);
+        /// ```
+        pub fn base_type(&self) -> Self {
+            let dt = match self {
+                Self::Char | Self::Boolean => Self::Char,
+                Self::Float
+                | Self::Amt
+                | Self::Price
+                | Self::PriceOffset
+                | Self::Qty
+                | Self::Percentage => Self::Float,
+                Self::Int
+                | Self::DayOfMonth
+                | Self::Length
+                | Self::NumInGroup
+                | Self::SeqNum
+                | Self::TagNum => Self::Int,
+                _ => Self::String,
+            };
+            debug_assert!(dt.is_base_type());
+            dt
+        }
+
+        /// Returns an [`Iterator`] over all variants of
+        /// [`Datatype`](super::Datatype).
+        pub fn iter_all() -> impl Iterator<Item = Self> {
+            <Self as IntoEnumIterator>::iter()
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn iter_all_unique() {
+            let as_vec = FixDatatype::iter_all();
+            let as_set = FixDatatype::iter_all();
+            assert_eq!(as_vec.count(), as_set.count());
+        }
+
+        #[test]
+        fn more_than_20_datatypes() {
+            // According to the official documentation, FIX has "about 20 data
+            // types". Including recent revisions, we should well exceed that
+            // number.
+            assert!(FixDatatype::iter_all().count() > 20);
+        }
+
+        #[test]
+        fn names_are_unique() {
+            let as_vec = FixDatatype::iter_all().map(|dt| dt.name());
+            let as_set = FixDatatype::iter_all().map(|dt| dt.name());
+            assert_eq!(as_vec.count(), as_set.count());
+        }
+
+        #[test]
+        fn base_type_is_itself() {
+            for dt in FixDatatype::iter_all() {
+                if dt.is_base_type() {
+                    assert_eq!(dt.base_type(), dt);
+                } else {
+                    assert_ne!(dt.base_type(), dt);
+                }
+            }
+        }
+
+        #[test]
+        fn base_type_is_actually_base_type() {
+            for dt in FixDatatype::iter_all() {
+                assert!(dt.base_type().is_base_type());
+            }
+        }
+    }
+}
+
+/// A field is identified by a unique tag number and a name. Each field in a
+/// message is associated with a value.
+#[derive(Clone, Debug)]
+struct FieldData {
+    /// A human readable string representing the name of the field.
+    name: String,
+    /// **Primary key.** A positive integer representing the unique
+    /// identifier for this field type.
+    tag: u32,
+    /// The datatype of the field.
+    data_type_iid: InternalId,
+    /// The associated data field. If given, this field represents the length of
+    /// the referenced data field
+    associated_data_tag: Option<usize>,
+    value_restrictions: Option<Vec<FieldEnumData>>,
+    /// Abbreviated form of the Name, typically to specify the element name when
+    /// the field is used in an XML message. Can be overridden by BaseCategory /
+    /// BaseCategoryAbbrName.
+    abbr_name: Option<String>,
+    /// Specifies the base message category when field is used in an XML message.
+    base_category_id: Option<usize>,
+    /// If BaseCategory is specified, this is the XML element identifier to use
+    /// for this field, overriding AbbrName.
+    base_category_abbr_name: Option<String>,
+    /// Indicates whether the field is required in an XML message.
+    required: bool,
+    description: Option<String>,
+    pedigree: PedigreeData,
+    constraints: FieldConstraints,
+}
+
+/// Constraints on the values of a [`Field`], on top of those of its datatype
+/// and enumerated values (see [`Field::enums`]). They come from Orchestra
+/// specifications (`minInclusive` and `maxInclusive`) and overlay files (see
+/// [`DictionaryEditor::apply_overlay`]).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FieldConstraints {
+    /// The minimum value of numeric fields, inclusive.
+    pub min: Option<f64>,
+    /// The maximum value of numeric fields, inclusive.
+    pub max: Option<f64>,
+    /// The maximum length of values, in bytes.
+    pub max_length: Option<usize>,
+    /// A regular expression which whole values must match.
+    pub pattern: Option<String>,
+}
+
+impl FieldConstraints {
+    /// Returns `true` if `self` doesn't constrain values at all.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Compiles [`FieldConstraints::pattern`], anchored so that it only
+    /// matches whole values.
+    pub(crate) fn pattern_regex(&self) -> Option<Result<regex::Regex, regex::Error>> {
+        self.pattern
+            .as_ref()
+            .map(|pattern| regex::Regex::new(&format!("^(?:{})$", pattern)))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FieldEnumData {
+    value: String,
+    description: String,
+    /// A name for `value` which is also a valid identifier, e.g. `Buy` for
+    /// `Side <54>` = `1`.
+    symbolic_name: Option<String>,
+    pedigree: PedigreeData,
+}
+
+/// A limitation imposed on the value of a specific FIX [`Field`].  Also known as
+/// "code set".
+#[derive(Debug)]
+pub struct FieldEnum<'a>(&'a Dictionary, &'a FieldEnumData);
+
+impl<'a> FieldEnum<'a> {
+    /// Returns the string representation of this field variant.
+    pub fn value(&self) -> &str {
+        &self.1.value[..]
+    }
+
+    /// Returns the documentation description for `self`.
+    pub fn description(&self) -> &str {
+        &self.1.description[..]
+    }
+
+    /// Returns the symbolic name of `self`, if known.
+    pub fn symbolic_name(&self) -> Option<&str> {
+        self.1.symbolic_name.as_deref()
+    }
+
+    /// Returns the [`Pedigree`] of `self`.
+    pub fn pedigree(&self) -> Pedigree<'a> {
+        Pedigree(&self.1.pedigree)
+    }
+}
+
+/// A field is the most granular message structure abstraction. It carries a
+/// specific business meaning as described by the FIX specifications. The data
+/// domain of a [`Field`] is either a [`Datatype`] or a "code set", i.e.
+/// enumeration.
+#[derive(Debug, Copy, Clone)]
+pub struct Field<'a>(&'a Dictionary, &'a FieldData);
+
+impl<'a> Field<'a> {
+    pub fn doc_url_onixs(&self, version: &str) -> String {
+        let v = match version {
+            "FIX.4.0" => "4.0",
+            "FIX.4.1" => "4.1",
+            "FIX.4.2" => "4.2",
+            "FIX.4.3" => "4.3",
+            "FIX.4.4" => "4.4",
+            "FIX.5.0" => "5.0",
+            "FIX.5.0SP1" => "5.0.SP1",
+            "FIX.5.0SP2" => "5.0.SP2",
+            "FIXT.1.1" => "FIXT.1.1",
+            s => s,
+        };
+        format!(
+            "https://www.onixs.biz/fix-dictionary/{}/tagNum_{}.html",
+            v,
+            self.1.tag.to_string().as_str()
+        )
+    }
+
+    /// Returns the [`FixDatatype`] of `self`.
+    pub fn fix_datatype(&self) -> FixDatatype {
+        self.data_type().basetype()
+    }
+
+    /// Returns the name of `self`. Field names are unique across each FIX
+    /// [`Dictionary`].
+    pub fn name(&self) -> &str {
+        self.1.name.as_str()
+    }
+
+    /// Returns the numeric tag of `self`. Field tags are unique across each FIX
+    /// [`Dictionary`].
+    pub fn tag(&self) -> TagU16 {
+        TagU16::new(self.1.tag as u16).unwrap()
+    }
+
+    /// Returns the documentation description of `self`, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.1.description.as_deref()
+    }
+
+    /// Returns the [`Pedigree`] of `self`.
+    pub fn pedigree(&self) -> Pedigree<'a> {
+        Pedigree(&self.1.pedigree)
+    }
+
+    /// In case this field allows any value, it returns `None`; otherwise; it
+    /// returns an [`Iterator`] of all allowed values.
+    pub fn enums(&self) -> Option<impl Iterator<Item = FieldEnum>> {
+        self.1
+            .value_restrictions
+            .as_ref()
+            .map(move |v| v.iter().map(move |f| FieldEnum(self.0, f)))
+    }
+
+    /// Returns the data field whose length is given by `self`, if any. E.g.
+    /// `RawData <96>` for `RawDataLength <95>`.
+    pub fn associated_data_field(&self) -> Option<Field<'a>> {
+        self.1
+            .associated_data_tag
+            .and_then(|tag| self.0.field_by_tag(tag as u32))
+    }
+
+    /// Returns the [`FieldConstraints`] of `self`.
+    pub fn constraints(&self) -> &'a FieldConstraints {
+        &self.1.constraints
+    }
+
+    /// Returns the [`Datatype`] of `self`.
+    pub fn data_type(&self) -> Datatype {
+        let data = self
+            .0
+            .inner
+            .data_types
+            .get(self.1.data_type_iid as usize)
+            .unwrap();
+        Datatype(self.0, data)
+    }
+}
+
+impl<'a> IsFieldDefinition for Field<'a> {
+    fn name(&self) -> &str {
+        self.1.name.as_str()
+    }
+
+    fn tag(&self) -> TagU16 {
+        TagU16::new(self.1.tag as u16).expect("Invalid FIX tag (0)")
+    }
+
+    fn location(&self) -> FieldLocation {
+        let tables = self.0.lookup_tables();
+        let tag = self.1.tag;
+        if tables.header_tags().contains(tag) {
+            FieldLocation::Header
+        } else if tables.trailer_tags().contains(tag) {
+            FieldLocation::Trailer
+        } else {
+            FieldLocation::Body
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[allow(dead_code)]
+enum LayoutItemKindData {
+    Component {
+        iid: InternalId,
+    },
+    Group {
+        len_field_iid: u32,
+        items: Vec<LayoutItemData>,
+    },
+    Field {
+        iid: InternalId,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct LayoutItemData {
+    required: bool,
+    kind: LayoutItemKindData,
+    /// Conditional presence rules, which override `required` when their
+    /// condition holds.
+    presence_rules: Vec<PresenceRuleData>,
+}
+
+/// The presence of a [`LayoutItem`] within its parent container.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Presence {
+    /// The item may or may not be present.
+    Optional,
+    /// The item must be present.
+    Required,
+    /// The item must not be present.
+    Forbidden,
+    /// The item may be present, but its value is ignored by the receiver.
+    Ignored,
+}
+
+impl Presence {
+    fn from_orchestra_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "optional" => Presence::Optional,
+            // Constant values must be present too.
+            "required" | "constant" => Presence::Required,
+            "forbidden" => Presence::Forbidden,
+            "ignored" => Presence::Ignored,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PresenceRuleData {
+    name: Option<String>,
+    presence: Presence,
+    /// The condition, as a Score DSL expression (e.g. `OrdType == ^Limit`).
+    condition: String,
+}
+
+/// A rule which changes the [`Presence`] of a [`LayoutItem`] when a certain
+/// condition holds, e.g. `Price <44>` is required if `OrdType <40>` is
+/// `Limit`.
+#[derive(Debug, Copy, Clone)]
+pub struct PresenceRule<'a>(&'a PresenceRuleData);
+
+impl<'a> PresenceRule<'a> {
+    /// Returns the name of `self`, if any.
+    pub fn name(&self) -> Option<&'a str> {
+        self.0.name.as_deref()
+    }
+
+    /// Returns the [`Presence`] of the item when [`PresenceRule::condition`]
+    /// holds.
+    pub fn presence(&self) -> Presence {
+        self.0.presence
+    }
+
+    /// Returns the condition of `self`, as a Score DSL expression.
+    pub fn condition(&self) -> &'a str {
+        self.0.condition.as_str()
+    }
+}
+
+pub trait IsFieldDefinition {
+    /// Returns the FIX tag associated with `self`.
+    fn tag(&self) -> TagU16;
+
+    /// Returns the official, ASCII, human-readable name associated with `self`.
+    fn name(&self) -> &str;
+
+    /// Returns the field location of `self`.
+    fn location(&self) -> FieldLocation;
+}
+
+fn layout_item_kind<'a>(item: &'a LayoutItemKindData, dict: &'a Dictionary) -> LayoutItemKind<'a> {
+    match item {
+        LayoutItemKindData::Component { iid } => LayoutItemKind::Component(Component(
+            dict,
+            dict.inner.components.get(*iid as usize).unwrap(),
+        )),
+        LayoutItemKindData::Group {
+            len_field_iid,
+            items: items_data,
+        } => {
+            let items = items_data
+                .iter()
+                .map(|item_data| LayoutItem(dict, item_data))
+                .collect::<Vec<_>>();
+            let len_field_data = &dict.inner.fields[*len_field_iid as usize];
+            let len_field = Field(dict, len_field_data);
+            LayoutItemKind::Group(len_field, items)
+        }
+        LayoutItemKindData::Field { iid } => {
+            LayoutItemKind::Field(Field(dict, dict.inner.fields.get(*iid as usize).unwrap()))
+        }
+    }
+}
+
+/// An entry in a sequence of FIX field definitions.
+#[derive(Clone, Debug)]
+pub struct LayoutItem<'a>(&'a Dictionary, &'a LayoutItemData);
+
+/// The kind of element contained in a [`Message`].
+#[derive(Debug)]
+pub enum LayoutItemKind<'a> {
+    /// This component item is another component.
+    Component(Component<'a>),
+    /// This component item is a FIX repeating group.
+    Group(Field<'a>, Vec<LayoutItem<'a>>),
+    /// This component item is a FIX field.
+    Field(Field<'a>),
+}
+
+impl<'a> LayoutItem<'a> {
+    /// Returns `true` if `self` is required in order to have a valid definition
+    /// of its parent container, `false` otherwise.
+    pub fn required(&self) -> bool {
+        self.1.required
+    }
+
+    /// Returns an [`Iterator`] over the conditional presence rules of `self`.
+    /// These come from Orchestra specifications and overlay files (see
+    /// [`DictionaryEditor::apply_overlay`]); [`LookupTables`] has them compiled
+    /// for evaluation.
+    pub fn presence_rules(&self) -> impl Iterator<Item = PresenceRule<'a>> {
+        self.1.presence_rules.iter().map(PresenceRule)
+    }
+
+    /// Returns the [`LayoutItemKind`] of `self`.
+    pub fn kind(&self) -> LayoutItemKind {
+        layout_item_kind(&self.1.kind, self.0)
+    }
+
+    /// Returns the human-readable name of `self`.
+    pub fn tag_text(&self) -> &str {
+        match &self.1.kind {
+            LayoutItemKindData::Component { iid } => self
+                .0
+                .inner
+                .components
+                .get(*iid as usize)
+                .unwrap()
+                .name
+                .as_str(),
+            LayoutItemKindData::Group {
+                len_field_iid,
+                items: _items,
+            } => self
+                .0
+                .inner
+                .fields
+                .get(*len_field_iid as usize)
+                .unwrap()
+                .name
+                .as_str(),
+            LayoutItemKindData::Field { iid } => self
+                .0
+                .inner
+                .fields
+                .get(*iid as usize)
+                .unwrap()
+                .name
+                .as_str(),
+        }
+    }
+}
+
+type LayoutItems = Vec<LayoutItemData>;
+
+#[derive(Clone, Debug)]
+struct MessageData {
+    /// The unique integer identifier of this message type.
+    component_id: u32,
+    /// **Primary key**. The unique character identifier of this message
+    /// type; used literally in FIX messages.
+    msg_type: String,
+    /// The name of this message type.
+    name: String,
+    /// Identifier of the category to which this message belongs.
+    category_iid: InternalId,
+    /// Identifier of the section to which this message belongs.
+    section_id: String,
+    layout_items: LayoutItems,
+    /// The abbreviated name of this message, when used in an XML context.
+    abbr_name: Option<String>,
+    /// A boolean used to indicate if the message is to be generated as part
+    /// of FIXML.
+    required: bool,
+    description: String,
+    elaboration: Option<String>,
+    pedigree: PedigreeData,
+    /// Messages with the same message type can have different layouts
+    /// depending on their use case, e.g. an `ExecutionReport <8>` for a fill
+    /// or for a cancellation.
+    scenario: String,
+}
+
+/// A [`Message`] is a unit of information sent on the wire between
+/// counterparties. Every [`Message`] is composed of fields and/or components.
+#[derive(Debug)]
+pub struct Message<'a>(&'a Dictionary, &'a MessageData);
+
+impl<'a> Message<'a> {
+    /// Returns the human-readable name of `self`.
+    pub fn name(&self) -> &str {
+        self.1.name.as_str()
+    }
+
+    /// Returns the message type of `self`.
+    pub fn msg_type(&self) -> &str {
+        self.1.msg_type.as_str()
+    }
+
+    /// Returns the description associated with `self`.
+    pub fn description(&self) -> &str {
+        &self.1.description
+    }
+
+    /// Returns the [`Category`] to which `self` belongs.
+    pub fn category(&self) -> Category<'a> {
+        let data = self
+            .0
+            .inner
+            .categories
+            .get(self.1.category_iid as usize)
+            .unwrap();
+        Category(self.0, data)
+    }
+
+    /// Returns the [`Section`] to which `self` belongs, if known. QuickFIX
+    /// specification files have no sections.
+    pub fn section(&self) -> Option<Section<'a>> {
+        self.0
+            .section_by_id(&self.1.section_id)
+            .or_else(|| self.category().section())
+    }
+
+    /// Returns `true` if `self` is an administrative (i.e. session-level)
+    /// message, e.g. `Logon <A>`; `false` if it's an application-level message.
+    pub fn is_admin(&self) -> bool {
+        self.category().name() == "admin"
+            || matches!(self.section(), Some(section) if section.id() == "Session")
+    }
+
+    /// Returns the [`Pedigree`] of `self`.
+    pub fn pedigree(&self) -> Pedigree<'a> {
+        Pedigree(&self.1.pedigree)
+    }
+
+    /// Returns the scenario of `self`, usually [`BASE_SCENARIO`].
+    pub fn scenario(&self) -> &str {
+        self.1.scenario.as_str()
+    }
+
+    pub fn group_info(&self, num_in_group_tag: TagU16) -> Option<TagU16> {
+        self.layout().find_map(|layout_item| {
+            if let LayoutItemKind::Group(field, items) = layout_item.kind() {
+                if field.tag() == num_in_group_tag {
+                    if let LayoutItemKind::Field(f) = items[0].kind() {
+                        Some(f.tag())
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                }
+            } else if let LayoutItemKind::Component(_component) = layout_item.kind() {
+                None
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns the component ID of `self`.
+    pub fn component_id(&self) -> u32 {
+        self.1.component_id
+    }
+
+    pub fn layout(&self) -> impl Iterator<Item = LayoutItem> {
+        self.1
+            .layout_items
+            .iter()
+            .map(move |data| LayoutItem(self.0, data))
+    }
+}
+
+/// A [`Section`] is a collection of many [`Component`]-s. It has no practical
+/// effect on encoding and decoding of FIX data and it's only used for
+/// documentation and human readability.
+#[derive(Clone, Debug)]
+pub struct Section<'a>(&'a Dictionary, &'a SectionData);
+
+#[derive(Clone, Debug)]
+struct SectionData {
+    /// **Primary key.** A string uniquely identifying this section, e.g.
+    /// `PreTrade`.
+    id: String,
+    /// The human readable name of this section.
+    name: String,
+    /// The position of this section relative to the others, used for
+    /// documentation purposes.
+    display_order: u32,
+    /// The FIXML file name for this section.
+    fixml_filename: String,
+    description: Option<String>,
+}
+
+impl<'a> Section<'a> {
+    /// Returns the unique identifier of `self`.
+    pub fn id(&self) -> &str {
+        self.1.id.as_str()
+    }
+
+    /// Returns the human-readable name of `self`.
+    pub fn name(&self) -> &str {
+        self.1.name.as_str()
+    }
+
+    /// Returns the position of `self` relative to the other sections of the
+    /// [`Dictionary`].
+    pub fn display_order(&self) -> u32 {
+        self.1.display_order
+    }
+
+    /// Returns the FIXML file name of `self`.
+    pub fn fixml_filename(&self) -> &str {
+        self.1.fixml_filename.as_str()
+    }
+
+    /// Returns the description associated with `self`, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.1.description.as_deref()
+    }
+
+    /// Returns an [`Iterator`] over all [`Category`]-s which belong to `self`.
+    pub fn categories(&self) -> impl Iterator<Item = Category<'a>> {
+        let id = self.1.id.as_str();
+        self.0
+            .iter_categories()
+            .filter(move |category| category.1.section_id.as_deref() == Some(id))
+    }
+}
+
+mod symbol_table {
+    use super::InternalId;
+    use fnv::FnvHashMap;
+    use std::borrow::Borrow;
+    use std::hash::Hash;
+
+    pub type SymbolTable = FnvHashMap<Key, InternalId>;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub enum Key {
+        Abbreviation(String),
+        CategoryByName(String),
+        ComponentByName(String),
+        DatatypeByName(String),
+        FieldByTag(u32),
+        FieldByName(String),
+        MessageByName(String),
+        MessageByMsgType(String),
+        SectionById(String),
+    }
+
+    #[derive(Copy, Debug, Clone, PartialEq, Eq, Hash)]
+    pub enum KeyRef<'a> {
+        Abbreviation(&'a str),
+        CategoryByName(&'a str),
+        ComponentByName(&'a str),
+        DatatypeByName(&'a str),
+        FieldByTag(u32),
+        FieldByName(&'a str),
+        MessageByName(&'a str),
+        MessageByMsgType(&'a str),
+        SectionById(&'a str),
+    }
+
+    impl Key {
+        fn as_ref(&self) -> KeyRef {
+            match self {
+                Key::Abbreviation(s) => KeyRef::Abbreviation(s.as_str()),
+                Key::CategoryByName(s) => KeyRef::CategoryByName(s.as_str()),
+                Key::ComponentByName(s) => KeyRef::ComponentByName(s.as_str()),
+                Key::DatatypeByName(s) => KeyRef::DatatypeByName(s.as_str()),
+                Key::FieldByTag(t) => KeyRef::FieldByTag(*t),
+                Key::FieldByName(s) => KeyRef::FieldByName(s.as_str()),
+                Key::MessageByName(s) => KeyRef::MessageByName(s.as_str()),
+                Key::MessageByMsgType(s) => KeyRef::MessageByMsgType(s.as_str()),
+                Key::SectionById(s) => KeyRef::SectionById(s.as_str()),
+            }
+        }
+    }
+
+    pub trait SymbolTableIndex {
+        fn to_key(&self) -> KeyRef;
+    }
+
+    impl SymbolTableIndex for Key {
+        fn to_key(&self) -> KeyRef {
+            self.as_ref()
+        }
+    }
+
+    impl<'a> SymbolTableIndex for KeyRef<'a> {
+        fn to_key(&self) -> KeyRef {
+            *self
+        }
+    }
+
+    impl<'a> Hash for dyn SymbolTableIndex + 'a {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.to_key().hash(state);
+        }
+    }
+
+    impl<'a> Borrow<dyn SymbolTableIndex + 'a> for Key {
+        fn borrow(&self) -> &(dyn SymbolTableIndex + 'a) {
+            self
+        }
+    }
+
+    impl<'a> Eq for dyn SymbolTableIndex + 'a {}
+
+    impl<'a> PartialEq for dyn SymbolTableIndex + 'a {
+        fn eq(&self, other: &dyn SymbolTableIndex) -> bool {
+            self.to_key() == other.to_key()
+        }
+    }
+}
+
+mod quickfix {
+    use super::*;
+
+    pub struct QuickFixReader<'a> {
+        node_with_header: roxmltree::Node<'a, 'a>,
+        node_with_trailer: roxmltree::Node<'a, 'a>,
+        node_with_components: roxmltree::Node<'a, 'a>,
+        node_with_messages: roxmltree::Node<'a, 'a>,
+        node_with_fields: roxmltree::Node<'a, 'a>,
+        builder: DictionaryBuilder,
+    }
+
+    impl<'a> QuickFixReader<'a> {
+        pub fn new_dict_from_xml(
+            xml_document: &'a roxmltree::Document<'a>,
+        ) -> ParseResult<Dictionary> {
+            let mut reader = Self::empty(xml_document)?;
+            for child in reader.node_with_fields.children() {
+                if child.is_element() {
+                    import_field(&mut reader.builder, child)?;
+                }
+            }
+            for child in reader.node_with_components.children() {
+                if child.is_element() {
+                    let name = child
+                        .attribute("name")
+                        .ok_or(ParseDictionaryError::InvalidFormat)?
+                        .to_string();
+                    // Skip components which were already imported while
+                    // resolving references.
+                    if reader
+                        .builder
+                        .symbol(KeyRef::ComponentByName(&name))
+                        .is_none()
+                    {
+                        import_component(&mut reader.builder, child, name)?;
+                    }
+                }
+            }
+            for child in reader.node_with_messages.children() {
+                if child.is_element() {
+                    import_message(&mut reader.builder, child)?;
+                }
+            }
+            // `StandardHeader` and `StandardTrailer` are defined in ad-hoc
+            // sections of the XML files. They're always there, even if
+            // potentially empty (e.g. FIX 5.0+).
+            import_component(
+                &mut reader.builder,
+                reader.node_with_header,
+                "StandardHeader",
+            )?;
+            import_component(
+                &mut reader.builder,
+                reader.node_with_trailer,
+                "StandardTrailer",
+            )?;
+            Ok(reader.builder.build())
+        }
+
+        fn empty(xml_document: &'a roxmltree::Document<'a>) -> ParseResult<Self> {
+            let root = xml_document.root_element();
+            let find_tagged_child = |tag: &str| {
+                root.children()
+                    .find(|n| n.has_tag_name(tag))
+                    .ok_or_else(|| {
+                        ParseDictionaryError::InvalidData(format!("<{}> tag not found", tag))
+                    })
+            };
+            Ok(QuickFixReader {
+                builder: DictionaryBuilder::new(version(root)?),
+                node_with_header: find_tagged_child("header")?,
+                node_with_trailer: find_tagged_child("trailer")?,
+                node_with_messages: find_tagged_child("messages")?,
+                node_with_components: find_tagged_child("components")?,
+                node_with_fields: find_tagged_child("fields")?,
+            })
+        }
+    }
+
+    /// Reads the version string from the attributes of the root `<fix>` tag.
+    pub(super) fn version(root: roxmltree::Node) -> ParseResult<String> {
+        let version_type = root.attribute("type").ok_or_else(|| {
+            ParseDictionaryError::InvalidData("No version attribute.".to_string())
+        })?;
+        let version_major = root.attribute("major").ok_or_else(|| {
+            ParseDictionaryError::InvalidData("No major version attribute.".to_string())
+        })?;
+        let version_minor = root.attribute("minor").ok_or_else(|| {
+            ParseDictionaryError::InvalidData("No minor version attribute.".to_string())
+        })?;
+        let version_sp = root.attribute("servicepack").unwrap_or("0");
+        Ok(format!(
+            "{}.{}.{}{}",
+            version_type,
+            version_major,
+            version_minor,
+            // Omit Service Pack ID if set to zero.
+            if version_sp != "0" {
+                format!("-SP{}", version_sp)
+            } else {
+                String::new()
+            }
+        ))
+    }
+
+    fn import_field(
+        builder: &mut DictionaryBuilder,
+        node: roxmltree::Node,
+    ) -> ParseResult<InternalId> {
+        if node.tag_name().name() != "field" {
+            return Err(ParseDictionaryError::InvalidFormat);
+        }
+        let data_type_iid = import_datatype(builder, node);
+        let value_restrictions = value_restrictions_from_node(node, data_type_iid);
+        let name = node
+            .attribute("name")
+            .ok_or(ParseDictionaryError::InvalidFormat)?
+            .to_string();
+        let tag = node
+            .attribute("number")
+            .ok_or(ParseDictionaryError::InvalidFormat)?
+            .parse()
+            .map_err(|_| ParseDictionaryError::InvalidFormat)?;
+        let field = FieldData {
+            name,
+            tag,
+            data_type_iid,
+            associated_data_tag: None,
+            value_restrictions,
+            required: true,
+            abbr_name: None,
+            base_category_abbr_name: None,
+            base_category_id: None,
+            description: None,
+            pedigree: PedigreeData::default(),
+            constraints: FieldConstraints::default(),
+        };
+        Ok(builder.add_field(field))
+    }
+
+    fn import_message(
+        builder: &mut DictionaryBuilder,
+        node: roxmltree::Node,
+    ) -> ParseResult<InternalId> {
+        debug_assert_eq!(node.tag_name().name(), "message");
+        let category_iid = import_category(builder, node)?;
+        let mut layout_items = LayoutItems::new();
+        for child in node.children() {
+            if child.is_element() {
+                // We don't need to generate new IID's because we're dealing
+                // with ranges.
+                layout_items.push(import_layout_item(builder, child)?);
+            }
+        }
+        let message = MessageData {
+            name: node
+                .attribute("name")
+                .ok_or(ParseDictionaryError::InvalidFormat)?
+                .to_string(),
+            msg_type: node
+                .attribute("msgtype")
+                .ok_or(ParseDictionaryError::InvalidFormat)?
+                .to_string(),
+            component_id: 0,
+            category_iid,
+            section_id: String::new(),
+            layout_items,
+            abbr_name: None,
+            required: true,
+            elaboration: None,
+            description: String::new(),
+            pedigree: PedigreeData::default(),
+            scenario: BASE_SCENARIO.to_string(),
+        };
+        Ok(builder.add_message(message))
+    }
+
+    fn import_component<S: AsRef<str>>(
+        builder: &mut DictionaryBuilder,
+        node: roxmltree::Node,
+        name: S,
+    ) -> ParseResult<InternalId> {
+        let mut layout_items = LayoutItems::new();
+        for child in node.children() {
+            if child.is_element() {
+                layout_items.push(import_layout_item(builder, child)?);
+            }
+        }
+        let component = ComponentData {
+            id: 0,
+            component_type: FixmlComponentAttributes::Block {
+                // FIXME
+                is_implicit: false,
+                is_repeating: false,
+                is_optimized: false,
+            },
+            layout_items,
+            category_iid: 0, // FIXME
+            name: name.as_ref().to_string(),
+            abbr_name: None,
+            description: None,
+            pedigree: PedigreeData::default(),
+            scenario: BASE_SCENARIO.to_string(),
+        };
+        let iid = builder.add_component(component);
+        match builder.symbol(KeyRef::ComponentByName(name.as_ref())) {
+            Some(x) => Ok(*x),
+            None => {
+                builder
+                    .symbol_table
+                    .insert(Key::ComponentByName(name.as_ref().to_string()), iid);
+                Ok(iid)
+            }
+        }
+    }
+
+    fn import_datatype(builder: &mut DictionaryBuilder, node: roxmltree::Node) -> InternalId {
+        // References should only happen at <field> tags.
+        debug_assert_eq!(node.tag_name().name(), "field");
+        let datatype = {
+            // The idenfier that QuickFIX uses for this type.
+            let quickfix_name = node.attribute("type").unwrap();
+            // Translate that into a real datatype.
+            FixDatatype::from_quickfix_name(quickfix_name).unwrap()
+        };
+        // Get the official (not QuickFIX's) name of `datatype`.
+        let name = datatype.name();
+        match builder.symbol(KeyRef::DatatypeByName(name)) {
+            Some(x) => *x,
+            None => {
+                let iid = builder.data_types.len() as u32;
+                let data = DatatypeData {
+                    datatype,
+                    description: String::new(),
+                    examples: Vec::new(),
+                };
+                builder.data_types.push(data);
+                builder
+                    .symbol_table
+                    .insert(Key::DatatypeByName(name.to_string()), iid);
+                iid
+            }
+        }
+    }
+
+    fn value_restrictions_from_node(
+        node: roxmltree::Node,
+        _datatype: InternalId,
+    ) -> Option<Vec<FieldEnumData>> {
+        let mut values = Vec::new();
+        for child in node.children() {
+            if child.is_element() {
+                let variant = child.attribute("enum").unwrap().to_string();
+                let description = child.attribute("description").unwrap().to_string();
+                let enum_value = FieldEnumData {
+                    value: variant,
+                    description,
+                    symbolic_name: None,
+                    pedigree: PedigreeData::default(),
+                };
+                values.push(enum_value);
+            }
+        }
+        if values.is_empty() {
+            None
+        } else {
+            Some(values)
+        }
+    }
+
+    fn import_layout_item(
+        builder: &mut DictionaryBuilder,
+        node: roxmltree::Node,
+    ) -> ParseResult<LayoutItemData> {
+        // This processing step requires on fields being already present in
+        // the dictionary.
+        debug_assert_ne!(builder.fields.len(), 0);
+        let name = node.attribute("name").unwrap();
+        let required = node.attribute("required").unwrap() == "Y";
+        let tag = node.tag_name().name();
+        let kind = match tag {
+            "field" => {
+                let field_iid = builder.symbol(KeyRef::FieldByName(name)).unwrap();
+                LayoutItemKindData::Field { iid: *field_iid }
+            }
+            "component" => {
+                // Components may *not* be already present, in which case we
+                // must look up their definition (`node` is just a reference).
+                let component_iid = match builder.symbol(KeyRef::ComponentByName(name)) {
+                    Some(iid) => *iid,
+                    None => {
+                        let definition = node
+                            .document()
+                            .root_element()
+                            .children()
+                            .find(|n| n.has_tag_name("components"))
+                            .and_then(|components| {
+                                components.children().find(|n| {
+                                    n.has_tag_name("component") && n.attribute("name") == Some(name)
+                                })
+                            })
+                            .ok_or(ParseDictionaryError::InvalidFormat)?;
+                        import_component(builder, definition, name)?
+                    }
+                };
+                LayoutItemKindData::Component { iid: component_iid }
+            }
+            "group" => {
+                let len_field_iid = *builder.symbol(KeyRef::FieldByName(name)).unwrap();
+                let mut items = Vec::new();
+                for child in node.children().filter(|n| n.is_element()) {
+                    items.push(import_layout_item(builder, child)?);
+                }
+                LayoutItemKindData::Group {
+                    len_field_iid,
+                    items,
+                }
+            }
+            _ => {
+                return Err(ParseDictionaryError::InvalidFormat);
+            }
+        };
+        let item = LayoutItemData {
+            required,
+            kind,
+            presence_rules: Vec::new(),
+        };
+        Ok(item)
+    }
+
+    fn import_category(
+        builder: &mut DictionaryBuilder,
+        node: roxmltree::Node,
+    ) -> ParseResult<InternalId> {
+        debug_assert_eq!(node.tag_name().name(), "message");
+        let name = node.attribute("msgcat").ok_or(ParseError::InvalidFormat)?;
+        Ok(match builder.symbol(KeyRef::CategoryByName(name)) {
+            Some(x) => *x,
+            None => {
+                let iid = builder.categories.len() as u32;
+                builder.categories.push(CategoryData {
+                    name: name.to_string(),
+                    fixml_filename: String::new(),
+                    section_id: None,
+                });
+                builder
+                    .symbol_table
+                    .insert(Key::CategoryByName(name.to_string()), iid);
+                iid
+            }
+        })
+    }
+
+    /// Writes `dict` as a QuickFIX-style specification file, with the same
+    /// layout and formatting as the files bundled with QuickFIX.
+    pub fn write_spec<W: fmt::Write>(dict: &Dictionary, w: &mut W) -> fmt::Result {
+        let (fix_type, major, minor, servicepack) = split_version(dict.get_version());
+        writeln!(
+            w,
+            "<fix type='{}' major='{}' minor='{}' servicepack='{}'>",
+            Xml(fix_type),
+            Xml(major),
+            Xml(minor),
+            Xml(servicepack)
+        )?;
+        write_std_component(w, dict, "header", "StandardHeader")?;
+        writeln!(w, " <messages>")?;
+        for message in dict
+            .iter_messages()
+            .filter(|message| message.scenario() == BASE_SCENARIO)
+        {
+            writeln!(
+                w,
+                "  <message name='{}' msgtype='{}' msgcat='{}'>",
+                Xml(message.name()),
+                Xml(message.msg_type()),
+                Xml(msgcat(&message))
+            )?;
+            // QuickFIX messages don't reference `StandardHeader` and
+            // `StandardTrailer` explicitly.
+            for item in message.layout().filter(|item| match item.kind() {
+                LayoutItemKind::Component(component) => !is_std_component(&component),
+                _ => true,
+            }) {
+                write_layout_item(w, 3, item)?;
+            }
+            writeln!(w, "  </message>")?;
+        }
+        writeln!(w, " </messages>")?;
+        write_std_component(w, dict, "trailer", "StandardTrailer")?;
+        writeln!(w, " <components>")?;
+        for component in dict.iter_components().filter(|component| {
+            component.scenario() == BASE_SCENARIO && !is_std_component(component)
+        }) {
+            writeln!(w, "  <component name='{}'>", Xml(component.name()))?;
+            for item in component.items() {
+                write_layout_item(w, 3, item)?;
+            }
+            writeln!(w, "  </component>")?;
+        }
+        writeln!(w, " </components>")?;
+        writeln!(w, " <fields>")?;
+        for field in dict.iter_fields() {
+            write!(
+                w,
+                "  <field number='{}' name='{}' type='{}'",
+                field.tag().get(),
+                Xml(field.name()),
+                field.fix_datatype().to_quickfix_name()
+            )?;
+            if let Some(enums) = field.enums() {
+                writeln!(w, ">")?;
+                for field_enum in enums {
+                    // QuickFIX descriptions are symbolic names, e.g. `LIMIT`.
+                    let description = field_enum
+                        .symbolic_name()
+                        .unwrap_or_else(|| field_enum.description());
+                    writeln!(
+                        w,
+                        "   <value enum='{}' description='{}' />",
+                        Xml(field_enum.value()),
+                        Xml(description)
+                    )?;
+                }
+                writeln!(w, "  </field>")?;
+            } else {
+                writeln!(w, " />")?;
+            }
+        }
+        writeln!(w, " </fields>")?;
+        writeln!(w, "</fix>")
+    }
+
+    /// Splits e.g. `FIX.5.0-SP2` into `("FIX", "5", "0", "2")`, i.e. the
+    /// opposite of what [`QuickFixReader`] does.
+    fn split_version(version: &str) -> (&str, &str, &str, &str) {
+        let (fix_type, rest) = version.split_once('.').unwrap_or((version, ""));
+        let (major, rest) = rest.split_once('.').unwrap_or((rest, ""));
+        let (minor, servicepack) = rest.split_once("-SP").unwrap_or((rest, "0"));
+        (fix_type, major, minor, servicepack)
+    }
+
+    /// QuickFIX only distinguishes between administrative and application
+    /// messages. Other dictionary sources have more granular categories.
+    fn msgcat(message: &Message) -> &'static str {
+        if message.is_admin() {
+            "admin"
+        } else {
+            "app"
+        }
+    }
+
+    fn is_std_component(component: &Component) -> bool {
+        component.name() == "StandardHeader" || component.name() == "StandardTrailer"
+    }
+
+    /// `StandardHeader` and `StandardTrailer` have their own ad-hoc sections.
+    fn write_std_component<W: fmt::Write>(
+        w: &mut W,
+        dict: &Dictionary,
+        tag: &str,
+        name: &str,
+    ) -> fmt::Result {
+        match dict.component_by_name(name) {
+            Some(component) if component.items().next().is_some() => {
+                writeln!(w, " <{}>", tag)?;
+                for item in component.items() {
+                    write_layout_item(w, 2, item)?;
+                }
+                writeln!(w, " </{}>", tag)
+            }
+            _ => writeln!(w, " <{} />", tag),
+        }
+    }
+
+    fn write_layout_item<W: fmt::Write>(w: &mut W, indent: usize, item: LayoutItem) -> fmt::Result {
+        let required = if item.required() { 'Y' } else { 'N' };
+        write!(w, "{:indent$}", "", indent = indent)?;
+        match item.kind() {
+            LayoutItemKind::Field(field) => writeln!(
+                w,
+                "<field name='{}' required='{}' />",
+                Xml(field.name()),
+                required
+            ),
+            LayoutItemKind::Component(component) => writeln!(
+                w,
+                "<component name='{}' required='{}' />",
+                Xml(component.name()),
+                required
+            ),
+            LayoutItemKind::Group(len_field, items) => {
+                writeln!(
+                    w,
+                    "<group name='{}' required='{}'>",
+                    Xml(len_field.name()),
+                    required
+                )?;
+                for item in items {
+                    write_layout_item(w, indent + 1, item)?;
+                }
+                writeln!(w, "{:indent$}</group>", "", indent = indent)
+            }
+        }
+    }
+
+    /// Escapes XML special characters when displayed.
+    struct Xml<'a>(&'a str);
+
+    impl<'a> fmt::Display for Xml<'a> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            for c in self.0.chars() {
+                match c {
+                    '&' => f.write_str("&amp;")?,
+                    '<' => f.write_str("&lt;")?,
+                    '>' => f.write_str("&gt;")?,
+                    '\'' => f.write_str("&apos;")?,
+                    '"' => f.write_str("&quot;")?,
+                    c => f.write_str(c.encode_utf8(&mut [0; 4]))?,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    type ParseError = ParseDictionaryError;
+    type ParseResult<T> = Result<T, ParseError>;
+
+    /// The error type that can arise when decoding a QuickFIX Dictionary.
+    #[derive(Clone, Debug)]
+    pub enum ParseDictionaryError {
+        InvalidFormat,
+        InvalidData(String),
+    }
+}
+
+mod fix_repository {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    /// Reads the XML files of a single FIX version from the FIX Repository
+    /// (2010 Edition). `Abbreviations.xml`, `Categories.xml` and `Sections.xml`
+    /// are only available from FIX 4.4 onwards, so they're optional.
+    pub struct FixRepositoryReader {
+        base_dir: PathBuf,
+        builder: DictionaryBuilder,
+    }
+
+    impl FixRepositoryReader {
+        pub fn new_dict_from_dir(dir: &Path) -> ParseResult<Dictionary> {
+            let base_dir = if dir.join("Base").is_dir() {
+                dir.join("Base")
+            } else {
+                dir.to_path_buf()
+            };
+            let fields_xml = read_file(&base_dir, "Fields.xml")?;
+            let fields_doc = parse_document(&fields_xml)?;
+            let mut reader = FixRepositoryReader {
+                builder: DictionaryBuilder::new(version(&fields_doc)?),
+                base_dir,
+            };
+            if let Some(xml) = reader.read_optional_file("Sections.xml")? {
+                import_sections(&mut reader.builder, &parse_document(&xml)?)?;
+            }
+            if let Some(xml) = reader.read_optional_file("Categories.xml")? {
+                import_categories(&mut reader.builder, &parse_document(&xml)?)?;
+            }
+            if let Some(xml) = reader.read_optional_file("Abbreviations.xml")? {
+                import_abbreviations(&mut reader.builder, &parse_document(&xml)?)?;
+            }
+            let datatypes_xml = read_file(&reader.base_dir, "Datatypes.xml")?;
+            let datatypes = datatypes(&parse_document(&datatypes_xml)?)?;
+            let enums_xml = read_file(&reader.base_dir, "Enums.xml")?;
+            let mut enums = enums(&parse_document(&enums_xml)?)?;
+            for node in elements(&fields_doc, "Field") {
+                import_field(&mut reader.builder, node, &datatypes, &mut enums)?;
+            }
+            // Layouts reference components by name, so all components must
+            // be known before any layout is imported.
+            let mut containers = HashMap::new();
+            let components_xml = read_file(&reader.base_dir, "Components.xml")?;
+            for node in elements(&parse_document(&components_xml)?, "Component") {
+                let (id, iid) = import_component(&mut reader.builder, node)?;
+                containers.insert(id, Container::Component(iid));
+            }
+            let messages_xml = read_file(&reader.base_dir, "Messages.xml")?;
+            for node in elements(&parse_document(&messages_xml)?, "Message") {
+                let (id, iid) = import_message(&mut reader.builder, node)?;
+                containers.insert(id, Container::Message(iid));
+            }
+            let msg_contents_xml = read_file(&reader.base_dir, "MsgContents.xml")?;
+            let msg_contents_doc = parse_document(&msg_contents_xml)?;
+            for (id, contents) in msg_contents(&msg_contents_doc)? {
+                let layout_items = import_layout_items(&reader.builder, &contents)?;
+                match containers.get(&id) {
+                    Some(Container::Component(iid)) => {
+                        reader.builder.components[*iid as usize].layout_items = layout_items;
+                    }
+                    Some(Container::Message(iid)) => {
+                        reader.builder.messages[*iid as usize].layout_items = layout_items;
+                    }
+                    None => {
+                        return Err(ParseDictionaryError::InvalidData(format!(
+                            "Unknown ComponentID {} in MsgContents.xml",
+                            id
+                        )));
+                    }
+                }
+            }
+            Ok(reader.builder.build())
+        }
+
+        fn read_optional_file(&self, filename: &str) -> ParseResult<Option<String>> {
+            if self.base_dir.join(filename).is_file() {
+                read_file(&self.base_dir, filename).map(Some)
+            } else {
+                Ok(None)
+            }
+        }
+    }
+
+    /// Both messages and components are identified by a `ComponentID` within
+    /// `MsgContents.xml`.
+    enum Container {
+        Component(InternalId),
+        Message(InternalId),
+    }
+
+    /// A single entry of `MsgContents.xml`.
+    struct MsgContent<'a> {
+        position: f64,
+        tag_text: &'a str,
+        indent: u32,
+        required: bool,
+    }
+
+    fn read_file(base_dir: &Path, filename: &str) -> ParseResult<String> {
+        let path = base_dir.join(filename);
+        std::fs::read_to_string(&path).map_err(|err| {
+            ParseDictionaryError::InvalidData(format!("Can't read {}: {}", path.display(), err))
+        })
+    }
+
+    fn parse_document(xml: &str) -> ParseResult<roxmltree::Document> {
+        roxmltree::Document::parse(xml).map_err(|_| ParseDictionaryError::InvalidFormat)
+    }
+
+    /// Returns the version string of the Repository, using the same naming
+    /// conventions as [`Dictionary::from_quickfix_spec`] (e.g. `FIX.5.0-SP2`
+    /// rather than `FIX.5.0SP2`).
+    fn version(document: &roxmltree::Document) -> ParseResult<String> {
+        let version = document
+            .root_element()
+            .attribute("version")
+            .ok_or_else(|| {
+                ParseDictionaryError::InvalidData("No version attribute.".to_string())
+            })?;
+        Ok(normalize_version(version))
+    }
+
+    pub(super) fn normalize_version(version: &str) -> String {
+        match version.find("SP") {
+            Some(i) => format!("{}-{}", &version[..i], &version[i..]),
+            None => version.to_string(),
+        }
+    }
+
+    /// Most Repository datatype names are also valid QuickFIX names, but
+    /// later editions introduce a few more.
+    pub(super) fn datatype_from_name(name: &str) -> Option<FixDatatype> {
+        FixDatatype::from_quickfix_name(name).or(match name {
+            "TagNum" => Some(FixDatatype::TagNum),
+            // There's no datatype for local times of day. `LocalMktTime`
+            // values have the same format as `UTCTimeOnly` ones, only in the
+            // time zone of the market instead of UTC.
+            "LocalMktTime" => Some(FixDatatype::UtcTimeOnly),
+            "XID" | "XIDREF" => Some(FixDatatype::String),
+            _ => None,
+        })
+    }
+
+    fn elements<'a, 'input>(
+        document: &'a roxmltree::Document<'input>,
+        tag: &'a str,
+    ) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+        document
+            .root_element()
+            .children()
+            .filter(move |node| node.has_tag_name(tag))
+    }
+
+    fn child_text<'a>(node: roxmltree::Node<'a, '_>, tag: &str) -> Option<&'a str> {
+        node.children()
+            .find(|child| child.has_tag_name(tag))
+            .and_then(|child| child.text())
+    }
+
+    fn required_child_text<'a>(node: roxmltree::Node<'a, '_>, tag: &str) -> ParseResult<&'a str> {
+        child_text(node, tag).ok_or_else(|| {
+            ParseDictionaryError::InvalidData(format!(
+                "<{}> without <{}>",
+                node.tag_name().name(),
+                tag
+            ))
+        })
+    }
+
+    fn parse_child<T: std::str::FromStr>(node: roxmltree::Node, tag: &str) -> ParseResult<T> {
+        let text = required_child_text(node, tag)?;
+        text.trim().parse().map_err(|_| {
+            ParseDictionaryError::InvalidData(format!("Invalid <{}>: '{}'", tag, text))
+        })
+    }
+
+    pub(super) fn pedigree(node: roxmltree::Node) -> PedigreeData {
+        let version = |name| node.attribute(name).map(str::to_string);
+        let ep = |name| node.attribute(name).and_then(|s| s.parse().ok());
+        PedigreeData {
+            added: version("added"),
+            added_ep: ep("addedEP"),
+            updated: version("updated"),
+            updated_ep: ep("updatedEP"),
+            deprecated: version("deprecated"),
+            deprecated_ep: ep("deprecatedEP"),
+        }
+    }
+
+    pub(super) fn category_iid(builder: &mut DictionaryBuilder, name: &str) -> InternalId {
+        match builder.symbol(KeyRef::CategoryByName(name)) {
+            Some(iid) => *iid,
+            // Older FIX versions have no `Categories.xml`.
+            None => builder.add_category(CategoryData {
+                name: name.to_string(),
+                fixml_filename: String::new(),
+                section_id: None,
+            }),
+        }
+    }
+
+    /// Makes sure that the section `id` exists and that the category
+    /// `category_iid` belongs to a section. Older FIX versions have no
+    /// `Sections.xml`, but messages refer to sections nonetheless.
+    fn link_section(builder: &mut DictionaryBuilder, id: &str, category_iid: InternalId) {
+        if builder.symbol(KeyRef::SectionById(id)).is_none() {
+            let display_order = builder.sections.len() as u32 + 1;
+            builder.add_section(SectionData {
+                id: id.to_string(),
+                name: id.to_string(),
+                display_order,
+                fixml_filename: String::new(),
+                description: None,
+            });
+        }
+        builder.categories[category_iid as usize]
+            .section_id
+            .get_or_insert_with(|| id.to_string());
+    }
+
+    fn import_sections(
+        builder: &mut DictionaryBuilder,
+        document: &roxmltree::Document,
+    ) -> ParseResult<()> {
+        for node in elements(document, "Section") {
+            builder.add_section(SectionData {
+                id: required_child_text(node, "SectionID")?.to_string(),
+                name: required_child_text(node, "Name")?.to_string(),
+                display_order: parse_child(node, "DisplayOrder")?,
+                fixml_filename: child_text(node, "FIXMLFileName")
+                    .unwrap_or_default()
+                    .to_string(),
+                description: child_text(node, "Description").map(str::to_string),
+            });
+        }
+        Ok(())
+    }
+
+    fn import_categories(
+        builder: &mut DictionaryBuilder,
+        document: &roxmltree::Document,
+    ) -> ParseResult<()> {
+        for node in elements(document, "Category") {
+            builder.add_category(CategoryData {
+                name: required_child_text(node, "CategoryID")?.to_string(),
+                fixml_filename: child_text(node, "FIXMLFileName")
+                    .unwrap_or_default()
+                    .to_string(),
+                section_id: child_text(node, "SectionID").map(str::to_string),
+            });
+        }
+        Ok(())
+    }
+
+    fn import_abbreviations(
+        builder: &mut DictionaryBuilder,
+        document: &roxmltree::Document,
+    ) -> ParseResult<()> {
+        for node in elements(document, "Abbreviation") {
+            builder.add_abbreviation(AbbreviationData {
+                term: required_child_text(node, "Term")?.to_string(),
+                abbreviation: required_child_text(node, "AbbrTerm")?.to_string(),
+                is_last: false,
+                pedigree: pedigree(node),
+            });
+        }
+        Ok(())
+    }
+
+    /// Several Repository datatype names may map to the same [`FixDatatype`]
+    /// (e.g. `date` and `UTCDate`), in which case we prefer the one with the
+    /// same name.
+    fn datatypes(
+        document: &roxmltree::Document,
+    ) -> ParseResult<HashMap<FixDatatype, DatatypeData>> {
+        let mut datatypes = HashMap::new();
+        for node in elements(document, "Datatype") {
+            let name = required_child_text(node, "Name")?;
+            // Some datatypes (e.g. `Reserved100Plus`) are never used by any
+            // field and have no equivalent.
+            let datatype = match datatype_from_name(name) {
+                Some(datatype) => datatype,
+                None => continue,
+            };
+            if datatypes.contains_key(&datatype) && datatype.name() != name {
+                continue;
+            }
+            let examples = node
+                .descendants()
+                .filter(|n| n.has_tag_name("Example"))
+                .filter_map(|n| n.text())
+                .map(str::to_string)
+                .collect();
+            let data = DatatypeData {
+                datatype,
+                description: child_text(node, "Description")
+                    .unwrap_or_default()
+                    .to_string(),
+                examples,
+            };
+            datatypes.insert(datatype, data);
+        }
+        Ok(datatypes)
+    }
+
+    pub(super) fn datatype_iid(
+        builder: &mut DictionaryBuilder,
+        datatype: FixDatatype,
+        datatypes: &HashMap<FixDatatype, DatatypeData>,
+    ) -> InternalId {
+        let name = datatype.name();
+        match builder.symbol(KeyRef::DatatypeByName(name)) {
+            Some(iid) => *iid,
+            None => {
+                let iid = builder.data_types.len() as InternalId;
+                let data = datatypes.get(&datatype).cloned().unwrap_or(DatatypeData {
+                    datatype,
+                    description: String::new(),
+                    examples: Vec::new(),
+                });
+                builder.data_types.push(data);
+                builder
+                    .symbol_table
+                    .insert(Key::DatatypeByName(name.to_string()), iid);
+                iid
+            }
+        }
+    }
+
+    /// Returns all enumerated values, grouped by tag and in order of
+    /// appearance.
+    fn enums(document: &roxmltree::Document) -> ParseResult<HashMap<u32, Vec<FieldEnumData>>> {
+        let mut enums: HashMap<u32, Vec<FieldEnumData>> = HashMap::new();
+        for node in elements(document, "Enum") {
+            let tag = parse_child(node, "Tag")?;
+            enums.entry(tag).or_default().push(FieldEnumData {
+                value: required_child_text(node, "Value")?.to_string(),
+                description: child_text(node, "Description")
+                    .unwrap_or_default()
+                    .to_string(),
+                symbolic_name: child_text(node, "SymbolicName").map(str::to_string),
+                pedigree: pedigree(node),
+            });
+        }
+        Ok(enums)
+    }
+
+    fn import_field(
+        builder: &mut DictionaryBuilder,
+        node: roxmltree::Node,
+        datatypes: &HashMap<FixDatatype, DatatypeData>,
+        enums: &mut HashMap<u32, Vec<FieldEnumData>>,
+    ) -> ParseResult<InternalId> {
+        let tag = parse_child(node, "Tag")?;
+        let type_name = required_child_text(node, "Type")?;
+        let datatype = datatype_from_name(type_name).ok_or_else(|| {
+            ParseDictionaryError::InvalidData(format!(
+                "Unknown datatype '{}' of field {}",
+                type_name, tag
+            ))
+        })?;
+        // `AssociatedDataTag` is also used to point at related (non-data)
+        // fields, e.g. `RepoCollateralSecurityType <239>`.
+        let associated_data_tag = if datatype == FixDatatype::Length {
+            child_text(node, "AssociatedDataTag").and_then(|s| s.trim().parse().ok())
+        } else {
+            None
+        };
+        let base_category_id = child_text(node, "BaseCategory")
+            .and_then(|name| builder.symbol(KeyRef::CategoryByName(name)))
+            .map(|iid| *iid as usize);
+        let field = FieldData {
+            name: required_child_text(node, "Name")?.to_string(),
+            tag,
+            data_type_iid: datatype_iid(builder, datatype, datatypes),
+            associated_data_tag,
+            value_restrictions: enums.remove(&tag),
+            abbr_name: child_text(node, "AbbrName").map(str::to_string),
+            base_category_id,
+            base_category_abbr_name: child_text(node, "BaseCategoryAbbrName").map(str::to_string),
+            required: child_text(node, "NotReqXML") != Some("1"),
+            description: child_text(node, "Description").map(str::to_string),
+            pedigree: pedigree(node),
+            constraints: FieldConstraints::default(),
+        };
+        Ok(builder.add_field(field))
+    }
+
+    fn component_type(name: &str) -> ParseResult<FixmlComponentAttributes> {
+        let block = |is_repeating, is_implicit, is_optimized| FixmlComponentAttributes::Block {
+            is_repeating,
+            is_implicit,
+            is_optimized,
+        };
+        Ok(match name {
+            "Block" => block(false, false, false),
+            "BlockRepeating" => block(true, false, false),
+            "ImplicitBlock" => block(false, true, false),
+            "ImplicitBlockRepeating" => block(true, true, false),
+            "OptimisedImplicitBlockRepeating" => block(true, true, true),
+            "XMLDataBlock" => FixmlComponentAttributes::Xml,
+            "Message" => FixmlComponentAttributes::Message,
+            _ => {
+                return Err(ParseDictionaryError::InvalidData(format!(
+                    "Unknown ComponentType '{}'",
+                    name
+                )));
+            }
+        })
+    }
+
+    /// Imports a component with an empty layout and returns its
+    /// `ComponentID` together with its [`InternalId`].
+    fn import_component(
+        builder: &mut DictionaryBuilder,
+        node: roxmltree::Node,
+    ) -> ParseResult<(u32, InternalId)> {
+        let id = parse_child(node, "ComponentID")?;
+        let component = ComponentData {
+            id: id as usize,
+            component_type: component_type(required_child_text(node, "ComponentType")?)?,
+            layout_items: LayoutItems::new(),
+            category_iid: category_iid(builder, required_child_text(node, "CategoryID")?),
+            name: required_child_text(node, "Name")?.to_string(),
+            abbr_name: child_text(node, "AbbrName").map(str::to_string),
+            description: child_text(node, "Description").map(str::to_string),
+            pedigree: pedigree(node),
+            scenario: BASE_SCENARIO.to_string(),
+        };
+        Ok((id, builder.add_component(component)))
+    }
+
+    /// Imports a message with an empty layout and returns its `ComponentID`
+    /// together with its [`InternalId`].
+    fn import_message(
+        builder: &mut DictionaryBuilder,
+        node: roxmltree::Node,
+    ) -> ParseResult<(u32, InternalId)> {
+        let component_id = parse_child(node, "ComponentID")?;
+        let category_iid = category_iid(builder, required_child_text(node, "CategoryID")?);
+        let section_id = child_text(node, "SectionID").unwrap_or_default();
+        if !section_id.is_empty() {
+            link_section(builder, section_id, category_iid);
+        }
+        let message = MessageData {
+            component_id,
+            msg_type: required_child_text(node, "MsgType")?.to_string(),
+            name: required_child_text(node, "Name")?.to_string(),
+            category_iid,
+            section_id: section_id.to_string(),
+            layout_items: LayoutItems::new(),
+            abbr_name: child_text(node, "AbbrName").map(str::to_string),
+            required: child_text(node, "NotReqXML") != Some("1"),
+            description: child_text(node, "Description")
+                .unwrap_or_default()
+                .to_string(),
+            elaboration: child_text(node, "Elaboration").map(str::to_string),
+            pedigree: pedigree(node),
+            scenario: BASE_SCENARIO.to_string(),
+        };
+        Ok((component_id, builder.add_message(message)))
+    }
+
+    /// Returns the contents of all messages and components, sorted by
+    /// position.
+    fn msg_contents<'a>(
+        document: &'a roxmltree::Document,
+    ) -> ParseResult<HashMap<u32, Vec<MsgContent<'a>>>> {
+        let mut contents: HashMap<u32, Vec<MsgContent>> = HashMap::new();
+        for node in elements(document, "MsgContent") {
+            let id = parse_child(node, "ComponentID")?;
+            contents.entry(id).or_default().push(MsgContent {
+                // Positions like `13.1` are used for later insertions.
+                position: parse_child(node, "Position")?,
+                tag_text: required_child_text(node, "TagText")?.trim(),
+                indent: parse_child(node, "Indent")?,
+                required: child_text(node, "Reqd") == Some("1"),
+            });
+        }
+        for items in contents.values_mut() {
+            items.sort_by(|a, b| a.position.total_cmp(&b.position));
+        }
+        Ok(contents)
+    }
+
+    /// Repeating groups are not marked as such: the `NumInGroup` field is
+    /// simply followed by the group's items, which have a deeper indentation.
+    fn import_layout_items(
+        builder: &DictionaryBuilder,
+        contents: &[MsgContent],
+    ) -> ParseResult<LayoutItems> {
+        let mut items = LayoutItems::new();
+        let mut i = 0;
+        while i < contents.len() {
+            let content = &contents[i];
+            let group_len = contents[i + 1..]
+                .iter()
+                .take_while(|c| c.indent > content.indent)
+                .count();
+            let kind = if group_len > 0 {
+                LayoutItemKindData::Group {
+                    len_field_iid: field_iid(builder, content.tag_text)?,
+                    items: import_layout_items(builder, &contents[i + 1..=i + group_len])?,
+                }
+            } else if content.tag_text.bytes().all(|b| b.is_ascii_digit()) {
+                LayoutItemKindData::Field {
+                    iid: field_iid(builder, content.tag_text)?,
+                }
+            } else {
+                let iid = builder
+                    .symbol(KeyRef::ComponentByName(content.tag_text))
+                    .ok_or_else(|| {
+                        ParseDictionaryError::InvalidData(format!(
+                            "Unknown component '{}'",
+                            content.tag_text
+                        ))
+                    })?;
+                LayoutItemKindData::Component { iid: *iid }
+            };
+            items.push(LayoutItemData {
+                required: content.required,
+                kind,
+                presence_rules: Vec::new(),
+            });
+            i += 1 + group_len;
+        }
+        Ok(items)
+    }
+
+    fn field_iid(builder: &DictionaryBuilder, tag_text: &str) -> ParseResult<InternalId> {
+        tag_text
+            .parse()
+            .ok()
+            .and_then(|tag| builder.symbol(KeyRef::FieldByTag(tag)))
+            .copied()
+            .ok_or_else(|| {
+                ParseDictionaryError::InvalidData(format!("Unknown field '{}'", tag_text))
+            })
+    }
+
+    type ParseResult<T> = Result<T, ParseDictionaryError>;
+}
+
+mod orchestra {
+    use super::fix_repository::{
+        category_iid, datatype_from_name, datatype_iid, normalize_version, pedigree,
+    };
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Reads FIX Orchestra repositories, e.g. a venue's rules of engagement.
+    /// Fields and code sets are only imported in their base scenario, while
+    /// all scenarios of messages, components and groups are kept.
+    pub struct OrchestraReader<'a> {
+        root: roxmltree::Node<'a, 'a>,
+        builder: DictionaryBuilder,
+        /// Both components and groups, keyed by ID and scenario.
+        components: HashMap<(u32, &'a str), InternalId>,
+        groups: HashMap<(u32, &'a str), InternalId>,
+    }
+
+    impl<'a> OrchestraReader<'a> {
+        pub fn new_dict_from_xml(
+            xml_document: &'a roxmltree::Document<'a>,
+        ) -> ParseResult<Dictionary> {
+            let root = xml_document.root_element();
+            if !root.has_tag_name("repository") {
+                return Err(ParseDictionaryError::InvalidFormat);
+            }
+            let mut reader = OrchestraReader {
+                root,
+                builder: DictionaryBuilder::new(version(root)),
+                components: HashMap::new(),
+                groups: HashMap::new(),
+            };
+            for node in reader.elements("sections", "section") {
+                import_section(&mut reader.builder, node)?;
+            }
+            for node in reader.elements("categories", "category") {
+                import_category(&mut reader.builder, node)?;
+            }
+            let datatypes = datatypes(reader.elements("datatypes", "datatype"));
+            let code_sets = code_sets(reader.elements("codeSets", "codeSet"))?;
+            let mut length_fields = Vec::new();
+            for node in reader.elements("fields", "field").filter(is_base) {
+                import_field(&mut reader.builder, node, &datatypes, &code_sets)?;
+                if let Some(length_id) = node.attribute("lengthId") {
+                    length_fields.push((parse_attribute::<usize>(node, "id")?, length_id));
+                }
+            }
+            for (data_tag, length_id) in length_fields {
+                let len_field_iid = field_iid(&reader.builder, length_id)?;
+                reader.builder.fields[len_field_iid as usize].associated_data_tag = Some(data_tag);
+            }
+            // Components and groups can reference each other in any order, so
+            // we import their layouts only after all of them are known.
+            for node in reader.elements("components", "component") {
+                let iid = import_component(&mut reader.builder, node, false)?;
+                reader.components.insert(id_and_scenario(node)?, iid);
+            }
+            for node in reader.elements("groups", "group") {
+                let iid = import_component(&mut reader.builder, node, true)?;
+                reader.groups.insert(id_and_scenario(node)?, iid);
+            }
+            for node in reader.elements("components", "component") {
+                let iid = reader.components[&id_and_scenario(node)?];
+                let layout_items = reader.import_layout_items(node)?;
+                reader.builder.components[iid as usize].layout_items = layout_items;
+            }
+            for node in reader.elements("groups", "group") {
+                let iid = reader.groups[&id_and_scenario(node)?];
+                let num_in_group = node
+                    .children()
+                    .find(|n| n.has_tag_name("numInGroup"))
+                    .ok_or_else(|| {
+                        ParseDictionaryError::InvalidData(format!(
+                            "Group {} has no <numInGroup>",
+                            node.attribute("name").unwrap_or_default()
+                        ))
+                    })?;
+                let group = LayoutItemData {
+                    required: false,
+                    kind: LayoutItemKindData::Group {
+                        len_field_iid: field_iid(&reader.builder, attribute(num_in_group, "id")?)?,
+                        items: reader.import_layout_items(node)?,
+                    },
+                    presence_rules: Vec::new(),
+                };
+                reader.builder.components[iid as usize].layout_items = vec![group];
+            }
+            for node in reader.elements("messages", "message") {
+                reader.import_message(node)?;
+            }
+            Ok(reader.builder.build())
+        }
+
+        /// Returns all `<tag>` elements within the (optional) `<parent_tag>`
+        /// element of the repository.
+        fn elements(
+            &self,
+            parent_tag: &'static str,
+            tag: &'static str,
+        ) -> impl Iterator<Item = roxmltree::Node<'a, 'a>> {
+            self.root
+                .children()
+                .filter(move |n| n.has_tag_name(parent_tag))
+                .flat_map(move |n| n.children().filter(move |c| c.has_tag_name(tag)))
+        }
+
+        fn import_message(&mut self, node: roxmltree::Node) -> ParseResult<InternalId> {
+            let category = node.attribute("category");
+            let category_iid = category_iid(&mut self.builder, category.unwrap_or_default());
+            let section_id = self.builder.categories[category_iid as usize]
+                .section_id
+                .clone()
+                .unwrap_or_default();
+            let layout_items = match node.children().find(|n| n.has_tag_name("structure")) {
+                Some(structure) => self.import_layout_items(structure)?,
+                None => LayoutItems::new(),
+            };
+            let message = MessageData {
+                component_id: parse_attribute(node, "id")?,
+                msg_type: attribute(node, "msgType")?.to_string(),
+                name: attribute(node, "name")?.to_string(),
+                category_iid,
+                section_id,
+                layout_items,
+                abbr_name: node.attribute("abbrName").map(str::to_string),
+                required: true,
+                description: documentation(node, "SYNOPSIS").unwrap_or_default(),
+                elaboration: documentation(node, "ELABORATION"),
+                pedigree: pedigree(node),
+                scenario: scenario(node).to_string(),
+            };
+            Ok(self.builder.add_message(message))
+        }
+
+        fn import_layout_items(&self, node: roxmltree::Node) -> ParseResult<LayoutItems> {
+            let mut items = LayoutItems::new();
+            for child in node.children().filter(|n| n.is_element()) {
+                let id = || parse_attribute(child, "id");
+                let kind = match child.tag_name().name() {
+                    "fieldRef" => LayoutItemKindData::Field {
+                        iid: field_iid(&self.builder, attribute(child, "id")?)?,
+                    },
+                    "componentRef" => LayoutItemKindData::Component {
+                        iid: lookup(&self.components, id()?, scenario(child), "component")?,
+                    },
+                    "groupRef" => LayoutItemKindData::Component {
+                        iid: lookup(&self.groups, id()?, scenario(child), "group")?,
+                    },
+                    // E.g. `<numInGroup>` and `<annotation>`.
+                    _ => continue,
+                };
+                let presence = match child.attribute("presence") {
+                    Some(name) => presence(name)?,
+                    None => Presence::Optional,
+                };
+                let presence_rules = child
+                    .children()
+                    .filter(|n| n.has_tag_name("rule"))
+                    .map(presence_rule)
+                    .collect::<ParseResult<_>>()?;
+                items.push(LayoutItemData {
+                    required: presence == Presence::Required,
+                    kind,
+                    presence_rules,
+                });
+            }
+            Ok(items)
+        }
+    }
+
+    /// Orchestra repositories of FIX versions are named e.g.
+    /// `FIX.5.0SP2_EP254`, while venues are free to choose any name. We try to
+    /// find out the underlying FIX version.
+    fn version(root: roxmltree::Node) -> String {
+        let fix_version = [root.attribute("version"), root.attribute("name")]
+            .into_iter()
+            .flatten()
+            .find(|s| s.starts_with("FIX"));
+        match fix_version {
+            Some(version) => normalize_version(version.split('_').next().unwrap()),
+            None => root.attribute("name").unwrap_or("FIX.???").to_string(),
+        }
+    }
+
+    fn is_base(node: &roxmltree::Node) -> bool {
+        scenario(*node) == BASE_SCENARIO
+    }
+
+    fn scenario<'a>(node: roxmltree::Node<'a, '_>) -> &'a str {
+        node.attribute("scenario").unwrap_or(BASE_SCENARIO)
+    }
+
+    fn id_and_scenario<'a>(node: roxmltree::Node<'a, '_>) -> ParseResult<(u32, &'a str)> {
+        Ok((parse_attribute(node, "id")?, scenario(node)))
+    }
+
+    fn attribute<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> ParseResult<&'a str> {
+        node.attribute(name).ok_or_else(|| {
+            ParseDictionaryError::InvalidData(format!(
+                "<{}> without '{}' attribute",
+                node.tag_name().name(),
+                name
+            ))
+        })
+    }
+
+    fn parse_attribute<T: std::str::FromStr>(node: roxmltree::Node, name: &str) -> ParseResult<T> {
+        let value = attribute(node, name)?;
+        value.parse().map_err(|_| {
+            ParseDictionaryError::InvalidData(format!("Invalid '{}' attribute: '{}'", name, value))
+        })
+    }
+
+    pub(super) fn optional_attribute<T: std::str::FromStr>(
+        node: roxmltree::Node,
+        name: &str,
+    ) -> ParseResult<Option<T>> {
+        match node.attribute(name) {
+            Some(_) => parse_attribute(node, name).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the text of all `<documentation>` elements with the given
+    /// `purpose`. Documentation without a purpose counts as a synopsis.
+    fn documentation(node: roxmltree::Node, purpose: &str) -> Option<String> {
+        let annotation = node.children().find(|n| n.has_tag_name("annotation"))?;
+        let text = annotation
+            .children()
+            .filter(|n| n.has_tag_name("documentation"))
+            .filter(|n| n.attribute("purpose").unwrap_or("SYNOPSIS") == purpose)
+            .flat_map(|n| {
+                n.descendants()
+                    .filter(|d| d.is_text())
+                    .filter_map(|d| d.text())
             })
+            .collect::<String>();
+        let text = text.trim();
+        if text.is_empty() {
+            None
+        } else {
+            Some(text.to_string())
         }
+    }
 
-        /// Returns the name adopted by QuickFIX for `self`.
-        pub fn to_quickfix_name(&self) -> &str {
-            match self {
-                FixDatatype::Int => "INT",
-                FixDatatype::Length => "LENGTH",
-                FixDatatype::Char => "CHAR",
-                FixDatatype::Boolean => "BOOLEAN",
-                FixDatatype::Float => "FLOAT",
-                FixDatatype::Amt => "AMT",
-                FixDatatype::Price => "PRICE",
-                FixDatatype::PriceOffset => "PRICEOFFSET",
-                FixDatatype::Qty => "QTY",
-                FixDatatype::Percentage => "PERCENTAGE",
-                FixDatatype::DayOfMonth => "DAYOFMONTH",
-                FixDatatype::NumInGroup => "NUMINGROUP",
-                FixDatatype::Language => "LANGUAGE",
-                FixDatatype::SeqNum => "SEQNUM",
-                FixDatatype::TagNum => "TAGNUM",
-                FixDatatype::String => "STRING",
-                FixDatatype::Data => "DATA",
-                FixDatatype::MonthYear => "MONTHYEAR",
-                FixDatatype::Currency => "CURRENCY",
-                FixDatatype::Exchange => "EXCHANGE",
-                FixDatatype::LocalMktDate => "LOCALMKTDATE",
-                FixDatatype::MultipleStringValue => "MULTIPLESTRINGVALUE",
-                FixDatatype::UtcTimeOnly => "UTCTIMEONLY",
-                FixDatatype::UtcTimestamp => "UTCTIMESTAMP",
-                FixDatatype::UtcDateOnly => "UTCDATEONLY",
-                FixDatatype::Country => "COUNTRY",
-                FixDatatype::MultipleCharValue => "MULTIPLECHARVALUE",
-                FixDatatype::XmlData => "XMLDATA",
+    fn presence(name: &str) -> ParseResult<Presence> {
+        Presence::from_orchestra_name(name).ok_or_else(|| {
+            ParseDictionaryError::InvalidData(format!("Invalid presence '{}'", name))
+        })
+    }
+
+    pub(super) fn presence_rule(node: roxmltree::Node) -> ParseResult<PresenceRuleData> {
+        let condition = node
+            .children()
+            .find(|n| n.has_tag_name("when"))
+            .and_then(|n| n.text())
+            .ok_or_else(|| {
+                ParseDictionaryError::InvalidData("<rule> without <when>".to_string())
+            })?;
+        Ok(PresenceRuleData {
+            name: node.attribute("name").map(str::to_string),
+            presence: presence(node.attribute("presence").unwrap_or("required"))?,
+            condition: condition.trim().to_string(),
+        })
+    }
+
+    fn lookup(
+        iids: &HashMap<(u32, &str), InternalId>,
+        id: u32,
+        scenario: &str,
+        kind: &str,
+    ) -> ParseResult<InternalId> {
+        iids.get(&(id, scenario)).copied().ok_or_else(|| {
+            ParseDictionaryError::InvalidData(format!(
+                "Unknown {} {} (scenario '{}')",
+                kind, id, scenario
+            ))
+        })
+    }
+
+    fn field_iid(builder: &DictionaryBuilder, id: &str) -> ParseResult<InternalId> {
+        id.parse()
+            .ok()
+            .and_then(|tag| builder.symbol(KeyRef::FieldByTag(tag)))
+            .copied()
+            .ok_or_else(|| ParseDictionaryError::InvalidData(format!("Unknown field '{}'", id)))
+    }
+
+    fn import_section(builder: &mut DictionaryBuilder, node: roxmltree::Node) -> ParseResult<()> {
+        builder.add_section(SectionData {
+            id: attribute(node, "name")?.to_string(),
+            name: attribute(node, "name")?.to_string(),
+            display_order: node
+                .attribute("displayOrder")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            fixml_filename: node
+                .attribute("FIXMLFileName")
+                .unwrap_or_default()
+                .to_string(),
+            description: documentation(node, "SYNOPSIS"),
+        });
+        Ok(())
+    }
+
+    fn import_category(builder: &mut DictionaryBuilder, node: roxmltree::Node) -> ParseResult<()> {
+        builder.add_category(CategoryData {
+            name: attribute(node, "name")?.to_string(),
+            fixml_filename: node
+                .attribute("FIXMLFileName")
+                .unwrap_or_default()
+                .to_string(),
+            section_id: node.attribute("section").map(str::to_string),
+        });
+        Ok(())
+    }
+
+    fn datatypes<'a>(
+        nodes: impl Iterator<Item = roxmltree::Node<'a, 'a>>,
+    ) -> HashMap<FixDatatype, DatatypeData> {
+        let mut datatypes = HashMap::new();
+        for node in nodes {
+            let name = node.attribute("name").unwrap_or_default();
+            let datatype = match datatype_from_name(name) {
+                Some(datatype) => datatype,
+                None => continue,
+            };
+            if datatypes.contains_key(&datatype) && datatype.name() != name {
+                continue;
             }
+            let data = DatatypeData {
+                datatype,
+                description: documentation(node, "SYNOPSIS").unwrap_or_default(),
+                examples: Vec::new(),
+            };
+            datatypes.insert(datatype, data);
         }
+        datatypes
+    }
 
-        /// Returns the name of `self`, character by character identical to the name
-        /// that appears in the official guidelines. **Generally** primitive datatypes
-        /// will use `snake_case` and non-primitive ones will have `PascalCase`, but
-        /// that's not true for every [`Datatype`](super::Datatype).
-        ///
-        /// # Examples
-        ///
-        /// ```
-        /// use fefix::dict::FixDatatype;
-        ///
-        /// assert_eq!(FixDatatype::Qty.name(), "Qty");
-        /// assert_eq!(FixDatatype::Float.name(), "float");
-        /// assert_eq!(FixDatatype::String.name(), "String");
-        /// ```
-        pub fn name(&self) -> &'static str {
-            // 1. Most primitive data types have `snake_case` names.
-            // 2. Most derivative data types have `PascalCase` names.
-            // 3. `data` and `String` ruin the party and mess it up.
-            //    Why, you ask? Oh, you sweet summer child. You'll learn soon enough
-            //    that nothing makes sense in FIX land.
-            match self {
-                FixDatatype::Int => "int",
-                FixDatatype::Length => "Length",
-                FixDatatype::Char => "char",
-                FixDatatype::Boolean => "Boolean",
-                FixDatatype::Float => "float",
-                FixDatatype::Amt => "Amt",
-                FixDatatype::Price => "Price",
-                FixDatatype::PriceOffset => "PriceOffset",
-                FixDatatype::Qty => "Qty",
-                FixDatatype::Percentage => "Percentage",
-                FixDatatype::DayOfMonth => "DayOfMonth",
-                FixDatatype::NumInGroup => "NumInGroup",
-                FixDatatype::Language => "Language",
-                FixDatatype::SeqNum => "SeqNum",
-                FixDatatype::TagNum => "TagNum",
-                FixDatatype::String => "String",
-                FixDatatype::Data => "data",
-                FixDatatype::MonthYear => "MonthYear",
-                FixDatatype::Currency => "Currency",
-                FixDatatype::Exchange => "Exchange",
-                FixDatatype::LocalMktDate => "LocalMktDate",
-                FixDatatype::MultipleStringValue => "MultipleStringValue",
-                FixDatatype::UtcTimeOnly => "UTCTimeOnly",
-                FixDatatype::UtcTimestamp => "UTCTimestamp",
-                FixDatatype::UtcDateOnly => "UTCDateOnly",
-                FixDatatype::Country => "Country",
-                FixDatatype::MultipleCharValue => "MultipleCharValue",
-                FixDatatype::XmlData => "XMLData",
+    /// Returns the underlying datatype and the codes of all code sets, by
+    /// name.
+    fn code_sets<'a>(
+        nodes: impl Iterator<Item = roxmltree::Node<'a, 'a>>,
+    ) -> ParseResult<HashMap<&'a str, (FixDatatype, Vec<FieldEnumData>)>> {
+        let mut code_sets = HashMap::new();
+        for node in nodes.filter(is_base) {
+            let type_name = attribute(node, "type")?;
+            let datatype = datatype_from_name(type_name).ok_or_else(|| {
+                ParseDictionaryError::InvalidData(format!("Unknown datatype '{}'", type_name))
+            })?;
+            let codes = node
+                .children()
+                .filter(|n| n.has_tag_name("code"))
+                .map(|code| {
+                    let name = attribute(code, "name")?;
+                    Ok(FieldEnumData {
+                        value: attribute(code, "value")?.to_string(),
+                        description: documentation(code, "SYNOPSIS")
+                            .unwrap_or_else(|| name.to_string()),
+                        symbolic_name: Some(name.to_string()),
+                        pedigree: pedigree(code),
+                    })
+                })
+                .collect::<ParseResult<_>>()?;
+            code_sets.insert(attribute(node, "name")?, (datatype, codes));
+        }
+        Ok(code_sets)
+    }
+
+    fn import_field(
+        builder: &mut DictionaryBuilder,
+        node: roxmltree::Node,
+        datatypes: &HashMap<FixDatatype, DatatypeData>,
+        code_sets: &HashMap<&str, (FixDatatype, Vec<FieldEnumData>)>,
+    ) -> ParseResult<InternalId> {
+        let tag = parse_attribute(node, "id")?;
+        let type_name = attribute(node, "type")?;
+        let (datatype, value_restrictions) = match code_sets.get(type_name) {
+            Some((datatype, codes)) => (*datatype, Some(codes.clone())),
+            None => {
+                let datatype = datatype_from_name(type_name).ok_or_else(|| {
+                    ParseDictionaryError::InvalidData(format!(
+                        "Unknown datatype '{}' of field {}",
+                        type_name, tag
+                    ))
+                })?;
+                (datatype, None)
+            }
+        };
+        let base_category_id = node
+            .attribute("baseCategory")
+            .and_then(|name| builder.symbol(KeyRef::CategoryByName(name)))
+            .map(|iid| *iid as usize);
+        let field = FieldData {
+            name: attribute(node, "name")?.to_string(),
+            tag,
+            data_type_iid: datatype_iid(builder, datatype, datatypes),
+            associated_data_tag: None,
+            value_restrictions,
+            abbr_name: node.attribute("abbrName").map(str::to_string),
+            base_category_id,
+            base_category_abbr_name: node.attribute("baseCategoryAbbrName").map(str::to_string),
+            required: true,
+            description: documentation(node, "SYNOPSIS"),
+            pedigree: pedigree(node),
+            constraints: FieldConstraints {
+                min: optional_attribute(node, "minInclusive")?,
+                max: optional_attribute(node, "maxInclusive")?,
+                ..FieldConstraints::default()
+            },
+        };
+        Ok(builder.add_field(field))
+    }
+
+    /// Imports a component or group with an empty layout.
+    fn import_component(
+        builder: &mut DictionaryBuilder,
+        node: roxmltree::Node,
+        is_group: bool,
+    ) -> ParseResult<InternalId> {
+        let component = ComponentData {
+            id: parse_attribute(node, "id")?,
+            component_type: FixmlComponentAttributes::Block {
+                is_repeating: is_group,
+                is_implicit: false,
+                is_optimized: false,
+            },
+            layout_items: LayoutItems::new(),
+            category_iid: category_iid(builder, node.attribute("category").unwrap_or_default()),
+            name: attribute(node, "name")?.to_string(),
+            abbr_name: node.attribute("abbrName").map(str::to_string),
+            description: documentation(node, "SYNOPSIS"),
+            pedigree: pedigree(node),
+            scenario: scenario(node).to_string(),
+        };
+        Ok(builder.add_component(component))
+    }
+
+    type ParseResult<T> = Result<T, ParseDictionaryError>;
+}
+
+mod diff {
+    use super::*;
+    use std::collections::HashMap;
+    use std::fmt::Write;
+
+    /// The differences between two [`Dictionary`]s. See [`Dictionary::diff`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DictionaryDiff {
+        /// The version string of the old [`Dictionary`].
+        pub old_version: String,
+        /// The version string of the new [`Dictionary`].
+        pub new_version: String,
+        /// Changed fields, sorted by tag.
+        pub fields: Vec<FieldDiff>,
+        /// Changed components, sorted by name.
+        pub components: Vec<LayoutDiff>,
+        /// Changed messages, sorted by `MsgType <35>`.
+        pub messages: Vec<LayoutDiff>,
+    }
+
+    /// A generic difference between an old and a new value.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Change<T> {
+        /// The value only exists in the new [`Dictionary`].
+        Added(T),
+        /// The value only exists in the old [`Dictionary`].
+        Removed(T),
+        /// The value exists in both, but it's different.
+        Modified { old: T, new: T },
+    }
+
+    /// The differences of a single field, identified by its tag.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FieldDiff {
+        /// The tag of the field.
+        pub tag: u32,
+        /// The name of the field, as found in the new [`Dictionary`] unless
+        /// removed.
+        pub name: String,
+        /// All changes to the field. Never empty.
+        pub changes: Vec<FieldChange>,
+    }
+
+    /// A change to a field.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum FieldChange {
+        /// The field is new and has this datatype.
+        Added(FixDatatype),
+        /// The field was removed.
+        Removed,
+        /// The field has a new name.
+        Renamed { old: String, new: String },
+        /// The field has a new datatype.
+        Datatype { old: FixDatatype, new: FixDatatype },
+        /// An enumerated value was added, removed, or its description
+        /// changed.
+        Enum {
+            value: String,
+            description: Change<String>,
+        },
+    }
+
+    /// The differences in the layout of a message or component.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct LayoutDiff {
+        /// The name of the message or component.
+        pub name: String,
+        /// The `MsgType <35>` of messages; `None` for components.
+        pub msg_type: Option<String>,
+        /// All changes to the layout. Never empty.
+        pub changes: Vec<LayoutChange>,
+    }
+
+    /// A change to the layout of a message or component.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum LayoutChange {
+        /// The whole message or component is new.
+        Added,
+        /// The whole message or component was removed.
+        Removed,
+        /// A layout item was added, removed, or its `required` flag changed.
+        /// Items within repeating groups are identified by their path, e.g.
+        /// `NoPartyIDs.PartyRole`. Reordering items is not a change.
+        Item {
+            path: String,
+            required: Change<bool>,
+        },
+    }
+
+    impl DictionaryDiff {
+        /// Returns `true` if there are no differences at all, ignoring
+        /// version strings.
+        pub fn is_empty(&self) -> bool {
+            self.fields.is_empty() && self.components.is_empty() && self.messages.is_empty()
+        }
+
+        /// Renders `self` as a Markdown document, for human review.
+        pub fn to_markdown(&self) -> String {
+            let mut markdown = String::new();
+            // Writing to a `String` never fails.
+            self.write_markdown(&mut markdown).unwrap();
+            markdown
+        }
+
+        fn write_markdown(&self, w: &mut String) -> fmt::Result {
+            writeln!(w, "# {} → {}", self.old_version, self.new_version)?;
+            if self.is_empty() {
+                writeln!(w)?;
+                writeln!(w, "No differences.")?;
+            }
+            if !self.fields.is_empty() {
+                writeln!(w)?;
+                writeln!(w, "## Fields")?;
+                writeln!(w)?;
+                for field in self.fields.iter() {
+                    write_field(w, field)?;
+                }
+            }
+            for (title, layouts) in [
+                ("Components", &self.components),
+                ("Messages", &self.messages),
+            ] {
+                if !layouts.is_empty() {
+                    writeln!(w)?;
+                    writeln!(w, "## {}", title)?;
+                    writeln!(w)?;
+                    for layout in layouts.iter() {
+                        write_layout(w, layout)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn write_field(w: &mut String, field: &FieldDiff) -> fmt::Result {
+        let name = format!("`{} <{}>`", field.name, field.tag);
+        match field.changes.as_slice() {
+            [FieldChange::Added(datatype)] => {
+                return writeln!(w, "- **Added** {} (`{}`)", name, datatype.name());
+            }
+            [FieldChange::Removed] => return writeln!(w, "- **Removed** {}", name),
+            _ => {}
+        }
+        writeln!(w, "- **Changed** {}:", name)?;
+        for change in field.changes.iter() {
+            write!(w, "  - ")?;
+            match change {
+                FieldChange::Added(_) | FieldChange::Removed => unreachable!(),
+                FieldChange::Renamed { old, new } => {
+                    writeln!(w, "Renamed from `{}` to `{}`", old, new)?
+                }
+                FieldChange::Datatype { old, new } => writeln!(
+                    w,
+                    "Datatype changed from `{}` to `{}`",
+                    old.name(),
+                    new.name()
+                )?,
+                FieldChange::Enum { value, description } => match description {
+                    Change::Added(description) => {
+                        writeln!(w, "Added value `{}`: {}", value, one_line(description))?
+                    }
+                    Change::Removed(description) => {
+                        writeln!(w, "Removed value `{}`: {}", value, one_line(description))?
+                    }
+                    Change::Modified { old, new } => writeln!(
+                        w,
+                        "Value `{}` changed from \"{}\" to \"{}\"",
+                        value,
+                        one_line(old),
+                        one_line(new)
+                    )?,
+                },
             }
         }
+        Ok(())
+    }
 
-        /// Returns `true` if and only if `self` is a "base type", i.e. a primitive;
-        /// returns `false` otherwise.
-        ///
-        /// # Examples
-        ///
-        /// ```
-        /// use fefix::dict::FixDatatype;
-        ///
-        /// assert_eq!(FixDatatype::Float.is_base_type(), true);
-        /// assert_eq!(FixDatatype::Price.is_base_type(), false);
-        /// ```
-        pub fn is_base_type(&self) -> bool {
-            matches!(self, Self::Char | Self::Float | Self::Int | Self::String)
+    fn write_layout(w: &mut String, layout: &LayoutDiff) -> fmt::Result {
+        let name = match &layout.msg_type {
+            Some(msg_type) => format!("`{}` (`{}`)", layout.name, msg_type),
+            None => format!("`{}`", layout.name),
+        };
+        match layout.changes.as_slice() {
+            [LayoutChange::Added] => return writeln!(w, "- **Added** {}", name),
+            [LayoutChange::Removed] => return writeln!(w, "- **Removed** {}", name),
+            _ => {}
         }
-
-        /// Returns the primitive [`Datatype`](super::Datatype) from which `self` is derived. If
-        /// `self` is primitive already, returns `self` unchanged.
-        ///
-        /// # Examples
-        ///
-        /// ```
-        /// use fefix::dict::FixDatatype;
-        ///
-        /// assert_eq!(FixDatatype::Float.base_type(), FixDatatype::Float);
-        /// assert_eq!(FixDatatype::Price.base_type(), FixDatatype::Float);
-        /// ```
-        pub fn base_type(&self) -> Self {
-            let dt = match self {
-                Self::Char | Self::Boolean => Self::Char,
-                Self::Float
-                | Self::Amt
-                | Self::Price
-                | Self::PriceOffset
-                | Self::Qty
-                | Self::Percentage => Self::Float,
-                Self::Int
-                | Self::DayOfMonth
-                | Self::Length
-                | Self::NumInGroup
-                | Self::SeqNum
-                | Self::TagNum => Self::Int,
-                _ => Self::String,
-            };
-            debug_assert!(dt.is_base_type());
-            dt
+        writeln!(w, "- **Changed** {}:", name)?;
+        let presence = |required: &bool| if *required { "required" } else { "optional" };
+        for change in layout.changes.iter() {
+            write!(w, "  - ")?;
+            match change {
+                LayoutChange::Added | LayoutChange::Removed => unreachable!(),
+                LayoutChange::Item { path, required } => match required {
+                    Change::Added(required) => {
+                        writeln!(w, "Added `{}` ({})", path, presence(required))?
+                    }
+                    Change::Removed(required) => {
+                        writeln!(w, "Removed `{}` ({})", path, presence(required))?
+                    }
+                    Change::Modified { new, .. } => {
+                        writeln!(w, "`{}` is now {}", path, presence(new))?
+                    }
+                },
+            }
         }
+        Ok(())
+    }
 
-        /// Returns an [`Iterator`] over all variants of
-        /// [`Datatype`](super::Datatype).
-        pub fn iter_all() -> impl Iterator<Item = Self> {
-            <Self as IntoEnumIterator>::iter()
+    fn one_line(s: &str) -> String {
+        s.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    pub fn diff(old: &Dictionary, new: &Dictionary) -> DictionaryDiff {
+        DictionaryDiff {
+            old_version: old.get_version().to_string(),
+            new_version: new.get_version().to_string(),
+            fields: diff_fields(old, new),
+            components: diff_components(old, new),
+            messages: diff_messages(old, new),
         }
     }
 
-    #[cfg(test)]
-    mod test {
-        use super::*;
+    fn diff_fields(old: &Dictionary, new: &Dictionary) -> Vec<FieldDiff> {
+        let mut tags: Vec<u32> = old
+            .iter_fields()
+            .chain(new.iter_fields())
+            .map(|field| field.tag().get() as u32)
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+        let mut diffs = Vec::new();
+        for tag in tags {
+            let (name, changes) = match (old.field_by_tag(tag), new.field_by_tag(tag)) {
+                (Some(old), Some(new)) => (new.name().to_string(), field_changes(&old, &new)),
+                (None, Some(new)) => (
+                    new.name().to_string(),
+                    vec![FieldChange::Added(new.fix_datatype())],
+                ),
+                (Some(old), None) => (old.name().to_string(), vec![FieldChange::Removed]),
+                (None, None) => unreachable!(),
+            };
+            if !changes.is_empty() {
+                diffs.push(FieldDiff { tag, name, changes });
+            }
+        }
+        diffs
+    }
 
-        #[test]
-        fn iter_all_unique() {
-            let as_vec = FixDatatype::iter_all();
-            let as_set = FixDatatype::iter_all();
-            assert_eq!(as_vec.count(), as_set.count());
+    fn field_changes(old: &Field, new: &Field) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        if old.name() != new.name() {
+            changes.push(FieldChange::Renamed {
+                old: old.name().to_string(),
+                new: new.name().to_string(),
+            });
+        }
+        if old.fix_datatype() != new.fix_datatype() {
+            changes.push(FieldChange::Datatype {
+                old: old.fix_datatype(),
+                new: new.fix_datatype(),
+            });
+        }
+        let enums = |field: &Field| -> Vec<(String, String)> {
+            field
+                .enums()
+                .map(|enums| {
+                    enums
+                        .map(|e| (e.value().to_string(), e.description().to_string()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let (old_enums, new_enums) = (enums(old), enums(new));
+        let old_descriptions: HashMap<&str, &str> = old_enums
+            .iter()
+            .map(|(value, description)| (value.as_str(), description.as_str()))
+            .collect();
+        let new_descriptions: HashMap<&str, &str> = new_enums
+            .iter()
+            .map(|(value, description)| (value.as_str(), description.as_str()))
+            .collect();
+        for (value, description) in new_enums.iter() {
+            let description = match old_descriptions.get(value.as_str()) {
+                None => Change::Added(description.clone()),
+                Some(old) if old != description => Change::Modified {
+                    old: old.to_string(),
+                    new: description.clone(),
+                },
+                Some(_) => continue,
+            };
+            changes.push(FieldChange::Enum {
+                value: value.clone(),
+                description,
+            });
         }
+        for (value, description) in old_enums.iter() {
+            if !new_descriptions.contains_key(value.as_str()) {
+                changes.push(FieldChange::Enum {
+                    value: value.clone(),
+                    description: Change::Removed(description.clone()),
+                });
+            }
+        }
+        changes
+    }
 
-        #[test]
-        fn more_than_20_datatypes() {
-            // According to the official documentation, FIX has "about 20 data
-            // types". Including recent revisions, we should well exceed that
-            // number.
-            assert!(FixDatatype::iter_all().count() > 20);
+    fn diff_components(old: &Dictionary, new: &Dictionary) -> Vec<LayoutDiff> {
+        let mut names: Vec<String> = old
+            .iter_components()
+            .chain(new.iter_components())
+            .filter(|component| component.scenario() == BASE_SCENARIO)
+            .map(|component| component.name().to_string())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        let mut diffs = Vec::new();
+        for name in names {
+            let changes = match (old.component_by_name(&name), new.component_by_name(&name)) {
+                (Some(old), Some(new)) => {
+                    layout_changes(&flatten_layout(old.items()), &flatten_layout(new.items()))
+                }
+                (None, _) => vec![LayoutChange::Added],
+                (_, None) => vec![LayoutChange::Removed],
+            };
+            if !changes.is_empty() {
+                diffs.push(LayoutDiff {
+                    name,
+                    msg_type: None,
+                    changes,
+                });
+            }
         }
+        diffs
+    }
 
-        #[test]
-        fn names_are_unique() {
-            let as_vec = FixDatatype::iter_all().map(|dt| dt.name());
-            let as_set = FixDatatype::iter_all().map(|dt| dt.name());
-            assert_eq!(as_vec.count(), as_set.count());
+    fn diff_messages(old: &Dictionary, new: &Dictionary) -> Vec<LayoutDiff> {
+        let mut msg_types: Vec<String> = old
+            .iter_messages()
+            .chain(new.iter_messages())
+            .filter(|message| message.scenario() == BASE_SCENARIO)
+            .map(|message| message.msg_type().to_string())
+            .collect();
+        msg_types.sort_unstable();
+        msg_types.dedup();
+        let mut diffs = Vec::new();
+        for msg_type in msg_types {
+            let (name, changes) = match (
+                old.message_by_msgtype(&msg_type),
+                new.message_by_msgtype(&msg_type),
+            ) {
+                (Some(old), Some(new)) => (
+                    new.name().to_string(),
+                    layout_changes(&flatten_layout(old.layout()), &flatten_layout(new.layout())),
+                ),
+                (None, Some(new)) => (new.name().to_string(), vec![LayoutChange::Added]),
+                (Some(old), None) => (old.name().to_string(), vec![LayoutChange::Removed]),
+                (None, None) => unreachable!(),
+            };
+            if !changes.is_empty() {
+                diffs.push(LayoutDiff {
+                    name,
+                    msg_type: Some(msg_type),
+                    changes,
+                });
+            }
         }
+        diffs
+    }
 
-        #[test]
-        fn base_type_is_itself() {
-            for dt in FixDatatype::iter_all() {
-                if dt.is_base_type() {
-                    assert_eq!(dt.base_type(), dt);
-                } else {
-                    assert_ne!(dt.base_type(), dt);
-                }
+    /// Returns the paths and `required` flags of all items in a layout,
+    /// including those within repeating groups.
+    fn flatten_layout<'a>(items: impl Iterator<Item = LayoutItem<'a>>) -> Vec<(String, bool)> {
+        let mut flattened = Vec::new();
+        flatten_layout_items(items, "", &mut flattened);
+        flattened
+    }
+
+    fn flatten_layout_items<'a>(
+        items: impl Iterator<Item = LayoutItem<'a>>,
+        prefix: &str,
+        flattened: &mut Vec<(String, bool)>,
+    ) {
+        for item in items {
+            let path = if prefix.is_empty() {
+                item.tag_text().to_string()
+            } else {
+                format!("{}.{}", prefix, item.tag_text())
+            };
+            flattened.push((path.clone(), item.required()));
+            if let LayoutItemKind::Group(_, items) = item.kind() {
+                flatten_layout_items(items.into_iter(), &path, flattened);
             }
         }
+    }
 
-        #[test]
-        fn base_type_is_actually_base_type() {
-            for dt in FixDatatype::iter_all() {
-                assert!(dt.base_type().is_base_type());
+    fn layout_changes(old: &[(String, bool)], new: &[(String, bool)]) -> Vec<LayoutChange> {
+        let old_items: HashMap<&str, bool> = old.iter().map(|(p, r)| (p.as_str(), *r)).collect();
+        let new_items: HashMap<&str, bool> = new.iter().map(|(p, r)| (p.as_str(), *r)).collect();
+        let mut changes = Vec::new();
+        for (path, required) in new.iter() {
+            let required = match old_items.get(path.as_str()) {
+                None => Change::Added(*required),
+                Some(old) if old != required => Change::Modified {
+                    old: *old,
+                    new: *required,
+                },
+                Some(_) => continue,
+            };
+            changes.push(LayoutChange::Item {
+                path: path.clone(),
+                required,
+            });
+        }
+        for (path, required) in old.iter() {
+            if !new_items.contains_key(path.as_str()) {
+                changes.push(LayoutChange::Item {
+                    path: path.clone(),
+                    required: Change::Removed(*required),
+                });
             }
         }
+        changes
     }
 }
 
-/// A field is identified by a unique tag number and a name. Each field in a
-/// message is associated with a value.
-#[derive(Clone, Debug)]
-struct FieldData {
-    /// A human readable string representing the name of the field.
-    name: String,
-    /// **Primary key.** A positive integer representing the unique
-    /// identifier for this field type.
-    tag: u32,
-    /// The datatype of the field.
-    data_type_iid: InternalId,
-    /// The associated data field. If given, this field represents the length of
-    /// the referenced data field
-    associated_data_tag: Option<usize>,
-    value_restrictions: Option<Vec<FieldEnumData>>,
-    /// Abbreviated form of the Name, typically to specify the element name when
-    /// the field is used in an XML message. Can be overridden by BaseCategory /
-    /// BaseCategoryAbbrName.
-    abbr_name: Option<String>,
-    /// Specifies the base message category when field is used in an XML message.
-    base_category_id: Option<usize>,
-    /// If BaseCategory is specified, this is the XML element identifier to use
-    /// for this field, overriding AbbrName.
-    base_category_abbr_name: Option<String>,
-    /// Indicates whether the field is required in an XML message.
-    required: bool,
-    description: Option<String>,
-}
+mod overlay {
+    use super::*;
 
-#[derive(Clone, Debug)]
-struct FieldEnumData {
-    value: String,
-    description: String,
-}
+    /// Applies the overlay file `document` to `editor`. See
+    /// [`DictionaryEditor::apply_overlay`] for the format.
+    pub fn apply(editor: &mut DictionaryEditor, document: &roxmltree::Document) -> ParseResult<()> {
+        let root = document.root_element();
+        if !root.has_tag_name("fix") {
+            return Err(ParseDictionaryError::InvalidFormat);
+        }
+        if root.attribute("type").is_some() {
+            editor.set_version(quickfix::version(root)?);
+        }
+        let section = |tag| root.children().find(|n| n.has_tag_name(tag));
+        if let Some(node) = section("fields") {
+            for child in elements(node) {
+                import_field(editor, child)?;
+            }
+        }
+        if let Some(node) = section("components") {
+            // Components can reference each other regardless of their order,
+            // so we must first declare all of them.
+            for child in elements(node) {
+                let name = attribute(child, "name")?;
+                if editor
+                    .builder
+                    .symbol(KeyRef::ComponentByName(name))
+                    .is_none()
+                {
+                    let category = child.attribute("category").unwrap_or("app");
+                    editor.add_component(name, category, Vec::new())?;
+                }
+            }
+            for child in elements(node) {
+                let name = attribute(child, "name")?;
+                import_layout(editor, LayoutTarget::Component(name), child)?;
+            }
+        }
+        if let Some(node) = section("groups") {
+            for child in elements(node) {
+                let name = attribute(child, "name")?;
+                import_layout(editor, LayoutTarget::Group(name), child)?;
+            }
+        }
+        if let Some(node) = section("header") {
+            import_layout(editor, LayoutTarget::Component("StandardHeader"), node)?;
+        }
+        if let Some(node) = section("trailer") {
+            import_layout(editor, LayoutTarget::Component("StandardTrailer"), node)?;
+        }
+        if let Some(node) = section("messages") {
+            for child in elements(node) {
+                import_message(editor, child)?;
+            }
+        }
+        if let Some(node) = section("rules") {
+            for child in elements(node) {
+                import_rules(editor, child)?;
+            }
+        }
+        Ok(())
+    }
 
-/// A limitation imposed on the value of a specific FIX [`Field`].  Also known as
-/// "code set".
-#[derive(Debug)]
-pub struct FieldEnum<'a>(&'a Dictionary, &'a FieldEnumData);
+    fn import_rules(editor: &mut DictionaryEditor, node: roxmltree::Node) -> ParseResult<()> {
+        let target = match node.tag_name().name() {
+            "message" => LayoutTarget::Message(attribute(node, "msgtype")?),
+            "component" => LayoutTarget::Component(attribute(node, "name")?),
+            "group" => LayoutTarget::Group(attribute(node, "name")?),
+            "header" => LayoutTarget::Component("StandardHeader"),
+            "trailer" => LayoutTarget::Component("StandardTrailer"),
+            _ => return Err(ParseDictionaryError::InvalidFormat),
+        };
+        for child in elements(node) {
+            if !child.has_tag_name("rule") {
+                return Err(ParseDictionaryError::InvalidFormat);
+            }
+            let rule = orchestra::presence_rule(child)?;
+            let mut spec = PresenceRuleSpec::new(rule.presence, rule.condition);
+            if let Some(name) = rule.name {
+                spec = spec.with_name(name);
+            }
+            editor.add_presence_rule(target, attribute(child, "item")?, spec)?;
+        }
+        Ok(())
+    }
 
-impl<'a> FieldEnum<'a> {
-    /// Returns the string representation of this field variant.
-    pub fn value(&self) -> &str {
-        &self.1.value[..]
+    fn import_field(editor: &mut DictionaryEditor, node: roxmltree::Node) -> ParseResult<()> {
+        let tag = attribute(node, "number")?
+            .parse()
+            .map_err(|_| ParseDictionaryError::InvalidFormat)?;
+        let datatype = match node.attribute("type") {
+            Some(name) => Some(FixDatatype::from_quickfix_name(name).ok_or_else(|| {
+                ParseDictionaryError::InvalidData(format!("Invalid type '{}'", name))
+            })?),
+            None => None,
+        };
+        match editor.builder.symbol(KeyRef::FieldByTag(tag)).copied() {
+            Some(iid) => {
+                let existing_name = &editor.builder.fields[iid as usize].name;
+                if let Some(name) = node.attribute("name").filter(|n| n != existing_name) {
+                    return Err(ParseDictionaryError::InvalidData(format!(
+                        "Field <{}> is named '{}', not '{}'",
+                        tag, existing_name, name
+                    )));
+                }
+                if let Some(datatype) = datatype {
+                    editor.set_field_datatype(tag, datatype)?;
+                }
+            }
+            None => {
+                let datatype = datatype.ok_or_else(|| {
+                    ParseDictionaryError::InvalidData(format!("No type for field <{}>", tag))
+                })?;
+                editor.add_field(tag, attribute(node, "name")?, datatype)?;
+            }
+        }
+        let iid = editor.field_iid_by_tag(tag)?;
+        let mut constraints = editor.builder.fields[iid as usize].constraints.clone();
+        if let Some(min) = orchestra::optional_attribute(node, "min")? {
+            constraints.min = Some(min);
+        }
+        if let Some(max) = orchestra::optional_attribute(node, "max")? {
+            constraints.max = Some(max);
+        }
+        if let Some(max_length) = orchestra::optional_attribute(node, "maxlength")? {
+            constraints.max_length = Some(max_length);
+        }
+        if let Some(pattern) = node.attribute("pattern") {
+            constraints.pattern = Some(pattern.to_string());
+        }
+        editor.set_field_constraints(tag, constraints)?;
+        for child in elements(node) {
+            if !child.has_tag_name("value") {
+                return Err(ParseDictionaryError::InvalidFormat);
+            }
+            editor.add_field_enum(
+                tag,
+                attribute(child, "enum")?,
+                attribute(child, "description")?,
+            )?;
+        }
+        Ok(())
     }
 
-    /// Returns the documentation description for `self`.
-    pub fn description(&self) -> &str {
-        &self.1.description[..]
+    fn import_message(editor: &mut DictionaryEditor, node: roxmltree::Node) -> ParseResult<()> {
+        let msg_type = attribute(node, "msgtype")?;
+        match editor
+            .builder
+            .symbol(KeyRef::MessageByMsgType(msg_type))
+            .copied()
+        {
+            Some(iid) => {
+                let existing_name = &editor.builder.messages[iid as usize].name;
+                if let Some(name) = node.attribute("name").filter(|n| n != existing_name) {
+                    return Err(ParseDictionaryError::InvalidData(format!(
+                        "Message '{}' is named '{}', not '{}'",
+                        msg_type, existing_name, name
+                    )));
+                }
+                import_layout(editor, LayoutTarget::Message(msg_type), node)
+            }
+            None => {
+                editor.add_message(
+                    attribute(node, "name")?,
+                    msg_type,
+                    attribute(node, "msgcat")?,
+                    layout_item_specs(node)?,
+                )?;
+                Ok(())
+            }
+        }
     }
-}
 
-/// A field is the most granular message structure abstraction. It carries a
-/// specific business meaning as described by the FIX specifications. The data
-/// domain of a [`Field`] is either a [`Datatype`] or a "code set", i.e.
-/// enumeration.
-#[derive(Debug, Copy, Clone)]
-pub struct Field<'a>(&'a Dictionary, &'a FieldData);
+    /// Replaces or, with `extend='Y'`, edits the layout of `target`.
+    fn import_layout(
+        editor: &mut DictionaryEditor,
+        target: LayoutTarget,
+        node: roxmltree::Node,
+    ) -> ParseResult<()> {
+        if node.attribute("extend") != Some("Y") {
+            editor.set_layout(target, layout_item_specs(node)?)?;
+            return Ok(());
+        }
+        for child in elements(node) {
+            if child.has_tag_name("remove") {
+                editor.remove_layout_item(target, attribute(child, "name")?)?;
+            } else {
+                editor.push_layout_item(target, layout_item_spec(child)?)?;
+            }
+        }
+        Ok(())
+    }
 
-impl<'a> Field<'a> {
-    pub fn doc_url_onixs(&self, version: &str) -> String {
-        let v = match version {
-            "FIX.4.0" => "4.0",
-            "FIX.4.1" => "4.1",
-            "FIX.4.2" => "4.2",
-            "FIX.4.3" => "4.3",
-            "FIX.4.4" => "4.4",
-            "FIX.5.0" => "5.0",
-            "FIX.5.0SP1" => "5.0.SP1",
-            "FIX.5.0SP2" => "5.0.SP2",
-            "FIXT.1.1" => "FIXT.1.1",
-            s => s,
-        };
-        format!(
-            "https://www.onixs.biz/fix-dictionary/{}/tagNum_{}.html",
-            v,
-            self.1.tag.to_string().as_str()
-        )
+    fn layout_item_specs(node: roxmltree::Node) -> ParseResult<Vec<LayoutItemSpec>> {
+        elements(node).map(layout_item_spec).collect()
+    }
+
+    fn layout_item_spec(node: roxmltree::Node) -> ParseResult<LayoutItemSpec> {
+        let name = attribute(node, "name")?;
+        let required = node.attribute("required") == Some("Y");
+        match node.tag_name().name() {
+            "field" => Ok(LayoutItemSpec::field(name, required)),
+            "component" => Ok(LayoutItemSpec::component(name, required)),
+            "group" => Ok(LayoutItemSpec::group(
+                name,
+                required,
+                layout_item_specs(node)?,
+            )),
+            _ => Err(ParseDictionaryError::InvalidFormat),
+        }
     }
 
-    /// Returns the [`FixDatatype`] of `self`.
-    pub fn fix_datatype(&self) -> FixDatatype {
-        self.data_type().basetype()
+    fn elements<'a, 'input>(
+        node: roxmltree::Node<'a, 'input>,
+    ) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+        node.children().filter(|n| n.is_element())
     }
 
-    /// Returns the name of `self`. Field names are unique across each FIX
-    /// [`Dictionary`].
-    pub fn name(&self) -> &str {
-        self.1.name.as_str()
+    fn attribute<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> ParseResult<&'a str> {
+        node.attribute(name).ok_or_else(|| {
+            ParseDictionaryError::InvalidData(format!(
+                "<{}> has no '{}' attribute",
+                node.tag_name().name(),
+                name
+            ))
+        })
     }
 
-    /// Returns the numeric tag of `self`. Field tags are unique across each FIX
-    /// [`Dictionary`].
-    pub fn tag(&self) -> TagU16 {
-        TagU16::new(self.1.tag as u16).unwrap()
+    impl From<EditDictionaryError> for ParseDictionaryError {
+        fn from(err: EditDictionaryError) -> Self {
+            ParseDictionaryError::InvalidData(err.to_string())
+        }
     }
 
-    /// In case this field allows any value, it returns `None`; otherwise; it
-    /// returns an [`Iterator`] of all allowed values.
-    pub fn enums(&self) -> Option<impl Iterator<Item = FieldEnum>> {
-        self.1
-            .value_restrictions
-            .as_ref()
-            .map(move |v| v.iter().map(move |f| FieldEnum(self.0, f)))
+    type ParseResult<T> = Result<T, ParseDictionaryError>;
+}
+
+mod binary {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    const MAGIC: &[u8] = b"FEFIXDICT";
+    const FORMAT_VERSION: u32 = 2;
+
+    type BinaryResult<T> = Result<T, ParseDictionaryError>;
+
+    pub fn write(dict: &Dictionary) -> Vec<u8> {
+        let data = dict.inner.as_ref();
+        let mut w = Vec::new();
+        w.extend_from_slice(MAGIC);
+        FORMAT_VERSION.write(&mut w);
+        data.version.write(&mut w);
+        // Hash maps have no stable iteration order, so symbols are sorted to
+        // get the same bytes for the same dictionary.
+        let mut symbols: Vec<Vec<u8>> = data
+            .symbol_table
+            .iter()
+            .map(|(key, iid)| {
+                let mut bytes = Vec::new();
+                key.write(&mut bytes);
+                iid.write(&mut bytes);
+                bytes
+            })
+            .collect();
+        symbols.sort_unstable();
+        symbols.len().write(&mut w);
+        for symbol in symbols {
+            w.extend_from_slice(&symbol);
+        }
+        data.abbreviations.write(&mut w);
+        data.data_types.write(&mut w);
+        data.fields.write(&mut w);
+        data.components.write(&mut w);
+        data.messages.write(&mut w);
+        data.categories.write(&mut w);
+        data.sections.write(&mut w);
+        data.header.write(&mut w);
+        w
     }
 
-    /// Returns the [`Datatype`] of `self`.
-    pub fn data_type(&self) -> Datatype {
-        let data = self
-            .0
-            .inner
-            .data_types
-            .get(self.1.data_type_iid as usize)
-            .unwrap();
-        Datatype(self.0, data)
+    pub fn read(bytes: &[u8]) -> BinaryResult<Dictionary> {
+        let mut r = Reader(bytes);
+        if r.take(MAGIC.len())? != MAGIC || u32::read(&mut r)? != FORMAT_VERSION {
+            return Err(ParseDictionaryError::InvalidFormat);
+        }
+        let version = String::read(&mut r)?;
+        let mut builder = DictionaryBuilder::new(version);
+        for _ in 0..usize::read(&mut r)? {
+            let key = Key::read(&mut r)?;
+            let iid = InternalId::read(&mut r)?;
+            builder.symbol_table.insert(key, iid);
+        }
+        builder.abbreviations = Binary::read(&mut r)?;
+        builder.data_types = Binary::read(&mut r)?;
+        builder.fields = Binary::read(&mut r)?;
+        builder.components = Binary::read(&mut r)?;
+        builder.messages = Binary::read(&mut r)?;
+        builder.categories = Binary::read(&mut r)?;
+        builder.sections = Binary::read(&mut r)?;
+        builder.header = Binary::read(&mut r)?;
+        if !r.0.is_empty() {
+            return Err(invalid_data("trailing bytes"));
+        }
+        check_internal_ids(&builder)?;
+        // Data fields are linked already, so there's no need to go through
+        // `DictionaryBuilder::build`.
+        Ok(Dictionary {
+            inner: Arc::new(DictionaryData {
+                version: builder.version,
+                symbol_table: builder.symbol_table,
+                abbreviations: builder.abbreviations,
+                data_types: builder.data_types,
+                fields: builder.fields,
+                components: builder.components,
+                messages: builder.messages,
+                categories: builder.categories,
+                sections: builder.sections,
+                header: builder.header,
+                lookup_tables: OnceCell::new(),
+            }),
+        })
     }
-}
 
-impl<'a> IsFieldDefinition for Field<'a> {
-    fn name(&self) -> &str {
-        self.1.name.as_str()
+    /// Makes sure that all internal IDs point to existing entities, so that
+    /// corrupted bytes can't cause panics later on.
+    fn check_internal_ids(builder: &DictionaryBuilder) -> BinaryResult<()> {
+        let check = |iid: InternalId, len: usize, what: &str| {
+            if (iid as usize) < len {
+                Ok(())
+            } else {
+                Err(invalid_data(&format!("unknown {} #{}", what, iid)))
+            }
+        };
+        let check_items = |items: &[LayoutItemData]| {
+            for_each_layout_item(items, &mut |item| match &item.kind {
+                LayoutItemKindData::Component { iid } => {
+                    check(*iid, builder.components.len(), "component")
+                }
+                LayoutItemKindData::Group { len_field_iid, .. } => {
+                    check(*len_field_iid, builder.fields.len(), "field")
+                }
+                LayoutItemKindData::Field { iid } => check(*iid, builder.fields.len(), "field"),
+            })
+        };
+        for field in builder.fields.iter() {
+            check(field.data_type_iid, builder.data_types.len(), "datatype")?;
+            if let Some(Err(_)) = field.constraints.pattern_regex() {
+                return Err(invalid_data("invalid pattern"));
+            }
+        }
+        for component in builder.components.iter() {
+            check(component.category_iid, builder.categories.len(), "category")?;
+            check_items(&component.layout_items)?;
+        }
+        for message in builder.messages.iter() {
+            check(message.category_iid, builder.categories.len(), "category")?;
+            check_items(&message.layout_items)?;
+        }
+        for (key, iid) in builder.symbol_table.iter() {
+            let len = match key {
+                Key::Abbreviation(_) => builder.abbreviations.len(),
+                Key::CategoryByName(_) => builder.categories.len(),
+                Key::ComponentByName(_) => builder.components.len(),
+                Key::DatatypeByName(_) => builder.data_types.len(),
+                Key::FieldByTag(_) | Key::FieldByName(_) => builder.fields.len(),
+                Key::MessageByName(_) | Key::MessageByMsgType(_) => builder.messages.len(),
+                Key::SectionById(_) => builder.sections.len(),
+            };
+            check(*iid, len, "symbol")?;
+        }
+        Ok(())
     }
 
-    fn tag(&self) -> TagU16 {
-        TagU16::new(self.1.tag as u16).expect("Invalid FIX tag (0)")
+    fn for_each_layout_item(
+        items: &[LayoutItemData],
+        f: &mut impl FnMut(&LayoutItemData) -> BinaryResult<()>,
+    ) -> BinaryResult<()> {
+        for item in items {
+            f(item)?;
+            if let LayoutItemKindData::Group { items, .. } = &item.kind {
+                for_each_layout_item(items, f)?;
+            }
+        }
+        Ok(())
     }
 
-    fn location(&self) -> FieldLocation {
-        FieldLocation::Body // FIXME
+    fn invalid_data(reason: &str) -> ParseDictionaryError {
+        ParseDictionaryError::InvalidData(format!("Invalid binary dictionary: {}.", reason))
     }
-}
 
-#[derive(Clone, Debug)]
-#[allow(dead_code)]
-enum LayoutItemKindData {
-    Component {
-        iid: InternalId,
-    },
-    Group {
-        len_field_iid: u32,
-        items: Vec<LayoutItemData>,
-    },
-    Field {
-        iid: InternalId,
-    },
-}
+    struct Reader<'a>(&'a [u8]);
 
-#[derive(Clone, Debug)]
-struct LayoutItemData {
-    required: bool,
-    kind: LayoutItemKindData,
-}
+    impl<'a> Reader<'a> {
+        fn take(&mut self, len: usize) -> BinaryResult<&'a [u8]> {
+            if len > self.0.len() {
+                return Err(ParseDictionaryError::InvalidFormat);
+            }
+            let (bytes, rest) = self.0.split_at(len);
+            self.0 = rest;
+            Ok(bytes)
+        }
 
-pub trait IsFieldDefinition {
-    /// Returns the FIX tag associated with `self`.
-    fn tag(&self) -> TagU16;
+        fn byte(&mut self) -> BinaryResult<u8> {
+            Ok(self.take(1)?[0])
+        }
+    }
 
-    /// Returns the official, ASCII, human-readable name associated with `self`.
-    fn name(&self) -> &str;
+    /// Values with a binary representation. Integers are LEB128-encoded, and
+    /// strings and sequences are prefixed by their length.
+    trait Binary: Sized {
+        fn write(&self, w: &mut Vec<u8>);
+        fn read(r: &mut Reader) -> BinaryResult<Self>;
+    }
 
-    /// Returns the field location of `self`.
-    fn location(&self) -> FieldLocation;
-}
+    impl Binary for u64 {
+        fn write(&self, w: &mut Vec<u8>) {
+            let mut n = *self;
+            while n >= 0x80 {
+                w.push((n as u8) | 0x80);
+                n >>= 7;
+            }
+            w.push(n as u8);
+        }
 
-fn layout_item_kind<'a>(item: &'a LayoutItemKindData, dict: &'a Dictionary) -> LayoutItemKind<'a> {
-    match item {
-        LayoutItemKindData::Component { iid } => LayoutItemKind::Component(Component(
-            dict,
-            dict.inner.components.get(*iid as usize).unwrap(),
-        )),
-        LayoutItemKindData::Group {
-            len_field_iid,
-            items: items_data,
-        } => {
-            let items = items_data
-                .iter()
-                .map(|item_data| LayoutItem(dict, item_data))
-                .collect::<Vec<_>>();
-            let len_field_data = &dict.inner.fields[*len_field_iid as usize];
-            let len_field = Field(dict, len_field_data);
-            LayoutItemKind::Group(len_field, items)
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            let mut n = 0u64;
+            for shift in (0..64).step_by(7) {
+                let byte = r.byte()?;
+                n |= ((byte & 0x7f) as u64) << shift;
+                if byte & 0x80 == 0 {
+                    return Ok(n);
+                }
+            }
+            Err(invalid_data("integer overflow"))
         }
-        LayoutItemKindData::Field { iid } => {
-            LayoutItemKind::Field(Field(dict, dict.inner.fields.get(*iid as usize).unwrap()))
+    }
+
+    impl Binary for f64 {
+        fn write(&self, w: &mut Vec<u8>) {
+            self.to_bits().write(w)
+        }
+
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            u64::read(r).map(f64::from_bits)
         }
     }
-}
 
-/// An entry in a sequence of FIX field definitions.
-#[derive(Clone, Debug)]
-pub struct LayoutItem<'a>(&'a Dictionary, &'a LayoutItemData);
+    impl Binary for u32 {
+        fn write(&self, w: &mut Vec<u8>) {
+            (*self as u64).write(w)
+        }
 
-/// The kind of element contained in a [`Message`].
-#[derive(Debug)]
-pub enum LayoutItemKind<'a> {
-    /// This component item is another component.
-    Component(Component<'a>),
-    /// This component item is a FIX repeating group.
-    Group(Field<'a>, Vec<LayoutItem<'a>>),
-    /// This component item is a FIX field.
-    Field(Field<'a>),
-}
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            u32::try_from(u64::read(r)?).map_err(|_| invalid_data("integer overflow"))
+        }
+    }
 
-impl<'a> LayoutItem<'a> {
-    /// Returns `true` if `self` is required in order to have a valid definition
-    /// of its parent container, `false` otherwise.
-    pub fn required(&self) -> bool {
-        self.1.required
+    impl Binary for usize {
+        fn write(&self, w: &mut Vec<u8>) {
+            (*self as u64).write(w)
+        }
+
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            usize::try_from(u64::read(r)?).map_err(|_| invalid_data("integer overflow"))
+        }
     }
 
-    /// Returns the [`LayoutItemKind`] of `self`.
-    pub fn kind(&self) -> LayoutItemKind {
-        layout_item_kind(&self.1.kind, self.0)
+    impl Binary for i32 {
+        fn write(&self, w: &mut Vec<u8>) {
+            // Zigzag encoding keeps small negative numbers short.
+            (((*self << 1) ^ (*self >> 31)) as u32).write(w)
+        }
+
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            let n = u32::read(r)?;
+            Ok((n >> 1) as i32 ^ -((n & 1) as i32))
+        }
     }
 
-    /// Returns the human-readable name of `self`.
-    pub fn tag_text(&self) -> &str {
-        match &self.1.kind {
-            LayoutItemKindData::Component { iid } => self
-                .0
-                .inner
-                .components
-                .get(*iid as usize)
-                .unwrap()
-                .name
-                .as_str(),
-            LayoutItemKindData::Group {
-                len_field_iid,
-                items: _items,
-            } => self
-                .0
-                .inner
-                .fields
-                .get(*len_field_iid as usize)
-                .unwrap()
-                .name
-                .as_str(),
-            LayoutItemKindData::Field { iid } => self
-                .0
-                .inner
-                .fields
-                .get(*iid as usize)
-                .unwrap()
-                .name
-                .as_str(),
+    impl Binary for bool {
+        fn write(&self, w: &mut Vec<u8>) {
+            w.push(*self as u8);
+        }
+
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            match r.byte()? {
+                0 => Ok(false),
+                1 => Ok(true),
+                _ => Err(invalid_data("invalid boolean")),
+            }
         }
     }
-}
 
-type LayoutItems = Vec<LayoutItemData>;
+    impl Binary for String {
+        fn write(&self, w: &mut Vec<u8>) {
+            self.len().write(w);
+            w.extend_from_slice(self.as_bytes());
+        }
 
-#[derive(Clone, Debug)]
-struct MessageData {
-    /// The unique integer identifier of this message type.
-    component_id: u32,
-    /// **Primary key**. The unique character identifier of this message
-    /// type; used literally in FIX messages.
-    msg_type: String,
-    /// The name of this message type.
-    name: String,
-    /// Identifier of the category to which this message belongs.
-    category_iid: InternalId,
-    /// Identifier of the section to which this message belongs.
-    section_id: String,
-    layout_items: LayoutItems,
-    /// The abbreviated name of this message, when used in an XML context.
-    abbr_name: Option<String>,
-    /// A boolean used to indicate if the message is to be generated as part
-    /// of FIXML.
-    required: bool,
-    description: String,
-    elaboration: Option<String>,
-}
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            let len = usize::read(r)?;
+            let bytes = r.take(len)?;
+            String::from_utf8(bytes.to_vec()).map_err(|_| invalid_data("invalid UTF-8"))
+        }
+    }
 
-/// A [`Message`] is a unit of information sent on the wire between
-/// counterparties. Every [`Message`] is composed of fields and/or components.
-#[derive(Debug)]
-pub struct Message<'a>(&'a Dictionary, &'a MessageData);
+    impl<T: Binary> Binary for Option<T> {
+        fn write(&self, w: &mut Vec<u8>) {
+            self.is_some().write(w);
+            if let Some(value) = self {
+                value.write(w);
+            }
+        }
 
-impl<'a> Message<'a> {
-    /// Returns the human-readable name of `self`.
-    pub fn name(&self) -> &str {
-        self.1.name.as_str()
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            Ok(if bool::read(r)? {
+                Some(T::read(r)?)
+            } else {
+                None
+            })
+        }
+    }
+
+    impl<T: Binary> Binary for Vec<T> {
+        fn write(&self, w: &mut Vec<u8>) {
+            self.len().write(w);
+            for item in self {
+                item.write(w);
+            }
+        }
+
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            let len = usize::read(r)?;
+            // Every item takes up at least one byte, which keeps corrupted
+            // lengths from causing huge allocations.
+            let mut items = Vec::with_capacity(len.min(r.0.len()));
+            for _ in 0..len {
+                items.push(T::read(r)?);
+            }
+            Ok(items)
+        }
+    }
+
+    impl Binary for FixDatatype {
+        fn write(&self, w: &mut Vec<u8>) {
+            w.push(*self as u8);
+        }
+
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            let byte = r.byte()?;
+            FixDatatype::iter()
+                .find(|datatype| *datatype as u8 == byte)
+                .ok_or_else(|| invalid_data("unknown datatype"))
+        }
+    }
+
+    impl Binary for Presence {
+        fn write(&self, w: &mut Vec<u8>) {
+            w.push(*self as u8);
+        }
+
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            Ok(match r.byte()? {
+                0 => Presence::Optional,
+                1 => Presence::Required,
+                2 => Presence::Forbidden,
+                3 => Presence::Ignored,
+                _ => return Err(invalid_data("unknown presence")),
+            })
+        }
     }
 
-    /// Returns the message type of `self`.
-    pub fn msg_type(&self) -> &str {
-        self.1.msg_type.as_str()
+    impl Binary for Key {
+        fn write(&self, w: &mut Vec<u8>) {
+            let (discriminant, name) = match self {
+                Key::Abbreviation(s) => (0, s),
+                Key::CategoryByName(s) => (1, s),
+                Key::ComponentByName(s) => (2, s),
+                Key::DatatypeByName(s) => (3, s),
+                Key::FieldByTag(tag) => {
+                    w.push(4);
+                    return tag.write(w);
+                }
+                Key::FieldByName(s) => (5, s),
+                Key::MessageByName(s) => (6, s),
+                Key::MessageByMsgType(s) => (7, s),
+                Key::SectionById(s) => (8, s),
+            };
+            w.push(discriminant);
+            name.write(w);
+        }
+
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            Ok(match r.byte()? {
+                0 => Key::Abbreviation(Binary::read(r)?),
+                1 => Key::CategoryByName(Binary::read(r)?),
+                2 => Key::ComponentByName(Binary::read(r)?),
+                3 => Key::DatatypeByName(Binary::read(r)?),
+                4 => Key::FieldByTag(Binary::read(r)?),
+                5 => Key::FieldByName(Binary::read(r)?),
+                6 => Key::MessageByName(Binary::read(r)?),
+                7 => Key::MessageByMsgType(Binary::read(r)?),
+                8 => Key::SectionById(Binary::read(r)?),
+                _ => return Err(invalid_data("unknown symbol")),
+            })
+        }
     }
 
-    /// Returns the description associated with `self`.
-    pub fn description(&self) -> &str {
-        &self.1.description
+    impl Binary for FixmlComponentAttributes {
+        fn write(&self, w: &mut Vec<u8>) {
+            match self {
+                FixmlComponentAttributes::Xml => w.push(0),
+                FixmlComponentAttributes::Block {
+                    is_repeating,
+                    is_implicit,
+                    is_optimized,
+                } => {
+                    w.push(1);
+                    is_repeating.write(w);
+                    is_implicit.write(w);
+                    is_optimized.write(w);
+                }
+                FixmlComponentAttributes::Message => w.push(2),
+            }
+        }
+
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            Ok(match r.byte()? {
+                0 => FixmlComponentAttributes::Xml,
+                1 => FixmlComponentAttributes::Block {
+                    is_repeating: Binary::read(r)?,
+                    is_implicit: Binary::read(r)?,
+                    is_optimized: Binary::read(r)?,
+                },
+                2 => FixmlComponentAttributes::Message,
+                _ => return Err(invalid_data("unknown component type")),
+            })
+        }
     }
 
-    pub fn group_info(&self, num_in_group_tag: TagU16) -> Option<TagU16> {
-        self.layout().find_map(|layout_item| {
-            if let LayoutItemKind::Group(field, items) = layout_item.kind() {
-                if field.tag() == num_in_group_tag {
-                    if let LayoutItemKind::Field(f) = items[0].kind() {
-                        Some(f.tag())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
+    impl Binary for LayoutItemKindData {
+        fn write(&self, w: &mut Vec<u8>) {
+            match self {
+                LayoutItemKindData::Component { iid } => {
+                    w.push(0);
+                    iid.write(w);
+                }
+                LayoutItemKindData::Group {
+                    len_field_iid,
+                    items,
+                } => {
+                    w.push(1);
+                    len_field_iid.write(w);
+                    items.write(w);
+                }
+                LayoutItemKindData::Field { iid } => {
+                    w.push(2);
+                    iid.write(w);
                 }
-            } else if let LayoutItemKind::Component(_component) = layout_item.kind() {
-                None
-            } else {
-                None
             }
-        })
-    }
+        }
 
-    /// Returns the component ID of `self`.
-    pub fn component_id(&self) -> u32 {
-        self.1.component_id
+        fn read(r: &mut Reader) -> BinaryResult<Self> {
+            Ok(match r.byte()? {
+                0 => LayoutItemKindData::Component {
+                    iid: Binary::read(r)?,
+                },
+                1 => LayoutItemKindData::Group {
+                    len_field_iid: Binary::read(r)?,
+                    items: Binary::read(r)?,
+                },
+                2 => LayoutItemKindData::Field {
+                    iid: Binary::read(r)?,
+                },
+                _ => return Err(invalid_data("unknown layout item")),
+            })
+        }
     }
 
-    pub fn layout(&self) -> impl Iterator<Item = LayoutItem> {
-        self.1
-            .layout_items
-            .iter()
-            .map(move |data| LayoutItem(self.0, data))
+    /// Implements [`Binary`] for structs, field by field.
+    macro_rules! binary_struct {
+        ($name:ident { $($field:ident),* $(,)? }) => {
+            impl Binary for $name {
+                fn write(&self, w: &mut Vec<u8>) {
+                    $(self.$field.write(w);)*
+                }
+
+                fn read(r: &mut Reader) -> BinaryResult<Self> {
+                    Ok($name {
+                        $($field: Binary::read(r)?,)*
+                    })
+                }
+            }
+        };
     }
+
+    binary_struct!(PedigreeData {
+        added,
+        added_ep,
+        updated,
+        updated_ep,
+        deprecated,
+        deprecated_ep,
+    });
+
+    binary_struct!(AbbreviationData {
+        term,
+        abbreviation,
+        is_last,
+        pedigree,
+    });
+
+    binary_struct!(DatatypeData {
+        datatype,
+        description,
+        examples,
+    });
+
+    binary_struct!(FieldData {
+        name,
+        tag,
+        data_type_iid,
+        associated_data_tag,
+        value_restrictions,
+        abbr_name,
+        base_category_id,
+        base_category_abbr_name,
+        required,
+        description,
+        pedigree,
+        constraints,
+    });
+
+    binary_struct!(FieldConstraints {
+        min,
+        max,
+        max_length,
+        pattern,
+    });
+
+    binary_struct!(FieldEnumData {
+        value,
+        description,
+        symbolic_name,
+        pedigree,
+    });
+
+    binary_struct!(ComponentData {
+        id,
+        component_type,
+        layout_items,
+        category_iid,
+        name,
+        abbr_name,
+        description,
+        pedigree,
+        scenario,
+    });
+
+    binary_struct!(LayoutItemData {
+        required,
+        kind,
+        presence_rules,
+    });
+
+    binary_struct!(PresenceRuleData {
+        name,
+        presence,
+        condition,
+    });
+
+    binary_struct!(MessageData {
+        component_id,
+        msg_type,
+        name,
+        category_iid,
+        section_id,
+        layout_items,
+        abbr_name,
+        required,
+        description,
+        elaboration,
+        pedigree,
+        scenario,
+    });
+
+    binary_struct!(CategoryData {
+        name,
+        fixml_filename,
+        section_id,
+    });
+
+    binary_struct!(SectionData {
+        id,
+        name,
+        display_order,
+        fixml_filename,
+        description,
+    });
 }
 
-/// A [`Section`] is a collection of many [`Component`]-s. It has no practical
-/// effect on encoding and decoding of FIX data and it's only used for
-/// documentation and human readability.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Section {}
+mod condition {
+    use super::*;
 
-mod symbol_table {
-    use super::InternalId;
-    use fnv::FnvHashMap;
-    use std::borrow::Borrow;
-    use std::hash::Hash;
+    /// A condition of a [`PresenceRule`], compiled against a [`Dictionary`]
+    /// and ready to be evaluated.
+    ///
+    /// Conditions are written in a subset of the Score DSL of FIX Orchestra:
+    ///
+    /// - Comparisons of a field with a value: `OrdType == ^Limit`,
+    ///   `OrderQty > 0`, `Symbol != "ACME"`. `^` refers to enumerated values of
+    ///   the field by their symbolic name (or, lacking that, by their
+    ///   description, e.g. `^StopLimit` matches `STOP_LIMIT`).
+    /// - Set membership: `OrdType in {^Stop, ^StopLimit}`.
+    /// - Field presence: `exists StopPx`.
+    /// - `!`, `&&`, `||`, and parentheses.
+    ///
+    /// Comparisons with missing fields never hold.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Condition(Expr<TagU16, String>);
+
+    impl Condition {
+        /// Compiles `text`, resolving field names and symbolic values within
+        /// `dict`. Returns [`None`] if `text` is not supported or it refers to
+        /// unknown fields or values.
+        pub(super) fn compile(text: &str, dict: &Dictionary) -> Option<Self> {
+            resolve(parse(text).ok()?, dict).map(Self)
+        }
 
-    pub type SymbolTable = FnvHashMap<Key, InternalId>;
+        /// Evaluates `self`, taking the raw values of fields from `field`.
+        pub fn evaluate<'a, F>(&self, field: F) -> bool
+        where
+            F: Fn(TagU16) -> Option<&'a [u8]>,
+        {
+            evaluate(&self.0, &field)
+        }
+    }
 
-    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-    pub enum Key {
-        #[allow(dead_code)]
-        Abbreviation(String),
-        CategoryByName(String),
-        ComponentByName(String),
-        DatatypeByName(String),
-        FieldByTag(u32),
-        FieldByName(String),
-        MessageByName(String),
-        MessageByMsgType(String),
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Operator {
+        Eq,
+        Ne,
+        Lt,
+        Le,
+        Gt,
+        Ge,
     }
 
-    #[derive(Copy, Debug, Clone, PartialEq, Eq, Hash)]
-    pub enum KeyRef<'a> {
-        Abbreviation(&'a str),
-        CategoryByName(&'a str),
-        ComponentByName(&'a str),
-        DatatypeByName(&'a str),
-        FieldByTag(u32),
-        FieldByName(&'a str),
-        MessageByName(&'a str),
-        MessageByMsgType(&'a str),
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Literal {
+        /// `^Name`, i.e. an enumerated value of the compared field.
+        Symbol(String),
+        Value(String),
     }
 
-    impl Key {
-        fn as_ref(&self) -> KeyRef {
-            match self {
-                Key::Abbreviation(s) => KeyRef::Abbreviation(s.as_str()),
-                Key::CategoryByName(s) => KeyRef::CategoryByName(s.as_str()),
-                Key::ComponentByName(s) => KeyRef::ComponentByName(s.as_str()),
-                Key::DatatypeByName(s) => KeyRef::DatatypeByName(s.as_str()),
-                Key::FieldByTag(t) => KeyRef::FieldByTag(*t),
-                Key::FieldByName(s) => KeyRef::FieldByName(s.as_str()),
-                Key::MessageByName(s) => KeyRef::MessageByName(s.as_str()),
-                Key::MessageByMsgType(s) => KeyRef::MessageByMsgType(s.as_str()),
-            }
+    /// A condition over fields `F` and values `V`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Expr<F, V> {
+        Or(Box<Expr<F, V>>, Box<Expr<F, V>>),
+        And(Box<Expr<F, V>>, Box<Expr<F, V>>),
+        Not(Box<Expr<F, V>>),
+        Exists(F),
+        Compare(F, Operator, V),
+        In(F, Vec<V>),
+    }
+
+    /// Parses the condition `text`, without resolving any names.
+    pub fn parse(text: &str) -> Result<Expr<String, Literal>, String> {
+        let error = || format!("Invalid condition '{}'", text);
+        let mut parser = Parser {
+            tokens: tokenize(text).ok_or_else(error)?,
+            i: 0,
+        };
+        match parser.or() {
+            Some(expr) if parser.i == parser.tokens.len() => Ok(expr),
+            _ => Err(error()),
         }
     }
 
-    pub trait SymbolTableIndex {
-        fn to_key(&self) -> KeyRef;
+    fn resolve(expr: Expr<String, Literal>, dict: &Dictionary) -> Option<Expr<TagU16, String>> {
+        let field = |name: &str| dict.field_by_name(name);
+        Some(match expr {
+            Expr::Or(a, b) => Expr::Or(Box::new(resolve(*a, dict)?), Box::new(resolve(*b, dict)?)),
+            Expr::And(a, b) => {
+                Expr::And(Box::new(resolve(*a, dict)?), Box::new(resolve(*b, dict)?))
+            }
+            Expr::Not(a) => Expr::Not(Box::new(resolve(*a, dict)?)),
+            Expr::Exists(name) => Expr::Exists(field(&name)?.tag()),
+            Expr::Compare(name, op, literal) => {
+                let field = field(&name)?;
+                Expr::Compare(field.tag(), op, resolve_literal(&field, literal)?)
+            }
+            Expr::In(name, literals) => {
+                let field = field(&name)?;
+                let values = literals
+                    .into_iter()
+                    .map(|literal| resolve_literal(&field, literal))
+                    .collect::<Option<Vec<_>>>()?;
+                Expr::In(field.tag(), values)
+            }
+        })
     }
 
-    impl SymbolTableIndex for Key {
-        fn to_key(&self) -> KeyRef {
-            self.as_ref()
+    fn resolve_literal(field: &Field, literal: Literal) -> Option<String> {
+        match literal {
+            Literal::Value(value) => Some(value),
+            Literal::Symbol(symbol) => {
+                let normalize = |s: &str| s.replace('_', "").to_ascii_lowercase();
+                let enums: Vec<_> = field.enums()?.collect();
+                enums
+                    .iter()
+                    .find(|e| e.symbolic_name() == Some(symbol.as_str()))
+                    .or_else(|| {
+                        enums
+                            .iter()
+                            .find(|e| normalize(e.description()) == normalize(&symbol))
+                    })
+                    .map(|e| e.value().to_string())
+            }
         }
     }
 
-    impl<'a> SymbolTableIndex for KeyRef<'a> {
-        fn to_key(&self) -> KeyRef {
-            *self
+    fn evaluate<'a>(
+        expr: &Expr<TagU16, String>,
+        field: &dyn Fn(TagU16) -> Option<&'a [u8]>,
+    ) -> bool {
+        let value = |tag| field(tag).and_then(|v| std::str::from_utf8(v).ok());
+        match expr {
+            Expr::Or(a, b) => evaluate(a, field) || evaluate(b, field),
+            Expr::And(a, b) => evaluate(a, field) && evaluate(b, field),
+            Expr::Not(a) => !evaluate(a, field),
+            Expr::Exists(tag) => field(*tag).is_some(),
+            Expr::Compare(tag, op, expected) => {
+                value(*tag).map_or(false, |value| compare(value, *op, expected))
+            }
+            Expr::In(tag, values) => value(*tag).map_or(false, |value| {
+                values.iter().any(|v| compare(value, Operator::Eq, v))
+            }),
         }
     }
 
-    impl<'a> Hash for dyn SymbolTableIndex + 'a {
-        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-            self.to_key().hash(state);
+    /// Compares numerically if both sides are numbers, and textually
+    /// otherwise. Only equality applies to text.
+    fn compare(value: &str, op: Operator, expected: &str) -> bool {
+        let ordering = match (value.parse::<f64>(), expected.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b),
+            _ if matches!(op, Operator::Eq | Operator::Ne) => Some(value.cmp(expected)),
+            _ => None,
+        };
+        let ordering = match ordering {
+            Some(ordering) => ordering,
+            None => return false,
+        };
+        match op {
+            Operator::Eq => ordering.is_eq(),
+            Operator::Ne => ordering.is_ne(),
+            Operator::Lt => ordering.is_lt(),
+            Operator::Le => ordering.is_le(),
+            Operator::Gt => ordering.is_gt(),
+            Operator::Ge => ordering.is_ge(),
         }
     }
 
-    impl<'a> Borrow<dyn SymbolTableIndex + 'a> for Key {
-        fn borrow(&self) -> &(dyn SymbolTableIndex + 'a) {
-            self
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Token {
+        Ident(String),
+        Symbol(String),
+        Value(String),
+        Punct(&'static str),
+    }
+
+    const PUNCTUATION: &[&str] = &[
+        "||", "&&", "==", "!=", "<=", ">=", "<", ">", "!", "(", ")", "{", "}", ",",
+    ];
+
+    fn tokenize(text: &str) -> Option<Vec<Token>> {
+        let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '.';
+        let mut tokens = Vec::new();
+        let mut rest = text.trim_start();
+        while let Some(c) = rest.chars().next() {
+            let (token, len) = if let Some(p) = PUNCTUATION.iter().find(|p| rest.starts_with(**p)) {
+                (Token::Punct(p), p.len())
+            } else if c == '"' || c == '\'' {
+                let end = rest[1..].find(c)? + 1;
+                (Token::Value(rest[1..end].to_string()), end + 1)
+            } else if c == '^' {
+                let len = rest[1..]
+                    .find(|c| !is_ident_char(c))
+                    .unwrap_or(rest.len() - 1)
+                    + 1;
+                (Token::Symbol(rest[1..len].to_string()), len)
+            } else if c.is_ascii_digit() || c == '-' {
+                let len = rest[1..]
+                    .find(|c: char| !c.is_ascii_digit() && c != '.')
+                    .unwrap_or(rest.len() - 1)
+                    + 1;
+                (Token::Value(rest[..len].to_string()), len)
+            } else if is_ident_char(c) {
+                let len = rest.find(|c| !is_ident_char(c)).unwrap_or(rest.len());
+                (Token::Ident(rest[..len].to_string()), len)
+            } else {
+                return None;
+            };
+            if matches!(&token, Token::Symbol(s) if s.is_empty()) {
+                return None;
+            }
+            tokens.push(token);
+            rest = rest[len..].trim_start();
         }
+        Some(tokens)
     }
 
-    impl<'a> Eq for dyn SymbolTableIndex + 'a {}
+    struct Parser {
+        tokens: Vec<Token>,
+        i: usize,
+    }
 
-    impl<'a> PartialEq for dyn SymbolTableIndex + 'a {
-        fn eq(&self, other: &dyn SymbolTableIndex) -> bool {
-            self.to_key() == other.to_key()
+    impl Parser {
+        fn next(&mut self) -> Option<Token> {
+            let token = self.tokens.get(self.i).cloned();
+            self.i += 1;
+            token
+        }
+
+        fn eat(&mut self, punct: &str) -> bool {
+            let found = matches!(self.tokens.get(self.i), Some(Token::Punct(p)) if *p == punct);
+            if found {
+                self.i += 1;
+            }
+            found
+        }
+
+        fn or(&mut self) -> Option<Expr<String, Literal>> {
+            let mut expr = self.and()?;
+            while self.eat("||") {
+                expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+            }
+            Some(expr)
+        }
+
+        fn and(&mut self) -> Option<Expr<String, Literal>> {
+            let mut expr = self.unary()?;
+            while self.eat("&&") {
+                expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+            }
+            Some(expr)
+        }
+
+        fn unary(&mut self) -> Option<Expr<String, Literal>> {
+            if self.eat("!") {
+                return Some(Expr::Not(Box::new(self.unary()?)));
+            }
+            if self.eat("(") {
+                let expr = self.or()?;
+                return self.eat(")").then_some(expr);
+            }
+            let name = match self.next()? {
+                Token::Ident(name) => name,
+                _ => return None,
+            };
+            if name == "exists" {
+                return match self.next()? {
+                    Token::Ident(name) => Some(Expr::Exists(name)),
+                    _ => None,
+                };
+            }
+            if matches!(self.tokens.get(self.i), Some(Token::Ident(k)) if k == "in") {
+                self.i += 1;
+                if !self.eat("{") {
+                    return None;
+                }
+                let mut literals = vec![self.literal()?];
+                while self.eat(",") {
+                    literals.push(self.literal()?);
+                }
+                return self.eat("}").then_some(Expr::In(name, literals));
+            }
+            let op = match self.next()? {
+                Token::Punct("==") => Operator::Eq,
+                Token::Punct("!=") => Operator::Ne,
+                Token::Punct("<") => Operator::Lt,
+                Token::Punct("<=") => Operator::Le,
+                Token::Punct(">") => Operator::Gt,
+                Token::Punct(">=") => Operator::Ge,
+                _ => return None,
+            };
+            Some(Expr::Compare(name, op, self.literal()?))
+        }
+
+        fn literal(&mut self) -> Option<Literal> {
+            match self.next()? {
+                Token::Symbol(symbol) => Some(Literal::Symbol(symbol)),
+                Token::Value(value) => Some(Literal::Value(value)),
+                _ => None,
+            }
         }
     }
 }
 
-mod quickfix {
+mod lookup {
     use super::*;
 
-    pub struct QuickFixReader<'a> {
-        node_with_header: roxmltree::Node<'a, 'a>,
-        node_with_trailer: roxmltree::Node<'a, 'a>,
-        node_with_components: roxmltree::Node<'a, 'a>,
-        node_with_messages: roxmltree::Node<'a, 'a>,
-        node_with_fields: roxmltree::Node<'a, 'a>,
-        builder: DictionaryBuilder,
+    /// Precomputed lookup tables of a [`Dictionary`], for decoders, encoders,
+    /// validators, and code generators which need fast access by tag or by
+    /// `MsgType <35>`. See [`Dictionary::lookup_tables`].
+    ///
+    /// Message and component layouts are flattened: components are expanded in
+    /// place, and repeating groups come with their delimiter and the set of
+    /// their member tags.
+    #[derive(Debug, Clone, Default)]
+    pub struct LookupTables {
+        // Internal IDs and datatypes of fields, indexed by tag.
+        fields: Vec<Option<(InternalId, FixDatatype)>>,
+        num_in_group_tags: TagSet,
+        // `Length` fields and their associated data fields, and vice versa.
+        data_fields: FnvHashMap<u16, TagU16>,
+        length_fields: FnvHashMap<u16, TagU16>,
+        header: FlatLayout,
+        trailer: FlatLayout,
+        header_tags: TagSet,
+        trailer_tags: TagSet,
+        messages: FnvHashMap<String, FlatLayout>,
+        groups: FnvHashMap<u16, FlatGroup>,
     }
 
-    impl<'a> QuickFixReader<'a> {
-        pub fn new_dict_from_xml(
-            xml_document: &'a roxmltree::Document<'a>,
-        ) -> ParseResult<Dictionary> {
-            let mut reader = Self::empty(xml_document)?;
-            for child in reader.node_with_fields.children() {
-                if child.is_element() {
-                    import_field(&mut reader.builder, child)?;
+    impl LookupTables {
+        pub(super) fn new(dict: &Dictionary) -> Self {
+            let mut tables = Self::default();
+            for data in dict.inner.fields.iter() {
+                let field = Field(dict, data);
+                let tag = field.tag().get();
+                let datatype = field.data_type().basetype();
+                let i = tag as usize;
+                if tables.fields.len() <= i {
+                    tables.fields.resize(i + 1, None);
                 }
-            }
-            for child in reader.node_with_components.children() {
-                if child.is_element() {
-                    let name = child
-                        .attribute("name")
-                        .ok_or(ParseDictionaryError::InvalidFormat)?
-                        .to_string();
-                    import_component(&mut reader.builder, child, name)?;
+                let iid = dict.inner.symbol(KeyRef::FieldByTag(tag as u32)).copied();
+                tables.fields[i] = iid.map(|iid| (iid, datatype));
+                if datatype == FixDatatype::NumInGroup {
+                    tables.num_in_group_tags.insert(field.tag());
+                }
+                if let Some(data_tag) = data.associated_data_tag.and_then(|t| TagU16::new(t as u16))
+                {
+                    tables.data_fields.insert(tag, data_tag);
+                    tables.length_fields.insert(data_tag.get(), field.tag());
                 }
             }
-            for child in reader.node_with_messages.children() {
-                if child.is_element() {
-                    import_message(&mut reader.builder, child)?;
+            let flatten_component = |name: &str| {
+                let mut layout = FlatLayout::default();
+                if let Some(component) = dict.component_by_name(name) {
+                    layout.extend(component.items(), true, &[]);
+                }
+                layout
+            };
+            tables.header = flatten_component("StandardHeader");
+            tables.trailer = flatten_component("StandardTrailer");
+            tables.header.collect_all_tags(&mut tables.header_tags);
+            tables.trailer.collect_all_tags(&mut tables.trailer_tags);
+            for message in dict.iter_messages() {
+                let mut layout = FlatLayout::default();
+                layout.extend(message.layout(), true, &[]);
+                tables.collect_groups(&layout);
+                if message.scenario() == BASE_SCENARIO {
+                    tables
+                        .messages
+                        .insert(message.msg_type().to_string(), layout);
                 }
             }
-            // `StandardHeader` and `StandardTrailer` are defined in ad-hoc
-            // sections of the XML files. They're always there, even if
-            // potentially empty (e.g. FIX 5.0+).
-            import_component(
-                &mut reader.builder,
-                reader.node_with_header,
-                "StandardHeader",
-            )?;
-            import_component(
-                &mut reader.builder,
-                reader.node_with_trailer,
-                "StandardTrailer",
-            )?;
-            Ok(reader.builder.build())
+            for component in dict.iter_components() {
+                let mut layout = FlatLayout::default();
+                layout.extend(component.items(), true, &[]);
+                tables.collect_groups(&layout);
+            }
+            tables
         }
 
-        fn empty(xml_document: &'a roxmltree::Document<'a>) -> ParseResult<Self> {
-            let root = xml_document.root_element();
-            let find_tagged_child = |tag: &str| {
-                root.children()
-                    .find(|n| n.has_tag_name(tag))
-                    .ok_or_else(|| {
-                        ParseDictionaryError::InvalidData(format!("<{}> tag not found", tag))
-                    })
-            };
-            let version_type = root.attribute("type").ok_or_else(|| {
-                ParseDictionaryError::InvalidData("No version attribute.".to_string())
-            })?;
-            let version_major = root.attribute("major").ok_or_else(|| {
-                ParseDictionaryError::InvalidData("No major version attribute.".to_string())
-            })?;
-            let version_minor = root.attribute("minor").ok_or_else(|| {
-                ParseDictionaryError::InvalidData("No minor version attribute.".to_string())
-            })?;
-            let version_sp = root.attribute("servicepack").unwrap_or("0");
-            let version = format!(
-                "{}.{}.{}{}",
-                version_type,
-                version_major,
-                version_minor,
-                // Omit Service Pack ID if set to zero.
-                if version_sp != "0" {
-                    format!("-SP{}", version_sp)
-                } else {
-                    String::new()
-                }
-            );
-            Ok(QuickFixReader {
-                builder: DictionaryBuilder::new(version),
-                node_with_header: find_tagged_child("header")?,
-                node_with_trailer: find_tagged_child("trailer")?,
-                node_with_messages: find_tagged_child("messages")?,
-                node_with_components: find_tagged_child("components")?,
-                node_with_fields: find_tagged_child("fields")?,
-            })
+        /// Returns the datatype of the field with the given `tag`, if any.
+        pub fn datatype(&self, tag: u32) -> Option<FixDatatype> {
+            self.field(tag).map(|(_, datatype)| datatype)
+        }
+
+        /// Returns the tags of all `NumInGroup` fields.
+        pub fn num_in_group_tags(&self) -> &TagSet {
+            &self.num_in_group_tags
+        }
+
+        /// Returns the tag of the data field associated to the `Length` field
+        /// `length_tag`, if any (e.g. `RawData <96>` for `RawDataLength <95>`).
+        pub fn data_field_tag(&self, length_tag: u32) -> Option<TagU16> {
+            u16::try_from(length_tag)
+                .ok()
+                .and_then(|tag| self.data_fields.get(&tag).copied())
+        }
+
+        /// Returns the tag of the `Length` field associated to the data field
+        /// `data_tag`, if any (e.g. `RawDataLength <95>` for `RawData <96>`).
+        pub fn length_field_tag(&self, data_tag: u32) -> Option<TagU16> {
+            u16::try_from(data_tag)
+                .ok()
+                .and_then(|tag| self.length_fields.get(&tag).copied())
+        }
+
+        /// Returns the flattened layout of the `StandardHeader` component.
+        pub fn header_layout(&self) -> &FlatLayout {
+            &self.header
+        }
+
+        /// Returns the flattened layout of the `StandardTrailer` component.
+        pub fn trailer_layout(&self) -> &FlatLayout {
+            &self.trailer
+        }
+
+        /// Returns the tags of all fields within the standard header, including
+        /// members of repeating groups.
+        pub fn header_tags(&self) -> &TagSet {
+            &self.header_tags
+        }
+
+        /// Returns the tags of all fields within the standard trailer,
+        /// including members of repeating groups.
+        pub fn trailer_tags(&self) -> &TagSet {
+            &self.trailer_tags
+        }
+
+        /// Returns the flattened layout of the message body with the given
+        /// `msg_type`, if any. Only messages of the [`BASE_SCENARIO`] are
+        /// available.
+        pub fn message_layout(&self, msg_type: &str) -> Option<&FlatLayout> {
+            self.messages.get(msg_type)
+        }
+
+        /// Returns the repeating group started by the `NumInGroup` field
+        /// `num_in_group_tag`, if any.
+        ///
+        /// The same group may be defined differently by different messages and
+        /// components: the delimiter of the first definition wins, and member
+        /// tags are the union of all definitions.
+        pub fn group(&self, num_in_group_tag: u32) -> Option<&FlatGroup> {
+            u16::try_from(num_in_group_tag)
+                .ok()
+                .and_then(|tag| self.groups.get(&tag))
         }
-    }
 
-    fn import_field(
-        builder: &mut DictionaryBuilder,
-        node: roxmltree::Node,
-    ) -> ParseResult<InternalId> {
-        if node.tag_name().name() != "field" {
-            return Err(ParseDictionaryError::InvalidFormat);
+        pub(super) fn field(&self, tag: u32) -> Option<(InternalId, FixDatatype)> {
+            self.fields.get(tag as usize).copied().flatten()
         }
-        let data_type_iid = import_datatype(builder, node);
-        let value_restrictions = value_restrictions_from_node(node, data_type_iid);
-        let name = node
-            .attribute("name")
-            .ok_or(ParseDictionaryError::InvalidFormat)?
-            .to_string();
-        let tag = node
-            .attribute("number")
-            .ok_or(ParseDictionaryError::InvalidFormat)?
-            .parse()
-            .map_err(|_| ParseDictionaryError::InvalidFormat)?;
-        let field = FieldData {
-            name,
-            tag,
-            data_type_iid,
-            associated_data_tag: None,
-            value_restrictions,
-            required: true,
-            abbr_name: None,
-            base_category_abbr_name: None,
-            base_category_id: None,
-            description: None,
-        };
-        Ok(builder.add_field(field))
-    }
 
-    fn import_message(
-        builder: &mut DictionaryBuilder,
-        node: roxmltree::Node,
-    ) -> ParseResult<InternalId> {
-        debug_assert_eq!(node.tag_name().name(), "message");
-        let category_iid = import_category(builder, node)?;
-        let mut layout_items = LayoutItems::new();
-        for child in node.children() {
-            if child.is_element() {
-                // We don't need to generate new IID's because we're dealing
-                // with ranges.
-                layout_items.push(import_layout_item(builder, child)?);
+        fn collect_groups(&mut self, layout: &FlatLayout) {
+            for item in layout.items() {
+                if let Some(group) = item.group() {
+                    self.groups
+                        .entry(item.tag().get())
+                        .and_modify(|known| known.member_tags.union_with(&group.member_tags))
+                        .or_insert_with(|| group.clone());
+                    self.collect_groups(group.layout());
+                }
             }
         }
-        let message = MessageData {
-            name: node
-                .attribute("name")
-                .ok_or(ParseDictionaryError::InvalidFormat)?
-                .to_string(),
-            msg_type: node
-                .attribute("msgtype")
-                .ok_or(ParseDictionaryError::InvalidFormat)?
-                .to_string(),
-            component_id: 0,
-            category_iid,
-            section_id: String::new(),
-            layout_items,
-            abbr_name: None,
-            required: true,
-            elaboration: None,
-            description: String::new(),
-        };
-        Ok(builder.add_message(message))
     }
 
-    fn import_component<S: AsRef<str>>(
-        builder: &mut DictionaryBuilder,
-        node: roxmltree::Node,
-        name: S,
-    ) -> ParseResult<InternalId> {
-        let mut layout_items = LayoutItems::new();
-        for child in node.children() {
-            if child.is_element() {
-                layout_items.push(import_layout_item(builder, child)?);
+    /// A message or component layout with all components expanded. See
+    /// [`LookupTables`].
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct FlatLayout {
+        items: Vec<FlatLayoutItem>,
+        tags: TagSet,
+        required_tags: TagSet,
+    }
+
+    impl FlatLayout {
+        /// Returns the items of `self`, in order.
+        pub fn items(&self) -> &[FlatLayoutItem] {
+            &self.items
+        }
+
+        /// Returns the tags of all items of `self`. Members of repeating groups
+        /// are not included, but their `NumInGroup` fields are.
+        pub fn tags(&self) -> &TagSet {
+            &self.tags
+        }
+
+        /// Returns the tags of all required items of `self`. See
+        /// [`FlatLayoutItem::required`].
+        pub fn required_tags(&self) -> &TagSet {
+            &self.required_tags
+        }
+
+        /// Flattens `items`. The presence rules of expanded components
+        /// (`component_rules`) apply to their items too, except that rules
+        /// which require the component only require its required items.
+        fn extend<'a>(
+            &mut self,
+            items: impl Iterator<Item = LayoutItem<'a>>,
+            required: bool,
+            component_rules: &[FlatPresenceRule],
+        ) {
+            for item in items {
+                let mut rules: Vec<FlatPresenceRule> = component_rules
+                    .iter()
+                    .filter(|rule| item.required() || rule.presence != Presence::Required)
+                    .cloned()
+                    .collect();
+                rules.extend(item.presence_rules().filter_map(|rule| {
+                    Some(FlatPresenceRule {
+                        name: rule.name().map(str::to_string),
+                        presence: rule.presence(),
+                        condition: Condition::compile(rule.condition(), item.0)?,
+                    })
+                }));
+                let required = required && item.required();
+                match item.kind() {
+                    LayoutItemKind::Component(component) => {
+                        self.extend(component.items(), required, &rules)
+                    }
+                    LayoutItemKind::Group(len_field, items) => {
+                        let mut layout = FlatLayout::default();
+                        layout.extend(items.into_iter(), true, &[]);
+                        let group = layout.items.first().map(|first| first.tag).map(|tag| {
+                            Box::new(FlatGroup {
+                                delimiter_tag: tag,
+                                member_tags: layout.tags.clone(),
+                                layout,
+                            })
+                        });
+                        self.push(len_field.tag(), required, rules, group);
+                    }
+                    LayoutItemKind::Field(field) => self.push(field.tag(), required, rules, None),
+                }
             }
         }
-        let component = ComponentData {
-            id: 0,
-            component_type: FixmlComponentAttributes::Block {
-                // FIXME
-                is_implicit: false,
-                is_repeating: false,
-                is_optimized: false,
-            },
-            layout_items,
-            category_iid: 0, // FIXME
-            name: name.as_ref().to_string(),
-            abbr_name: None,
-        };
-        let iid = builder.add_component(component);
-        match builder.symbol(KeyRef::ComponentByName(name.as_ref())) {
-            Some(x) => Ok(*x),
-            None => {
-                builder
-                    .symbol_table
-                    .insert(Key::ComponentByName(name.as_ref().to_string()), iid);
-                Ok(iid)
+
+        fn push(
+            &mut self,
+            tag: TagU16,
+            required: bool,
+            presence_rules: Vec<FlatPresenceRule>,
+            group: Option<Box<FlatGroup>>,
+        ) {
+            self.tags.insert(tag);
+            if required {
+                self.required_tags.insert(tag);
             }
+            self.items.push(FlatLayoutItem {
+                tag,
+                required,
+                presence_rules,
+                group,
+            });
         }
-    }
 
-    fn import_datatype(builder: &mut DictionaryBuilder, node: roxmltree::Node) -> InternalId {
-        // References should only happen at <field> tags.
-        debug_assert_eq!(node.tag_name().name(), "field");
-        let datatype = {
-            // The idenfier that QuickFIX uses for this type.
-            let quickfix_name = node.attribute("type").unwrap();
-            // Translate that into a real datatype.
-            FixDatatype::from_quickfix_name(quickfix_name).unwrap()
-        };
-        // Get the official (not QuickFIX's) name of `datatype`.
-        let name = datatype.name();
-        match builder.symbol(KeyRef::DatatypeByName(name)) {
-            Some(x) => *x,
-            None => {
-                let iid = builder.data_types.len() as u32;
-                let data = DatatypeData {
-                    datatype,
-                    description: String::new(),
-                    examples: Vec::new(),
-                };
-                builder.data_types.push(data);
-                builder
-                    .symbol_table
-                    .insert(Key::DatatypeByName(name.to_string()), iid);
-                iid
+        fn collect_all_tags(&self, tags: &mut TagSet) {
+            tags.union_with(&self.tags);
+            for group in self.items.iter().filter_map(FlatLayoutItem::group) {
+                group.layout.collect_all_tags(tags);
             }
         }
     }
 
-    fn value_restrictions_from_node(
-        node: roxmltree::Node,
-        _datatype: InternalId,
-    ) -> Option<Vec<FieldEnumData>> {
-        let mut values = Vec::new();
-        for child in node.children() {
-            if child.is_element() {
-                let variant = child.attribute("enum").unwrap().to_string();
-                let description = child.attribute("description").unwrap().to_string();
-                let enum_value = FieldEnumData {
-                    value: variant,
-                    description,
-                };
-                values.push(enum_value);
+    /// A field within a [`FlatLayout`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FlatLayoutItem {
+        tag: TagU16,
+        required: bool,
+        presence_rules: Vec<FlatPresenceRule>,
+        group: Option<Box<FlatGroup>>,
+    }
+
+    impl FlatLayoutItem {
+        /// Returns the tag of `self`.
+        pub fn tag(&self) -> TagU16 {
+            self.tag
+        }
+
+        /// Returns `true` if `self` is required, i.e. it's required and so are
+        /// all the components it was expanded from. Within repeating groups,
+        /// `self` is required in every entry.
+        pub fn required(&self) -> bool {
+            self.required
+        }
+
+        /// Returns the conditional presence rules of `self`, including those of
+        /// the components it was expanded from. Rules whose condition can't be
+        /// compiled (see [`Condition`]) are left out.
+        pub fn presence_rules(&self) -> &[FlatPresenceRule] {
+            &self.presence_rules
+        }
+
+        /// Returns the [`Presence`] of `self` within a message or group entry
+        /// whose fields are given by `field`, i.e. the presence of the first
+        /// rule whose condition holds or, failing that, whether `self` is
+        /// [`FlatLayoutItem::required`]. The matching rule is returned too.
+        pub fn presence<'a, F>(&self, field: F) -> (Presence, Option<&FlatPresenceRule>)
+        where
+            F: Fn(TagU16) -> Option<&'a [u8]>,
+        {
+            match self
+                .presence_rules
+                .iter()
+                .find(|rule| rule.condition.evaluate(&field))
+            {
+                Some(rule) => (rule.presence, Some(rule)),
+                None if self.required => (Presence::Required, None),
+                None => (Presence::Optional, None),
             }
         }
-        if values.is_empty() {
-            None
-        } else {
-            Some(values)
+
+        /// Returns the repeating group started by `self`, if `self` is a
+        /// non-empty repeating group.
+        pub fn group(&self) -> Option<&FlatGroup> {
+            self.group.as_deref()
         }
     }
 
-    fn import_layout_item(
-        builder: &mut DictionaryBuilder,
-        node: roxmltree::Node,
-    ) -> ParseResult<LayoutItemData> {
-        // This processing step requires on fields being already present in
-        // the dictionary.
-        debug_assert_ne!(builder.fields.len(), 0);
-        let name = node.attribute("name").unwrap();
-        let required = node.attribute("required").unwrap() == "Y";
-        let tag = node.tag_name().name();
-        let kind = match tag {
-            "field" => {
-                let field_iid = builder.symbol(KeyRef::FieldByName(name)).unwrap();
-                LayoutItemKindData::Field { iid: *field_iid }
-            }
-            "component" => {
-                // Components may *not* be already present.
-                let component_iid = import_component(builder, node, name)?;
-                LayoutItemKindData::Component { iid: component_iid }
-            }
-            "group" => {
-                let len_field_iid = *builder.symbol(KeyRef::FieldByName(name)).unwrap();
-                let mut items = Vec::new();
-                for child in node.children().filter(|n| n.is_element()) {
-                    items.push(import_layout_item(builder, child)?);
-                }
-                LayoutItemKindData::Group {
-                    len_field_iid,
-                    items,
-                }
+    /// A [`PresenceRule`] of a [`FlatLayoutItem`], with its condition
+    /// compiled.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FlatPresenceRule {
+        name: Option<String>,
+        presence: Presence,
+        condition: Condition,
+    }
+
+    impl FlatPresenceRule {
+        /// Returns the name of `self`, if any.
+        pub fn name(&self) -> Option<&str> {
+            self.name.as_deref()
+        }
+
+        /// Returns the [`Presence`] of the item when the condition of `self`
+        /// holds.
+        pub fn presence(&self) -> Presence {
+            self.presence
+        }
+
+        /// Returns the compiled condition of `self`.
+        pub fn condition(&self) -> &Condition {
+            &self.condition
+        }
+    }
+
+    /// A repeating group within a [`FlatLayout`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FlatGroup {
+        delimiter_tag: TagU16,
+        member_tags: TagSet,
+        layout: FlatLayout,
+    }
+
+    impl FlatGroup {
+        /// Returns the tag of the first field of every entry.
+        pub fn delimiter_tag(&self) -> TagU16 {
+            self.delimiter_tag
+        }
+
+        /// Returns the tags of all fields which may appear directly within
+        /// entries of `self`.
+        pub fn member_tags(&self) -> &TagSet {
+            &self.member_tags
+        }
+
+        /// Returns the flattened layout of every entry of `self`.
+        pub fn layout(&self) -> &FlatLayout {
+            &self.layout
+        }
+    }
+
+    /// A compact set of FIX tags, i.e. a bitset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::dict::TagSet;
+    /// use fefix::TagU16;
+    ///
+    /// let tags: TagSet = [35, 8, 9].iter().map(|t| TagU16::new(*t).unwrap()).collect();
+    /// assert!(tags.contains(35));
+    /// assert!(!tags.contains(10));
+    /// assert_eq!(tags.iter().map(|t| t.get()).collect::<Vec<_>>(), vec![8, 9, 35]);
+    /// ```
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct TagSet {
+        // The index of the first word, i.e. the smallest tag divided by 64.
+        offset: usize,
+        words: Vec<u64>,
+        len: usize,
+    }
+
+    impl TagSet {
+        /// Creates a new empty [`TagSet`].
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Adds `tag` to `self`. Returns `true` if `tag` was not present.
+        pub fn insert(&mut self, tag: TagU16) -> bool {
+            let (i, bit) = Self::position(tag.get() as u32);
+            if self.words.is_empty() {
+                self.offset = i;
+            } else if i < self.offset {
+                let n = self.offset - i;
+                self.words.splice(0..0, vec![0; n]);
+                self.offset = i;
             }
-            _ => {
-                return Err(ParseDictionaryError::InvalidFormat);
+            if i - self.offset >= self.words.len() {
+                self.words.resize(i - self.offset + 1, 0);
             }
-        };
-        let item = LayoutItemData { required, kind };
-        Ok(item)
+            let word = &mut self.words[i - self.offset];
+            let is_new = *word & bit == 0;
+            *word |= bit;
+            self.len += is_new as usize;
+            is_new
+        }
+
+        /// Returns `true` if `self` contains `tag`.
+        pub fn contains(&self, tag: u32) -> bool {
+            let (i, bit) = Self::position(tag);
+            i.checked_sub(self.offset)
+                .and_then(|i| self.words.get(i))
+                .map_or(false, |word| *word & bit != 0)
+        }
+
+        /// Returns the number of tags in `self`.
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        /// Returns `true` if `self` contains no tags.
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        /// Returns an [`Iterator`] over all tags in `self`, in ascending order.
+        pub fn iter(&self) -> impl Iterator<Item = TagU16> + '_ {
+            self.words.iter().enumerate().flat_map(move |(i, &word)| {
+                let base = (self.offset + i) * 64;
+                (0..64)
+                    .filter(move |bit| word & (1 << bit) != 0)
+                    .filter_map(move |bit| TagU16::new((base + bit) as u16))
+            })
+        }
+
+        /// Returns an [`Iterator`] over all tags in `self` which are not in
+        /// `other`, in ascending order. E.g. the required tags of a message
+        /// which are missing.
+        pub fn difference<'a>(&'a self, other: &'a TagSet) -> impl Iterator<Item = TagU16> + 'a {
+            self.iter()
+                .filter(move |tag| !other.contains(tag.get() as u32))
+        }
+
+        /// Adds all tags in `other` to `self`.
+        pub fn union_with(&mut self, other: &TagSet) {
+            self.extend(other.iter());
+        }
+
+        fn position(tag: u32) -> (usize, u64) {
+            ((tag / 64) as usize, 1 << (tag % 64))
+        }
     }
 
-    fn import_category(
-        builder: &mut DictionaryBuilder,
-        node: roxmltree::Node,
-    ) -> ParseResult<InternalId> {
-        debug_assert_eq!(node.tag_name().name(), "message");
-        let name = node.attribute("msgcat").ok_or(ParseError::InvalidFormat)?;
-        Ok(match builder.symbol(KeyRef::CategoryByName(name)) {
-            Some(x) => *x,
-            None => {
-                let iid = builder.categories.len() as u32;
-                builder.categories.push(CategoryData {
-                    name: name.to_string(),
-                    fixml_filename: String::new(),
-                });
-                builder
-                    .symbol_table
-                    .insert(Key::CategoryByName(name.to_string()), iid);
-                iid
+    impl Extend<TagU16> for TagSet {
+        fn extend<I: IntoIterator<Item = TagU16>>(&mut self, iter: I) {
+            for tag in iter {
+                self.insert(tag);
             }
-        })
+        }
     }
 
-    type ParseError = ParseDictionaryError;
-    type ParseResult<T> = Result<T, ParseError>;
-
-    /// The error type that can arise when decoding a QuickFIX Dictionary.
-    #[derive(Clone, Debug)]
-    pub enum ParseDictionaryError {
-        InvalidFormat,
-        InvalidData(String),
+    impl FromIterator<TagU16> for TagSet {
+        fn from_iter<I: IntoIterator<Item = TagU16>>(iter: I) -> Self {
+            let mut tags = Self::new();
+            tags.extend(iter);
+            tags
+        }
     }
 }
 
@@ -1889,6 +6511,339 @@ mod test {
         assert!(field_167.enums().unwrap().any(|e| e.value() == "EUCORP"));
     }
 
+    #[test]
+    fn fix44_field_locations() {
+        let dict = Dictionary::fix44();
+        let location = |tag| dict.field_by_tag(tag).unwrap().location();
+        assert_eq!(location(35), FieldLocation::Header);
+        assert_eq!(location(49), FieldLocation::Header);
+        assert_eq!(location(627), FieldLocation::Header);
+        assert_eq!(location(628), FieldLocation::Header);
+        assert_eq!(location(93), FieldLocation::Trailer);
+        assert_eq!(location(10), FieldLocation::Trailer);
+        assert_eq!(location(55), FieldLocation::Body);
+    }
+
+    #[test]
+    fn fix44_length_fields_are_linked_to_data_fields() {
+        let dict = Dictionary::fix44();
+        let data_tag = |tag| {
+            dict.field_by_tag(tag)
+                .unwrap()
+                .associated_data_field()
+                .map(|field| field.tag().get())
+        };
+        assert_eq!(data_tag(95), Some(96));
+        assert_eq!(data_tag(212), Some(213));
+        assert_eq!(data_tag(93), Some(89));
+        assert_eq!(data_tag(354), Some(355));
+        assert_eq!(data_tag(618), Some(619));
+        // `Length` fields which are not followed by data fields.
+        assert_eq!(data_tag(9), None);
+        assert_eq!(data_tag(383), None);
+        assert_eq!(data_tag(96), None);
+    }
+
+    /// The built-in dictionaries as read from their QuickFIX specification
+    /// files, rather than from the binary forms produced by `build.rs`.
+    fn all_from_builtin_specs() -> Vec<Dictionary> {
+        let specs = [
+            #[cfg(feature = "fix40")]
+            (include_str!("resources/quickfix/FIX-4.0.xml"), "FIX.4.0"),
+            #[cfg(feature = "fix41")]
+            (include_str!("resources/quickfix/FIX-4.1.xml"), "FIX.4.1"),
+            #[cfg(feature = "fix42")]
+            (include_str!("resources/quickfix/FIX-4.2.xml"), "FIX.4.2"),
+            #[cfg(feature = "fix43")]
+            (include_str!("resources/quickfix/FIX-4.3.xml"), "FIX.4.3"),
+            (include_str!("resources/quickfix/FIX-4.4.xml"), "FIX.4.4"),
+            #[cfg(feature = "fix50")]
+            (include_str!("resources/quickfix/FIX-5.0.xml"), "FIX.5.0"),
+            #[cfg(feature = "fix50sp1")]
+            (
+                include_str!("resources/quickfix/FIX-5.0-SP1.xml"),
+                "FIX.5.0SP1",
+            ),
+            #[cfg(feature = "fix50sp2")]
+            (
+                include_str!("resources/quickfix/FIX-5.0-SP2.xml"),
+                "FIX.5.0SP2",
+            ),
+            #[cfg(feature = "fixt11")]
+            (include_str!("resources/quickfix/FIXT-1.1.xml"), "FIXT.1.1"),
+        ];
+        specs
+            .iter()
+            .map(|(spec, repository_version)| {
+                Dictionary::from_builtin_spec(spec, repository_version)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn builtin_dictionaries_are_equivalent_to_builtin_specs() {
+        for (builtin, dict) in Dictionary::all().iter().zip(all_from_builtin_specs()) {
+            assert_eq!(builtin.get_version(), dict.get_version());
+            assert!(builtin.diff(&dict).is_empty());
+            assert_eq!(builtin.to_quickfix_spec(), dict.to_quickfix_spec());
+            assert_eq!(builtin.to_bytes(), dict.to_bytes());
+            for field in dict.iter_fields() {
+                let builtin_field = builtin.field_by_name(field.name()).unwrap();
+                assert_eq!(builtin_field.tag(), field.tag());
+                assert_eq!(builtin_field.location(), field.location());
+                assert_eq!(
+                    builtin_field.associated_data_field().map(|f| f.tag()),
+                    field.associated_data_field().map(|f| f.tag())
+                );
+            }
+            for message in dict.iter_messages() {
+                let builtin_message = builtin.message_by_msgtype(message.msg_type()).unwrap();
+                assert_eq!(builtin_message.name(), message.name());
+                assert_eq!(
+                    builtin.lookup_tables().message_layout(message.msg_type()),
+                    dict.lookup_tables().message_layout(message.msg_type())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn binary_roundtrip_of_other_sources() {
+        let mut editor = Dictionary::empty().edit();
+        editor
+            .add_field(5001, "VenueStatusCode", FixDatatype::Int)
+            .unwrap()
+            .add_component(
+                "VenueStatus",
+                "Common",
+                vec![LayoutItemSpec::field("VenueStatusCode", true)],
+            )
+            .unwrap()
+            .add_message(
+                "VenueStatusReport",
+                "U1",
+                "app",
+                vec![LayoutItemSpec::component("VenueStatus", true)],
+            )
+            .unwrap();
+        let dicts = [
+            Dictionary::from_orchestra_spec(include_str!("test_data/orchestra/venue.xml")).unwrap(),
+            Dictionary::fix44()
+                .with_overlay(include_str!("test_data/overlays/venue.xml"))
+                .unwrap(),
+            Dictionary::empty(),
+            editor.build(),
+        ];
+        for dict in dicts.iter() {
+            let bytes = dict.to_bytes();
+            let copy = Dictionary::from_bytes(&bytes).unwrap();
+            assert_eq!(copy.to_bytes(), bytes);
+            assert!(dict.diff(&copy).is_empty());
+            assert_eq!(dict.iter_messages().count(), copy.iter_messages().count());
+            for message in dict.iter_messages() {
+                let copy_message = copy
+                    .message_by_scenario(message.msg_type(), message.scenario())
+                    .unwrap();
+                assert_eq!(copy_message.description(), message.description());
+                assert_eq!(copy_message.category().name(), message.category().name());
+                assert_eq!(
+                    layout_summary(copy_message.layout()),
+                    layout_summary(message.layout())
+                );
+            }
+            for component in dict.iter_components() {
+                let copy_component = copy.component_by_name(component.name()).unwrap();
+                assert_eq!(
+                    copy_component.category().name(),
+                    component.category().name()
+                );
+            }
+            for field in dict.iter_fields() {
+                let copy_field = copy.field_by_tag(field.tag().get() as u32).unwrap();
+                assert_eq!(copy_field.description(), field.description());
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_binary_dictionaries() {
+        let bytes = Dictionary::fix44().to_bytes();
+        assert!(matches!(
+            Dictionary::from_bytes(b""),
+            Err(ParseDictionaryError::InvalidFormat)
+        ));
+        assert!(matches!(
+            Dictionary::from_bytes(b"<fix></fix>"),
+            Err(ParseDictionaryError::InvalidFormat)
+        ));
+        // Unknown format version.
+        let mut other_version = bytes.clone();
+        other_version[9] = 0;
+        assert!(Dictionary::from_bytes(&other_version).is_err());
+        // Truncated and trailing bytes.
+        for len in [10, 100, bytes.len() / 2, bytes.len() - 1] {
+            assert!(Dictionary::from_bytes(&bytes[..len]).is_err());
+        }
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            Dictionary::from_bytes(&trailing),
+            Err(ParseDictionaryError::InvalidData(_))
+        ));
+        // Corrupted bytes must never cause panics.
+        for i in (0..bytes.len()).step_by(bytes.len() / 500) {
+            let mut corrupted = bytes.clone();
+            corrupted[i] = corrupted[i].wrapping_add(0x55);
+            if let Ok(dict) = Dictionary::from_bytes(&corrupted) {
+                for message in dict.iter_messages() {
+                    layout_summary(message.layout());
+                }
+                dict.lookup_tables();
+            }
+        }
+    }
+
+    #[test]
+    fn lookup_tables_agree_with_fields() {
+        for dict in Dictionary::all().iter() {
+            let tables = dict.lookup_tables();
+            for field in dict.iter_fields() {
+                let tag = field.tag().get() as u32;
+                let datatype = field.data_type().basetype();
+                assert_eq!(dict.field_by_tag(tag).unwrap().tag(), field.tag());
+                assert_eq!(tables.datatype(tag), Some(datatype));
+                assert_eq!(
+                    tables.num_in_group_tags().contains(tag),
+                    datatype == FixDatatype::NumInGroup
+                );
+                let data_field = field.associated_data_field();
+                assert_eq!(tables.data_field_tag(tag), data_field.map(|f| f.tag()));
+                if let Some(data_field) = data_field {
+                    let data_tag = data_field.tag().get() as u32;
+                    assert_eq!(tables.length_field_tag(data_tag), Some(field.tag()));
+                }
+            }
+            assert!(dict.field_by_tag(0).is_none());
+            assert!(dict.field_by_tag(u32::MAX).is_none());
+            assert!(tables.datatype(u32::MAX).is_none());
+        }
+    }
+
+    #[test]
+    fn fix44_flattened_message_layout() {
+        let dict = Dictionary::fix44();
+        let tables = dict.lookup_tables();
+        let layout = tables.message_layout("D").unwrap();
+        let tags: Vec<u16> = layout.items().iter().map(|item| item.tag().get()).collect();
+        assert_eq!(&tags[..4], &[11, 526, 583, 453]);
+        // Components are expanded, groups are not.
+        assert!(layout.tags().contains(55));
+        assert!(layout.tags().contains(453));
+        assert!(!layout.tags().contains(448));
+        assert!(!layout.tags().contains(35));
+        // `Symbol <55>` is optional within the required `Instrument` component.
+        for tag in [11, 54, 60, 40] {
+            assert!(layout.required_tags().contains(tag));
+        }
+        for tag in [1, 55, 453] {
+            assert!(!layout.required_tags().contains(tag));
+        }
+        let parties = layout.items()[3].group().unwrap();
+        assert_eq!(parties.delimiter_tag().get(), 448);
+        let members: Vec<u16> = parties.member_tags().iter().map(|t| t.get()).collect();
+        assert_eq!(members, vec![447, 448, 452, 802]);
+        let sub_ids = parties.layout().items()[3].group().unwrap();
+        assert_eq!(sub_ids.delimiter_tag().get(), 523);
+        assert_eq!(tables.group(453), Some(parties));
+        assert_eq!(tables.group(802), Some(sub_ids));
+        assert!(tables.group(448).is_none());
+        assert!(tables.message_layout("ZZ").is_none());
+    }
+
+    #[test]
+    fn fix44_header_and_trailer_tables() {
+        let dict = Dictionary::fix44();
+        let tables = dict.lookup_tables();
+        let header = tables.header_layout();
+        assert_eq!(header.items()[0].tag().get(), 8);
+        assert!(header.required_tags().contains(35));
+        assert!(!header.required_tags().contains(115));
+        // Members of `NoHops <627>` are only part of the recursive tag set.
+        assert!(!header.tags().contains(628));
+        assert!(tables.header_tags().contains(628));
+        assert!(tables.trailer_tags().contains(10));
+        assert!(tables.trailer_layout().required_tags().contains(10));
+        assert!(!tables.header_tags().contains(10));
+        for field in dict.iter_fields() {
+            let tag = field.tag().get() as u32;
+            let location = match field.location() {
+                FieldLocation::Header => tables.header_tags().contains(tag),
+                FieldLocation::Trailer => tables.trailer_tags().contains(tag),
+                FieldLocation::Body => {
+                    !tables.header_tags().contains(tag) && !tables.trailer_tags().contains(tag)
+                }
+            };
+            assert!(location);
+        }
+    }
+
+    #[test]
+    fn lookup_tables_are_shared_by_clones() {
+        let dict = Dictionary::fix44();
+        let clone = dict.clone();
+        assert!(std::ptr::eq(dict.lookup_tables(), clone.lookup_tables()));
+        // Edited dictionaries get their own tables.
+        let mut editor = dict.edit();
+        editor
+            .add_field(5000, "Custom", FixDatatype::NumInGroup)
+            .unwrap();
+        let edited = editor.build();
+        assert!(edited.lookup_tables().num_in_group_tags().contains(5000));
+        assert!(!dict.lookup_tables().num_in_group_tags().contains(5000));
+    }
+
+    #[test]
+    fn tag_sets() {
+        let tag = |t| TagU16::new(t).unwrap();
+        let mut tags = TagSet::new();
+        assert!(tags.is_empty());
+        assert!(tags.insert(tag(500)));
+        assert!(tags.insert(tag(1)));
+        assert!(tags.insert(tag(65535)));
+        assert!(!tags.insert(tag(500)));
+        assert_eq!(tags.len(), 3);
+        assert!(tags.contains(1) && tags.contains(500) && tags.contains(65535));
+        assert!(!tags.contains(0) && !tags.contains(2) && !tags.contains(65536));
+        let others: TagSet = [tag(1), tag(2)].into_iter().collect();
+        let difference: Vec<u16> = tags.difference(&others).map(|t| t.get()).collect();
+        assert_eq!(difference, vec![500, 65535]);
+        tags.union_with(&others);
+        let all: Vec<u16> = tags.iter().map(|t| t.get()).collect();
+        assert_eq!(all, vec![1, 2, 500, 65535]);
+    }
+
+    #[test]
+    fn forward_component_references_are_resolved() {
+        let dict = Dictionary::fix44();
+        let parties = dict.component_by_name("Parties").unwrap();
+        let sub_ids = match parties.items().next().unwrap().kind() {
+            LayoutItemKind::Group(_, items) => match items[3].kind() {
+                LayoutItemKind::Component(component) => component.items().count(),
+                _ => panic!("Expected a component."),
+            },
+            _ => panic!("Expected a group."),
+        };
+        assert_eq!(sub_ids, 1);
+        let num_components = dict.iter_components().count();
+        let mut names: Vec<String> = dict
+            .iter_components()
+            .map(|c| c.name().to_string())
+            .collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), num_components);
+    }
+
     const INVALID_QUICKFIX_SPECS: &[&str] = &[
         include_str!("test_data/quickfix_specs/empty_file.xml"),
         include_str!("test_data/quickfix_specs/missing_components.xml"),
@@ -1908,4 +6863,1084 @@ mod test {
             assert!(dict.is_err(), "{}", spec);
         }
     }
+
+    /// A structural summary of `items`, suitable for comparisons across
+    /// dictionaries.
+    fn layout_summary<'a>(items: impl Iterator<Item = LayoutItem<'a>>) -> Vec<String> {
+        items
+            .map(|item| {
+                let kind = match item.kind() {
+                    LayoutItemKind::Field(field) => format!("field {}", field.name()),
+                    LayoutItemKind::Component(component) => {
+                        format!("component {}", component.name())
+                    }
+                    LayoutItemKind::Group(len_field, items) => format!(
+                        "group {} {:?}",
+                        len_field.name(),
+                        layout_summary(items.into_iter())
+                    ),
+                };
+                format!("{} (required: {})", kind, item.required())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quickfix_export_roundtrip() {
+        for dict in Dictionary::all().iter() {
+            let spec = dict.to_quickfix_spec();
+            let other = Dictionary::from_quickfix_spec(&spec).unwrap();
+            assert_eq!(other.get_version(), dict.get_version());
+            assert_eq!(other.iter_fields().count(), dict.iter_fields().count());
+            for field in dict.iter_fields() {
+                let other_field = other.field_by_tag(field.tag().get() as u32).unwrap();
+                assert_eq!(other_field.name(), field.name());
+                assert_eq!(other_field.fix_datatype(), field.fix_datatype());
+                let enums = |field: Field| {
+                    field.enums().map(|enums| {
+                        enums
+                            .map(|e| (e.value().to_string(), e.description().to_string()))
+                            .collect::<Vec<_>>()
+                    })
+                };
+                assert_eq!(enums(other_field), enums(field));
+            }
+            assert_eq!(other.iter_messages().count(), dict.iter_messages().count());
+            for message in dict.iter_messages() {
+                let other_message = other.message_by_msgtype(message.msg_type()).unwrap();
+                assert_eq!(other_message.name(), message.name());
+                assert_eq!(other_message.is_admin(), message.is_admin());
+                assert_eq!(
+                    layout_summary(other_message.layout()),
+                    layout_summary(message.layout())
+                );
+            }
+            assert_eq!(
+                other.iter_components().count(),
+                dict.iter_components().count()
+            );
+            for component in dict.iter_components() {
+                let other_component = other.component_by_name(component.name()).unwrap();
+                assert_eq!(
+                    layout_summary(other_component.items()),
+                    layout_summary(component.items())
+                );
+            }
+            // Exporting is deterministic.
+            assert_eq!(other.to_quickfix_spec(), spec);
+        }
+    }
+
+    #[test]
+    fn quickfix_export_escapes_special_characters() {
+        let spec = include_str!("resources/quickfix/FIX-4.4.xml").replacen(
+            "enum='B' description='BUY'",
+            "enum='B' description='&lt;Fish &amp; &apos;Chips&apos;&gt;'",
+            1,
+        );
+        let dict = Dictionary::from_quickfix_spec(spec).unwrap();
+        let exported = dict.to_quickfix_spec();
+        assert!(exported.contains("description='&lt;Fish &amp; &apos;Chips&apos;&gt;'"));
+        let dict = Dictionary::from_quickfix_spec(exported).unwrap();
+        let adv_side = dict.field_by_tag(4).unwrap();
+        let buy = adv_side.enums().unwrap().next().unwrap();
+        assert_eq!(buy.description(), "<Fish & 'Chips'>");
+    }
+
+    fn layout_item<'a>(message: &'a Message, name: &str) -> Option<LayoutItem<'a>> {
+        message.layout().find(|item| item.tag_text() == name)
+    }
+
+    #[test]
+    fn editor_adds_fields_components_and_messages() {
+        let fix44 = Dictionary::fix44();
+        let mut editor = fix44.edit();
+        editor
+            .add_field(5001, "VenueStatusCode", FixDatatype::Int)
+            .unwrap()
+            .add_field_enum(5001, "0", "OPEN")
+            .unwrap()
+            .add_field_enum(5001, "1", "HALTED")
+            .unwrap()
+            .add_field(5002, "NoVenueParties", FixDatatype::NumInGroup)
+            .unwrap()
+            .add_component(
+                "VenueParties",
+                "app",
+                vec![LayoutItemSpec::group(
+                    "NoVenueParties",
+                    false,
+                    vec![LayoutItemSpec::field("PartyID", true)],
+                )],
+            )
+            .unwrap()
+            .add_message(
+                "VenueStatus",
+                "U1",
+                "app",
+                vec![
+                    LayoutItemSpec::field("VenueStatusCode", true),
+                    LayoutItemSpec::component("VenueParties", false),
+                ],
+            )
+            .unwrap();
+        let dict = editor.build();
+        assert_eq!(dict.get_version(), "FIX.4.4");
+        let field = dict.field_by_tag(5001).unwrap();
+        assert_eq!(field.name(), "VenueStatusCode");
+        assert_eq!(field.fix_datatype(), FixDatatype::Int);
+        assert_eq!(field.enums().unwrap().count(), 2);
+        let venue_parties = dict.component_by_name("VenueParties").unwrap();
+        assert!(venue_parties.is_group());
+        assert_eq!(venue_parties.category().name(), "app");
+        let message = dict.message_by_name("VenueStatus").unwrap();
+        assert_eq!(message.msg_type(), "U1");
+        assert_eq!(message.category().name(), "app");
+        assert!(layout_item(&message, "VenueStatusCode").unwrap().required());
+        // Standard entities are still there, and the base dictionary is left
+        // untouched.
+        assert!(dict.message_by_msgtype("D").is_some());
+        assert_eq!(dict.iter_fields().count(), fix44.iter_fields().count() + 2);
+        assert!(fix44.field_by_tag(5001).is_none());
+    }
+
+    #[test]
+    fn editor_resolves_categories_from_scratch() {
+        let mut editor = Dictionary::empty().edit();
+        editor
+            .add_field(5001, "VenueStatusCode", FixDatatype::Int)
+            .unwrap()
+            .add_component(
+                "VenueStatus",
+                "Common",
+                vec![LayoutItemSpec::field("VenueStatusCode", true)],
+            )
+            .unwrap()
+            .add_message(
+                "VenueStatusReport",
+                "U1",
+                "app",
+                vec![LayoutItemSpec::component("VenueStatus", true)],
+            )
+            .unwrap();
+        let dict = editor.build();
+        let component = dict.component_by_name("VenueStatus").unwrap();
+        assert_eq!(component.category().name(), "Common");
+        let message = dict.message_by_msgtype("U1").unwrap();
+        assert_eq!(message.category().name(), "app");
+    }
+
+    #[test]
+    fn editor_edits_layouts() {
+        let mut editor = Dictionary::fix44().edit();
+        editor
+            .push_layout_item(
+                LayoutTarget::Message("D"),
+                LayoutItemSpec::field("Account", true),
+            )
+            .unwrap()
+            .remove_layout_item(LayoutTarget::Message("D"), "IOIID")
+            .unwrap()
+            .push_layout_item(
+                LayoutTarget::Group("NoPartyIDs"),
+                LayoutItemSpec::field("Text", false),
+            )
+            .unwrap()
+            .set_layout(
+                LayoutTarget::Component("StandardTrailer"),
+                vec![LayoutItemSpec::field("CheckSum", true)],
+            )
+            .unwrap();
+        let dict = editor.build();
+        let new_order_single = dict.message_by_msgtype("D").unwrap();
+        assert!(layout_item(&new_order_single, "Account")
+            .unwrap()
+            .required());
+        assert!(layout_item(&new_order_single, "IOIID").is_none());
+        assert_eq!(
+            new_order_single.layout().count(),
+            Dictionary::fix44()
+                .message_by_msgtype("D")
+                .unwrap()
+                .layout()
+                .count()
+                - 1
+        );
+        let parties = dict.component_by_name("Parties").unwrap();
+        match parties.items().next().unwrap().kind() {
+            LayoutItemKind::Group(_, items) => {
+                assert_eq!(items.last().unwrap().tag_text(), "Text");
+            }
+            _ => panic!("Expected a group."),
+        }
+        let std_trailer = dict.component_by_name("StandardTrailer").unwrap();
+        assert_eq!(std_trailer.items().count(), 1);
+    }
+
+    #[test]
+    fn editor_errors() {
+        let mut editor = Dictionary::fix44().edit();
+        assert_eq!(
+            editor
+                .add_field(1, "VenueAccount", FixDatatype::String)
+                .err(),
+            Some(EditDictionaryError::AlreadyExists("Field <1>".to_string()))
+        );
+        assert!(matches!(
+            editor.add_field(5001, "Account", FixDatatype::String),
+            Err(EditDictionaryError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            editor.add_field(0, "Zero", FixDatatype::String),
+            Err(EditDictionaryError::InvalidData(_))
+        ));
+        assert!(matches!(
+            editor.add_field_enum(5001, "1", "ONE"),
+            Err(EditDictionaryError::NotFound(_))
+        ));
+        assert!(matches!(
+            editor.add_message("Foo", "D", "app", Vec::new()),
+            Err(EditDictionaryError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            editor.add_component("Foo", "app", vec![LayoutItemSpec::field("Foo", false)]),
+            Err(EditDictionaryError::NotFound(_))
+        ));
+        assert!(matches!(
+            editor.push_layout_item(
+                LayoutTarget::Message("U1"),
+                LayoutItemSpec::field("Text", false)
+            ),
+            Err(EditDictionaryError::NotFound(_))
+        ));
+        assert!(matches!(
+            editor.remove_layout_item(LayoutTarget::Message("0"), "Account"),
+            Err(EditDictionaryError::NotFound(_))
+        ));
+        assert!(matches!(
+            editor.set_layout(LayoutTarget::Group("Account"), Vec::new()),
+            Err(EditDictionaryError::NotFound(_))
+        ));
+        // Failed edits leave no trace.
+        let dict = editor.build();
+        assert!(dict.component_by_name("Foo").is_none());
+        assert_eq!(
+            dict.iter_fields().count(),
+            Dictionary::fix44().iter_fields().count()
+        );
+    }
+
+    const VENUE_OVERLAY: &str = include_str!("test_data/overlays/venue.xml");
+
+    #[test]
+    fn overlay_is_applied() {
+        let dict = Dictionary::fix44().with_overlay(VENUE_OVERLAY).unwrap();
+        assert_eq!(dict.field_by_tag(5001).unwrap().name(), "VenueOrderTag");
+        assert_eq!(
+            dict.field_by_tag(1).unwrap().fix_datatype(),
+            FixDatatype::Int
+        );
+        let ord_type = dict.field_by_tag(40).unwrap();
+        let description = |value| {
+            ord_type
+                .enums()
+                .unwrap()
+                .find(|e| e.value() == value)
+                .map(|e| e.description().to_string())
+        };
+        assert_eq!(description("Z").as_deref(), Some("VENUE_PEGGED"));
+        assert_eq!(description("1").as_deref(), Some("MARKET_ORDER"));
+        assert_eq!(description("2").as_deref(), Some("LIMIT"));
+
+        let new_order_single = dict.message_by_msgtype("D").unwrap();
+        assert!(layout_item(&new_order_single, "Account")
+            .unwrap()
+            .required());
+        assert!(layout_item(&new_order_single, "IOIID").is_none());
+        assert_eq!(
+            new_order_single.layout().last().unwrap().tag_text(),
+            "VenueParties"
+        );
+        let venue_status = dict.message_by_msgtype("U1").unwrap();
+        assert_eq!(venue_status.name(), "VenueStatus");
+        assert_eq!(venue_status.layout().count(), 2);
+        assert_eq!(
+            dict.field_by_tag(5006).unwrap().location(),
+            FieldLocation::Header
+        );
+        let parties = dict.component_by_name("Parties").unwrap();
+        match parties.items().next().unwrap().kind() {
+            LayoutItemKind::Group(_, items) => {
+                assert_eq!(items.last().unwrap().tag_text(), "VenuePartyRank");
+            }
+            _ => panic!("Expected a group."),
+        }
+        assert!(dict.component_by_name("VenueParties").unwrap().is_group());
+        // Overlays don't affect the round trip to QuickFIX.
+        let other = Dictionary::from_quickfix_spec(dict.to_quickfix_spec()).unwrap();
+        assert_eq!(other.iter_fields().count(), dict.iter_fields().count());
+    }
+
+    #[test]
+    fn invalid_overlays() {
+        let overlays = [
+            String::new(),
+            "<overlay />".to_string(),
+            "<fix><fields><field number='5001' name='Foo' /></fields></fix>".to_string(),
+            "<fix><fields><field number='1' name='Foo' /></fields></fix>".to_string(),
+            "<fix><fields><field number='5001' name='Foo' type='FOO' /></fields></fix>".to_string(),
+            "<fix><messages><message msgtype='U1' name='Foo' /></messages></fix>".to_string(),
+            "<fix><messages><message msgtype='D' name='Foo' /></messages></fix>".to_string(),
+            "<fix><groups><group name='Account' /></groups></fix>".to_string(),
+            VENUE_OVERLAY.replace(
+                "<remove name='IOIID' />",
+                "<remove name='Price' /><remove name='Price' />",
+            ),
+            VENUE_OVERLAY.replace("name='VenuePartyID' type", "name='VenuePartyId' type"),
+        ];
+        for overlay in overlays.iter() {
+            assert!(
+                Dictionary::fix44().with_overlay(overlay).is_err(),
+                "{}",
+                overlay
+            );
+        }
+        let overlay = "<fix type='FIX' major='4' minor='4' servicepack='1' />";
+        let dict = Dictionary::fix44().with_overlay(overlay).unwrap();
+        assert_eq!(dict.get_version(), "FIX.4.4-SP1");
+    }
+
+    #[test]
+    fn merge_dictionaries() {
+        let venue = Dictionary::from_orchestra_spec(ORCHESTRA_VENUE_SPEC).unwrap();
+        let mut editor = Dictionary::fix44().edit();
+        editor.set_version("FIX.4.4-ExampleVenue");
+        editor.merge(&venue).unwrap();
+        let dict = editor.build();
+        assert_eq!(dict.get_version(), "FIX.4.4-ExampleVenue");
+        assert_eq!(dict.field_by_tag(5001).unwrap().name(), "VenueOrderTag");
+        assert_eq!(
+            dict.iter_fields().count(),
+            Dictionary::fix44().iter_fields().count() + 1
+        );
+        // Layouts come from the venue's rules of engagement.
+        let new_order_single = dict.message_by_msgtype("D").unwrap();
+        assert!(layout_item(&new_order_single, "VenueOrderTag").is_some());
+        assert!(layout_item(&new_order_single, "IOIID").is_none());
+        assert!(dict.component_by_name("Attachment").is_some());
+        // Everything else is left as is.
+        assert_eq!(
+            dict.iter_messages().count(),
+            Dictionary::fix44().iter_messages().count()
+        );
+        assert!(layout_item(&dict.message_by_msgtype("F").unwrap(), "OrigClOrdID").is_some());
+    }
+
+    #[test]
+    fn diff_of_equal_dictionaries_is_empty() {
+        let diff = Dictionary::fix44().diff(&Dictionary::fix44());
+        assert!(diff.is_empty());
+        assert_eq!(
+            diff.to_markdown(),
+            "# FIX.4.4 → FIX.4.4\n\nNo differences.\n"
+        );
+    }
+
+    #[test]
+    fn diff_of_overlay() {
+        let old = Dictionary::fix44();
+        let new = old.with_overlay(VENUE_OVERLAY).unwrap();
+        let diff = old.diff(&new);
+        let field = |tag| diff.fields.iter().find(|f| f.tag == tag).unwrap();
+        assert_eq!(field(5001).name, "VenueOrderTag");
+        assert_eq!(
+            field(5001).changes,
+            vec![FieldChange::Added(FixDatatype::String)]
+        );
+        assert_eq!(
+            field(1).changes,
+            vec![FieldChange::Datatype {
+                old: FixDatatype::String,
+                new: FixDatatype::Int
+            }]
+        );
+        assert_eq!(
+            field(40).changes,
+            vec![
+                FieldChange::Enum {
+                    value: "1".to_string(),
+                    description: Change::Modified {
+                        old: "MARKET".to_string(),
+                        new: "MARKET_ORDER".to_string()
+                    }
+                },
+                FieldChange::Enum {
+                    value: "Z".to_string(),
+                    description: Change::Added("VENUE_PEGGED".to_string())
+                },
+            ]
+        );
+        assert_eq!(diff.fields.len(), 8);
+
+        let message = |msg_type| {
+            diff.messages
+                .iter()
+                .find(|m| m.msg_type.as_deref() == Some(msg_type))
+                .unwrap()
+        };
+        assert_eq!(message("U1").changes, vec![LayoutChange::Added]);
+        let new_order_single = message("D");
+        assert_eq!(new_order_single.name, "NewOrderSingle");
+        let item = |path: &str, required| LayoutChange::Item {
+            path: path.to_string(),
+            required,
+        };
+        assert_eq!(
+            new_order_single.changes,
+            vec![
+                item(
+                    "Account",
+                    Change::Modified {
+                        old: false,
+                        new: true
+                    }
+                ),
+                item("VenueOrderTag", Change::Added(false)),
+                item("VenueParties", Change::Added(false)),
+                item("IOIID", Change::Removed(false)),
+            ]
+        );
+        assert_eq!(diff.messages.len(), 2);
+
+        let component = |name| diff.components.iter().find(|c| c.name == name).unwrap();
+        assert_eq!(component("VenueParties").changes, vec![LayoutChange::Added]);
+        assert_eq!(
+            component("Parties").changes,
+            vec![item("NoPartyIDs.VenuePartyRank", Change::Added(false))]
+        );
+        assert_eq!(
+            component("StandardHeader").changes,
+            vec![item("VenueSessionTag", Change::Added(false))]
+        );
+
+        let reverse_diff = new.diff(&old);
+        assert_eq!(reverse_diff.fields.len(), diff.fields.len());
+        assert_eq!(
+            reverse_diff
+                .fields
+                .iter()
+                .find(|f| f.tag == 5001)
+                .unwrap()
+                .changes,
+            vec![FieldChange::Removed]
+        );
+    }
+
+    #[test]
+    fn diff_to_markdown() {
+        let old = Dictionary::fix44();
+        let mut editor = old.with_overlay(VENUE_OVERLAY).unwrap().edit();
+        editor.set_version("FIX.4.4-Venue");
+        let new = editor.build();
+        let markdown = old.diff(&new).to_markdown();
+        assert!(markdown.starts_with("# FIX.4.4 → FIX.4.4-Venue\n\n## Fields\n\n"));
+        for line in [
+            "- **Added** `VenueOrderTag <5001>` (`String`)",
+            "- **Changed** `Account <1>`:\n  - Datatype changed from `String` to `int`",
+            "  - Value `1` changed from \"MARKET\" to \"MARKET_ORDER\"",
+            "  - Added value `Z`: VENUE_PEGGED",
+            "## Components\n\n- **Changed** `Parties`:\n  - Added `NoPartyIDs.VenuePartyRank` (optional)",
+            "- **Added** `VenueParties`",
+            "## Messages",
+            "- **Changed** `NewOrderSingle` (`D`):",
+            "  - `Account` is now required",
+            "  - Removed `IOIID` (optional)",
+            "- **Added** `VenueStatus` (`U1`)",
+        ] {
+            assert!(markdown.contains(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn diff_between_fix_versions() {
+        let diff = fix_repository("FIX.4.2").diff(&fix_repository("FIX.4.4"));
+        assert_eq!(diff.old_version, "FIX.4.2");
+        assert_eq!(diff.new_version, "FIX.4.4");
+        let no_party_ids = diff.fields.iter().find(|f| f.tag == 453).unwrap();
+        assert_eq!(
+            no_party_ids.changes,
+            vec![FieldChange::Added(FixDatatype::NumInGroup)]
+        );
+        assert!(diff
+            .components
+            .iter()
+            .any(|c| c.name == "Parties" && c.changes == vec![LayoutChange::Added]));
+        assert!(
+            diff.messages
+                .iter()
+                .any(|m| m.msg_type.as_deref() == Some("AE")
+                    && m.changes == vec![LayoutChange::Added])
+        );
+    }
+
+    #[test]
+    fn quickfix_export_of_other_sources() {
+        let dict = fix_repository("FIX.4.4");
+        let exported = Dictionary::from_quickfix_spec(dict.to_quickfix_spec()).unwrap();
+        assert_eq!(exported.get_version(), "FIX.4.4");
+        assert_eq!(exported.iter_fields().count(), dict.iter_fields().count());
+        let msgcat = |msgtype| exported.message_by_msgtype(msgtype).unwrap().category();
+        assert_eq!(msgcat("0").name(), "admin");
+        assert_eq!(msgcat("D").name(), "app");
+        let side = exported.field_by_tag(54).unwrap();
+        let buy = side.enums().unwrap().find(|e| e.value() == "1").unwrap();
+        assert_eq!(buy.description(), "Buy");
+
+        let dict = Dictionary::from_orchestra_spec(ORCHESTRA_VENUE_SPEC).unwrap();
+        let exported = Dictionary::from_quickfix_spec(dict.to_quickfix_spec()).unwrap();
+        // Only base scenarios are exported.
+        assert_eq!(exported.iter_messages().count(), 3);
+        assert_eq!(exported.field_by_tag(5001).unwrap().name(), "VenueOrderTag");
+        assert!(exported.component_by_name("Parties").is_some());
+    }
+
+    const FIX_REPOSITORY_DIR: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/resources/repositories/fix_repository_2010_edition_20140507"
+    );
+
+    fn fix_repository(version: &str) -> Dictionary {
+        Dictionary::from_fix_repository(Path::new(FIX_REPOSITORY_DIR).join(version)).unwrap()
+    }
+
+    #[test]
+    fn editor_imports_metadata() {
+        let spec = include_str!("resources/quickfix/FIX-4.4.xml");
+        let dict = Dictionary::from_quickfix_spec(spec).unwrap();
+        let new_order_single = dict.message_by_msgtype("D").unwrap();
+        assert_eq!(new_order_single.category().name(), "app");
+        assert!(new_order_single.section().is_none());
+        assert!(new_order_single.pedigree().added().is_none());
+        assert!(dict.field_by_tag(1).unwrap().description().is_none());
+
+        let mut editor = dict.edit();
+        editor.import_metadata(&fix_repository("FIX.4.4"));
+        let dict = editor.build();
+        let new_order_single = dict.message_by_msgtype("D").unwrap();
+        assert_eq!(
+            new_order_single.category().name(),
+            "SingleGeneralOrderHandling"
+        );
+        assert_eq!(new_order_single.section().unwrap().id(), "Trade");
+        assert_eq!(new_order_single.pedigree().added(), Some("FIX.2.7"));
+        assert!(new_order_single
+            .description()
+            .starts_with("The new order message"));
+        assert!(!new_order_single.is_admin());
+        // Layouts are left untouched.
+        assert_eq!(
+            layout_summary(new_order_single.layout()),
+            layout_summary(
+                Dictionary::fix44()
+                    .message_by_msgtype("D")
+                    .unwrap()
+                    .layout()
+            )
+        );
+        let account = dict.field_by_tag(1).unwrap();
+        assert!(account
+            .description()
+            .unwrap()
+            .starts_with("Account mnemonic"));
+        assert_eq!(account.pedigree().added(), Some("FIX.2.7"));
+        let repo_collateral_security_type = dict.field_by_tag(239).unwrap().pedigree();
+        assert_eq!(repo_collateral_security_type.deprecated(), Some("FIX.4.4"));
+        assert!(repo_collateral_security_type.is_deprecated());
+        let parties = dict.component_by_name("Parties").unwrap();
+        assert_eq!(parties.category().name(), "Common");
+    }
+
+    #[test]
+    fn builtin_dictionaries_have_metadata() {
+        let dict = Dictionary::fix44();
+        let section = |msg_type| {
+            let message = dict.message_by_msgtype(msg_type).unwrap();
+            message.section().unwrap().id().to_string()
+        };
+        assert_eq!(section("0"), "Session");
+        assert_eq!(section("R"), "PreTrade");
+        assert_eq!(section("D"), "Trade");
+        assert_eq!(section("J"), "PostTrade");
+        let trade = dict.section_by_id("Trade").unwrap();
+        assert!(trade
+            .categories()
+            .any(|c| c.name() == "SingleGeneralOrderHandling"));
+        assert!(dict
+            .field_by_tag(1)
+            .unwrap()
+            .description()
+            .unwrap()
+            .starts_with("Account mnemonic"));
+        // See `Dictionary::from_builtin_spec`.
+        let dicts = Dictionary::all();
+        for dict in dicts.iter().filter(|dict| dict.get_version() != "FIX.4.3") {
+            for message in dict.iter_messages() {
+                let section = message.section().unwrap();
+                assert_eq!(message.is_admin(), section.id() == "Session");
+                assert!(message.pedigree().added().is_some());
+            }
+            let logon = dict.message_by_msgtype("A");
+            assert!(logon.map_or(true, |logon| logon.is_admin()));
+        }
+    }
+
+    #[test]
+    fn fix_repository_versions_are_ok() {
+        // The vendored FIX 4.3 `Fields.xml` is a copy of FIX 4.2's and lacks
+        // many fields.
+        let versions = [
+            ("FIX.4.0", "FIX.4.0"),
+            ("FIX.4.1", "FIX.4.1"),
+            ("FIX.4.2", "FIX.4.2"),
+            ("FIX.4.4", "FIX.4.4"),
+            ("FIX.5.0", "FIX.5.0"),
+            ("FIX.5.0SP1", "FIX.5.0-SP1"),
+            ("FIX.5.0SP2", "FIX.5.0-SP2"),
+            ("FIXT.1.1", "FIXT.1.1"),
+        ];
+        for (dir, version) in versions {
+            let dict = fix_repository(dir);
+            assert_eq!(dict.get_version(), version);
+            assert!(dict.component_by_name("StandardHeader").is_some());
+            assert!(dict.component_by_name("StandardTrailer").is_some());
+        }
+        let base_dir = Path::new(FIX_REPOSITORY_DIR).join("FIX.4.4").join("Base");
+        assert!(Dictionary::from_fix_repository(base_dir).is_ok());
+        assert!(Dictionary::from_fix_repository(FIX_REPOSITORY_DIR).is_err());
+    }
+
+    #[test]
+    fn fix_repository_fix44_is_equivalent_to_quickfix() {
+        let repository = fix_repository("FIX.4.4");
+        let quickfix = Dictionary::fix44();
+        assert_eq!(
+            repository.iter_fields().count(),
+            quickfix.iter_fields().count()
+        );
+        for field in quickfix.iter_fields() {
+            let other = repository.field_by_tag(field.tag().get() as u32).unwrap();
+            assert_eq!(other.fix_datatype(), field.fix_datatype());
+        }
+        assert_eq!(
+            repository.iter_messages().count(),
+            quickfix.iter_messages().count()
+        );
+        for message in quickfix.iter_messages() {
+            let other = repository.message_by_msgtype(message.msg_type()).unwrap();
+            assert_eq!(other.name(), message.name());
+        }
+        let parties = repository.component_by_name("Parties").unwrap();
+        assert!(parties.is_group());
+        match parties.items().next().unwrap().kind() {
+            LayoutItemKind::Group(len_field, items) => {
+                assert_eq!(len_field.tag().get(), 453);
+                assert_eq!(items.len(), 4);
+                assert!(
+                    matches!(items[3].kind(), LayoutItemKind::Component(c) if c.name() == "PtysSubGrp")
+                );
+            }
+            _ => panic!("Expected a group."),
+        }
+        let data_tag = |tag| {
+            repository
+                .field_by_tag(tag)
+                .unwrap()
+                .associated_data_field()
+                .map(|field| field.tag().get())
+        };
+        assert_eq!(data_tag(95), Some(96));
+        assert_eq!(data_tag(239), None);
+    }
+
+    #[test]
+    fn fix_repository_fix44_metadata() {
+        let dict = fix_repository("FIX.4.4");
+        let field = dict.field_by_tag(239).unwrap();
+        assert_eq!(field.pedigree().added(), Some("FIX.4.3"));
+        assert_eq!(field.pedigree().deprecated(), Some("FIX.4.4"));
+        assert!(field.pedigree().is_deprecated());
+        assert!(!dict.field_by_tag(54).unwrap().pedigree().is_deprecated());
+        assert!(field.description().unwrap().contains("DEPRECATED"));
+        let side = dict.field_by_tag(54).unwrap();
+        let buy = side.enums().unwrap().find(|e| e.value() == "1").unwrap();
+        assert_eq!(buy.symbolic_name(), Some("Buy"));
+        assert_eq!(buy.description(), "Buy");
+        let abbreviation = dict.abbreviation_for("Account").unwrap();
+        assert_eq!(abbreviation.term(), "Account");
+        assert_eq!(abbreviation.abbreviation(), "Acct");
+        let category = dict.message_by_msgtype("D").unwrap().category();
+        assert_eq!(category.name(), "SingleGeneralOrderHandling");
+        let section = category.section().unwrap();
+        assert_eq!(section.id(), "Trade");
+        assert!(section
+            .categories()
+            .any(|c| c.name() == "SingleGeneralOrderHandling"));
+        assert_eq!(dict.section_by_id("Session").unwrap().display_order(), 0);
+        assert!(dict
+            .datatype_by_name("Length")
+            .map(|dt| dt.basetype() == FixDatatype::Length)
+            .unwrap());
+    }
+
+    #[test]
+    fn fix_repository_ep254_is_ok() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("resources/repositories/FIXRepository_FIX.5.0SP2_EP254/Basic");
+        let dict = Dictionary::from_fix_repository(dir).unwrap();
+        assert_eq!(dict.get_version(), "FIX.5.0-SP2");
+        let field = dict.field_by_tag(2846).unwrap();
+        assert_eq!(field.name(), "FundingSource");
+        assert_eq!(field.pedigree().added_ep(), Some(254));
+        assert_eq!(
+            dict.field_by_tag(40039).unwrap().fix_datatype(),
+            FixDatatype::String
+        );
+    }
+
+    const ORCHESTRA_VENUE_SPEC: &str = include_str!("test_data/orchestra/venue.xml");
+
+    #[test]
+    fn orchestra_venue_spec_is_ok() {
+        let dict = Dictionary::from_orchestra_spec(ORCHESTRA_VENUE_SPEC).unwrap();
+        assert_eq!(dict.get_version(), "FIX.4.4");
+        let custom_field = dict.field_by_tag(5001).unwrap();
+        assert_eq!(custom_field.name(), "VenueOrderTag");
+        assert_eq!(custom_field.location(), FieldLocation::Body);
+        assert_eq!(custom_field.pedigree().added(), Some("FIX.4.4"));
+        assert_eq!(
+            dict.field_by_tag(9).unwrap().location(),
+            FieldLocation::Header
+        );
+        assert_eq!(
+            dict.field_by_tag(10).unwrap().location(),
+            FieldLocation::Trailer
+        );
+        assert_eq!(
+            dict.field_by_tag(44).unwrap().fix_datatype(),
+            FixDatatype::Price
+        );
+        let raw_data_length = dict.field_by_tag(95).unwrap();
+        assert_eq!(
+            raw_data_length.associated_data_field().unwrap().tag().get(),
+            96
+        );
+        let new_order_single = dict.message_by_msgtype("D").unwrap();
+        assert_eq!(new_order_single.description(), "Submits a new order.");
+        assert_eq!(
+            new_order_single.category().name(),
+            "SingleGeneralOrderHandling"
+        );
+        assert_eq!(new_order_single.category().section().unwrap().id(), "Trade");
+        let parties = new_order_single
+            .layout()
+            .find(|item| item.tag_text() == "Parties")
+            .unwrap();
+        assert!(parties.required());
+        match parties.kind() {
+            LayoutItemKind::Component(component) => {
+                assert!(component.is_group());
+                match component.items().next().unwrap().kind() {
+                    LayoutItemKind::Group(len_field, items) => {
+                        assert_eq!(len_field.tag().get(), 453);
+                        assert_eq!(items.len(), 2);
+                        assert!(items[0].required());
+                    }
+                    _ => panic!("Expected a group."),
+                }
+            }
+            _ => panic!("Expected a component."),
+        }
+    }
+
+    #[test]
+    fn orchestra_code_sets() {
+        let dict = Dictionary::from_orchestra_spec(ORCHESTRA_VENUE_SPEC).unwrap();
+        let side = dict.field_by_tag(54).unwrap();
+        assert_eq!(side.fix_datatype(), FixDatatype::Char);
+        assert_eq!(side.description(), Some("Side of order."));
+        let values: Vec<_> = side
+            .enums()
+            .unwrap()
+            .map(|e| {
+                (
+                    e.value().to_string(),
+                    e.symbolic_name().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("1".to_string(), "Buy".to_string()),
+                ("2".to_string(), "Sell".to_string())
+            ]
+        );
+        let ord_type = dict.field_by_tag(40).unwrap();
+        let description = |value| {
+            ord_type
+                .enums()
+                .unwrap()
+                .find(|e| e.value() == value)
+                .map(|e| e.description().to_string())
+        };
+        assert_eq!(
+            description("2").as_deref(),
+            Some("Limit order, i.e. Price is required.")
+        );
+        // Codes without documentation are described by their name.
+        assert_eq!(description("1").as_deref(), Some("Market"));
+        assert_eq!(
+            dict.field_by_tag(452).unwrap().fix_datatype(),
+            FixDatatype::Int
+        );
+    }
+
+    #[test]
+    fn orchestra_message_scenarios() {
+        let dict = Dictionary::from_orchestra_spec(ORCHESTRA_VENUE_SPEC).unwrap();
+        let base = dict.message_by_msgtype("8").unwrap();
+        assert_eq!(base.scenario(), BASE_SCENARIO);
+        assert_eq!(
+            dict.message_by_name("ExecutionReport").unwrap().scenario(),
+            BASE_SCENARIO
+        );
+        let canceled = dict.message_by_scenario("8", "Canceled").unwrap();
+        assert_eq!(canceled.scenario(), "Canceled");
+        let is_required = |message: &Message, name| {
+            message
+                .layout()
+                .find(|item| item.tag_text() == name)
+                .map(|item| item.required())
+        };
+        assert_eq!(is_required(&base, "ClOrdID"), Some(false));
+        assert_eq!(is_required(&canceled, "ClOrdID"), Some(true));
+        assert_eq!(is_required(&canceled, "ExecType"), Some(true));
+        assert_eq!(is_required(&canceled, "VenueOrderTag"), None);
+        assert!(dict.message_by_scenario("8", "Fill").is_none());
+        assert_eq!(
+            dict.iter_messages().filter(|m| m.msg_type() == "8").count(),
+            2
+        );
+    }
+
+    #[test]
+    fn orchestra_presence_rules() {
+        let dict = Dictionary::from_orchestra_spec(ORCHESTRA_VENUE_SPEC).unwrap();
+        let new_order_single = dict.message_by_msgtype("D").unwrap();
+        let item = |name| {
+            new_order_single
+                .layout()
+                .find(|item| item.tag_text() == name)
+                .unwrap()
+        };
+        let price = item("Price");
+        assert!(!price.required());
+        let rules: Vec<_> = price.presence_rules().collect();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].name(), Some("PriceRequiredForLimitOrders"));
+        assert_eq!(rules[0].presence(), Presence::Required);
+        assert_eq!(
+            rules[0].condition(),
+            "OrdType == ^Limit || OrdType == ^StopLimit"
+        );
+        assert_eq!(rules[1].presence(), Presence::Forbidden);
+        let stop_px = item("StopPx");
+        let rule = stop_px.presence_rules().next().unwrap();
+        assert_eq!(rule.name(), None);
+        assert_eq!(rule.condition(), "OrdType in {^Stop, ^StopLimit}");
+        assert_eq!(item("OrdType").presence_rules().count(), 0);
+        let layout = dict.lookup_tables().message_layout("D").unwrap();
+        let flat_price = layout.items().iter().find(|i| i.tag().get() == 44).unwrap();
+        assert_eq!(flat_price.presence_rules().len(), 2);
+        let ord_type = |value: &'static [u8]| {
+            move |tag: TagU16| if tag.get() == 40 { Some(value) } else { None }
+        };
+        assert_eq!(flat_price.presence(ord_type(b"2")).0, Presence::Required);
+        assert_eq!(flat_price.presence(ord_type(b"1")).0, Presence::Forbidden);
+        assert_eq!(flat_price.presence(|_| None).0, Presence::Optional);
+    }
+
+    #[test]
+    fn field_constraints() {
+        let dict = Dictionary::from_orchestra_spec(ORCHESTRA_VENUE_SPEC).unwrap();
+        let order_qty = dict.field_by_tag(38).unwrap();
+        assert_eq!(order_qty.constraints().min, Some(1.0));
+        assert_eq!(order_qty.constraints().max, Some(1_000_000.0));
+        assert!(dict.field_by_tag(11).unwrap().constraints().is_empty());
+
+        let overlay = r#"
+            <fix>
+                <fields>
+                    <field number='11' maxlength='20' pattern='[A-Z]{3}-[0-9]+' />
+                    <field number='44' min='0.01' />
+                    <field number='5001' name='VenueOrderTag' type='STRING' maxlength='8' />
+                </fields>
+            </fix>
+        "#;
+        let dict = Dictionary::fix44().with_overlay(overlay).unwrap();
+        let dict = Dictionary::from_bytes(&dict.to_bytes()).unwrap();
+        assert_eq!(
+            dict.field_by_tag(11).unwrap().constraints(),
+            &FieldConstraints {
+                max_length: Some(20),
+                pattern: Some("[A-Z]{3}-[0-9]+".to_string()),
+                ..FieldConstraints::default()
+            }
+        );
+        assert_eq!(dict.field_by_tag(44).unwrap().constraints().min, Some(0.01));
+        assert_eq!(
+            dict.field_by_tag(5001).unwrap().constraints().max_length,
+            Some(8)
+        );
+        let dict = dict
+            .with_overlay("<fix><fields><field number='44' max='1000' /></fields></fix>")
+            .unwrap();
+        let price = dict.field_by_tag(44).unwrap();
+        assert_eq!(price.constraints().min, Some(0.01));
+        assert_eq!(price.constraints().max, Some(1000.0));
+
+        for field in [
+            "<field number='44' min='abc' />",
+            "<field number='44' maxlength='-1' />",
+            "<field number='44' min='10' max='1' />",
+            "<field number='44' pattern='(' />",
+        ] {
+            let overlay = format!("<fix><fields>{}</fields></fix>", field);
+            assert!(
+                Dictionary::fix44().with_overlay(&overlay).is_err(),
+                "{}",
+                field
+            );
+        }
+        let invalid_pattern = FieldConstraints {
+            pattern: Some("[A-Z".to_string()),
+            ..FieldConstraints::default()
+        };
+        assert!(matches!(
+            Dictionary::fix44()
+                .edit()
+                .set_field_constraints(58, invalid_pattern),
+            Err(EditDictionaryError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn conditions() {
+        let dict = Dictionary::fix44();
+        let fields: &[(u16, &[u8])] = &[(40, b"4"), (38, b"100.0"), (55, b"ACME")];
+        let eval = |text: &str| {
+            Condition::compile(text, &dict)
+                .unwrap_or_else(|| panic!("{}", text))
+                .evaluate(|tag| fields.iter().find(|f| f.0 == tag.get()).map(|f| f.1))
+        };
+        assert!(eval("OrdType == ^StopLimit"));
+        assert!(eval("OrdType == ^STOP_LIMIT && !(OrdType == ^Limit)"));
+        assert!(eval("OrdType in {^Stop, ^StopLimit}"));
+        assert!(eval(
+            "OrderQty == 100 && OrderQty >= 100 && OrderQty < 100.5"
+        ));
+        assert!(eval("Symbol == \"ACME\" && Symbol != 'ACME2'"));
+        assert!(eval("exists Symbol && !exists Price"));
+        assert!(eval("Price > 1 || OrdType == '4'"));
+        assert!(!eval("Price != 1"));
+        assert!(!eval("Symbol > 1"));
+        for text in [
+            "OrdType == ^NoSuchValue",
+            "NoSuchField == 1",
+            "OrdType ==",
+            "OrdType = 1",
+            "(exists Price",
+            "exists Price Price",
+            "OrdType in {}",
+            "OrdType == ^",
+            "Symbol == \"ACME",
+        ] {
+            assert!(Condition::compile(text, &dict).is_none(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn overlay_presence_rules() {
+        let overlay = r#"
+            <fix>
+                <rules>
+                    <message msgtype='D'>
+                        <rule item='Price' name='PriceForLimitOrders'>
+                            <when>OrdType == ^Limit</when>
+                        </rule>
+                    </message>
+                    <header>
+                        <rule item='OnBehalfOfCompID' presence='forbidden'>
+                            <when>exists DeliverToCompID</when>
+                        </rule>
+                    </header>
+                </rules>
+            </fix>
+        "#;
+        let dict = Dictionary::fix44().with_overlay(overlay).unwrap();
+        let item = |message: Message, name| {
+            message
+                .layout()
+                .find(|item| item.tag_text() == name)
+                .unwrap()
+                .presence_rules()
+                .map(|rule| (rule.name().map(str::to_string), rule.presence()))
+                .collect::<Vec<_>>()
+        };
+        let new_order_single = dict.message_by_msgtype("D").unwrap();
+        assert_eq!(
+            item(new_order_single, "Price"),
+            vec![(Some("PriceForLimitOrders".to_string()), Presence::Required)]
+        );
+        let header = dict.lookup_tables().header_layout();
+        let on_behalf_of = header.items().iter().find(|i| i.tag().get() == 115);
+        assert_eq!(
+            on_behalf_of.unwrap().presence_rules()[0].presence(),
+            Presence::Forbidden
+        );
+        let dict = Dictionary::from_bytes(&dict.to_bytes()).unwrap();
+        let new_order_single = dict.message_by_msgtype("D").unwrap();
+        assert_eq!(item(new_order_single, "Price").len(), 1);
+        for rules in [
+            "<message msgtype='D'><rule item='NoSuchField'><when>exists Price</when></rule></message>",
+            "<message msgtype='D'><rule item='Price'><when>Price ==</when></rule></message>",
+            "<message msgtype='D'><rule item='Price' /></message>",
+            "<message msgtype='D'><rule item='Price' presence='maybe'><when>exists Price</when></rule></message>",
+            "<message msgtype='D'><rule item='TestReqID'><when>exists Price</when></rule></message>",
+            "<message msgtype='ZZ'><rule item='Price'><when>exists Price</when></rule></message>",
+            "<field name='Price' />",
+        ] {
+            let overlay = format!("<fix><rules>{}</rules></fix>", rules);
+            assert!(Dictionary::fix44().with_overlay(&overlay).is_err(), "{}", rules);
+        }
+    }
+
+    #[test]
+    fn invalid_orchestra_specs() {
+        let specs = [
+            String::new(),
+            include_str!("resources/quickfix/FIX-4.4.xml").to_string(),
+            ORCHESTRA_VENUE_SPEC.replace(
+                r#"<fixr:fieldRef id="112"/>"#,
+                r#"<fixr:fieldRef id="113"/>"#,
+            ),
+            ORCHESTRA_VENUE_SPEC
+                .replace(r#"<fixr:groupRef id="2029""#, r#"<fixr:groupRef id="2030""#),
+            ORCHESTRA_VENUE_SPEC.replace(r#"type="SideCodeSet""#, r#"type="SideCodes""#),
+            ORCHESTRA_VENUE_SPEC.replace(r#"<fixr:numInGroup id="453"/>"#, ""),
+        ];
+        for spec in specs.iter() {
+            assert!(Dictionary::from_orchestra_spec(spec).is_err());
+        }
+    }
 }
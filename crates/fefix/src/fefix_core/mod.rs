@@ -1,5 +1,14 @@
 //! FerrumFIX code generation utilities.
 
+/// Loads a built-in [`Dictionary`](dict::Dictionary) from the binary form
+/// which `build.rs` produces out of its QuickFIX specification file.
+macro_rules! builtin_dictionary {
+    ($name:literal, $repository_version:literal) => {
+        Dictionary::from_bytes(include_bytes!(concat!(env!("OUT_DIR"), "/", $name, ".bin")))
+            .unwrap()
+    };
+}
+
 #[cfg(feature = "codegen")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "codegen")))]
 pub mod codegen;
@@ -0,0 +1,311 @@
+use crate::Buffer;
+use crate::FixValue;
+use encoding_rs::Encoding;
+use std::borrow::Cow;
+use std::fmt;
+
+const ERR_UNKNOWN: &str = "Unknown or unsupported character encoding.";
+
+/// The character encoding of a FIX message, as specified by
+/// `MessageEncoding <347>`.
+///
+/// FIX mandates ISO-8859-1 (Latin alphabet No. 1) for all string fields, with
+/// the exception of the `Encoded*` family of fields (e.g.
+/// `EncodedText <355>`, `EncodedIssuer <349>`), which carry their contents
+/// in the encoding advertised by `MessageEncoding <347>` and are always
+/// preceded by a `Length` field (e.g. `EncodedTextLen <354>`). Rust strings
+/// are UTF-8, so [`MessageEncoding`] takes care of the conversion.
+///
+/// All encodings supported by [`encoding_rs`] are available, including
+/// `Shift_JIS`, `EUC-JP`, `ISO-2022-JP`, `GB18030`, `Big5`, and `EUC-KR`.
+/// Unlike [`encoding_rs`], the `ISO-8859-1` label is mapped to actual
+/// Latin-1 rather than `windows-1252`.
+///
+/// # Examples
+///
+/// ```
+/// use fefix::fix_value::MessageEncoding;
+/// use fefix::FixValue;
+///
+/// let encoding = MessageEncoding::deserialize(b"Shift_JIS").unwrap();
+/// assert_eq!(encoding, MessageEncoding::SHIFT_JIS);
+///
+/// let bytes = encoding.encode("東京").unwrap();
+/// assert_eq!(&bytes[..], b"\x93\x8c\x8b\x9e");
+/// assert_eq!(encoding.decode(&bytes).unwrap(), "東京");
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MessageEncoding {
+    kind: Kind,
+}
+
+// `encoding_rs` encodings are `static`s, which can't be referenced from
+// `const`s; well-known encodings are thus resolved lazily.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum Kind {
+    Latin1,
+    Utf8,
+    ShiftJis,
+    EucJp,
+    Iso2022Jp,
+    Other(&'static Encoding),
+}
+
+impl MessageEncoding {
+    /// ISO-8859-1, i.e. Latin alphabet No. 1. This is the FIX default.
+    pub const ISO_8859_1: Self = Self { kind: Kind::Latin1 };
+    /// UTF-8.
+    pub const UTF_8: Self = Self { kind: Kind::Utf8 };
+    /// Shift_JIS.
+    pub const SHIFT_JIS: Self = Self {
+        kind: Kind::ShiftJis,
+    };
+    /// EUC-JP.
+    pub const EUC_JP: Self = Self { kind: Kind::EucJp };
+    /// ISO-2022-JP.
+    pub const ISO_2022_JP: Self = Self {
+        kind: Kind::Iso2022Jp,
+    };
+
+    /// Creates a [`MessageEncoding`] from an [`encoding_rs::Encoding`].
+    pub fn from_encoding(encoding: &'static Encoding) -> Self {
+        let kind = if encoding == encoding_rs::UTF_8 {
+            Kind::Utf8
+        } else if encoding == encoding_rs::SHIFT_JIS {
+            Kind::ShiftJis
+        } else if encoding == encoding_rs::EUC_JP {
+            Kind::EucJp
+        } else if encoding == encoding_rs::ISO_2022_JP {
+            Kind::Iso2022Jp
+        } else {
+            Kind::Other(encoding)
+        };
+        Self { kind }
+    }
+
+    /// Returns the [`encoding_rs::Encoding`] of `self`, or [`None`] for
+    /// ISO-8859-1, which [`encoding_rs`] doesn't support.
+    fn encoding(&self) -> Option<&'static Encoding> {
+        match self.kind {
+            Kind::Latin1 => None,
+            Kind::Utf8 => Some(encoding_rs::UTF_8),
+            Kind::ShiftJis => Some(encoding_rs::SHIFT_JIS),
+            Kind::EucJp => Some(encoding_rs::EUC_JP),
+            Kind::Iso2022Jp => Some(encoding_rs::ISO_2022_JP),
+            Kind::Other(encoding) => Some(encoding),
+        }
+    }
+
+    /// Looks up a [`MessageEncoding`] by its `MessageEncoding <347>` value,
+    /// e.g. `b"Shift_JIS"` or `b"UTF-8"`. Labels are case-insensitive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::fix_value::MessageEncoding;
+    ///
+    /// assert_eq!(MessageEncoding::for_label(b"utf-8"), Some(MessageEncoding::UTF_8));
+    /// assert_eq!(MessageEncoding::for_label(b"latin1"), Some(MessageEncoding::ISO_8859_1));
+    /// assert_eq!(MessageEncoding::for_label(b"foobar"), None);
+    /// ```
+    pub fn for_label(label: &[u8]) -> Option<Self> {
+        let is_latin1 = [&b"ISO-8859-1"[..], b"ISO8859-1", b"ISO_8859-1", b"latin1"]
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(label));
+        if is_latin1 {
+            Some(Self::ISO_8859_1)
+        } else {
+            Encoding::for_label_no_replacement(label).map(Self::from_encoding)
+        }
+    }
+
+    /// Returns the canonical name of `self`, which is also its
+    /// `MessageEncoding <347>` value.
+    pub fn name(&self) -> &'static str {
+        match self.encoding() {
+            None => "ISO-8859-1",
+            Some(encoding) => encoding.name(),
+        }
+    }
+
+    /// Decodes `data` into a Rust string. No allocation takes place when `data`
+    /// is already valid UTF-8 (e.g. ASCII-only).
+    ///
+    /// Malformed byte sequences result in [`EncodingError::Malformed`].
+    pub fn decode<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, str>, EncodingError> {
+        match self.encoding() {
+            None if data.is_ascii() => {
+                // ASCII is a subset of UTF-8.
+                Ok(Cow::Borrowed(std::str::from_utf8(data).unwrap()))
+            }
+            None => Ok(Cow::Owned(data.iter().map(|byte| *byte as char).collect())),
+            Some(encoding) => encoding
+                .decode_without_bom_handling_and_without_replacement(data)
+                .ok_or(EncodingError::Malformed),
+        }
+    }
+
+    /// Encodes `s` with `self`. No allocation takes place when the byte
+    /// representation of `s` is the same in both UTF-8 and `self`.
+    ///
+    /// Characters that can't be represented by `self` result in
+    /// [`EncodingError::Unmappable`].
+    pub fn encode<'a>(&self, s: &'a str) -> Result<Cow<'a, [u8]>, EncodingError> {
+        match self.encoding() {
+            None if s.is_ascii() => Ok(Cow::Borrowed(s.as_bytes())),
+            None => s
+                .chars()
+                .map(|c| u8::try_from(c as u32).map_err(|_| EncodingError::Unmappable))
+                .collect::<Result<Vec<u8>, _>>()
+                .map(Cow::Owned),
+            Some(encoding) => {
+                let (bytes, actual_encoding, had_errors) = encoding.encode(s);
+                // `encoding_rs` falls back to UTF-8 for encodings which can't be
+                // used for output (e.g. UTF-16).
+                if had_errors || actual_encoding != encoding {
+                    Err(EncodingError::Unmappable)
+                } else {
+                    Ok(bytes)
+                }
+            }
+        }
+    }
+}
+
+impl Default for MessageEncoding {
+    fn default() -> Self {
+        Self::ISO_8859_1
+    }
+}
+
+impl fmt::Debug for MessageEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MessageEncoding")
+            .field(&self.name())
+            .finish()
+    }
+}
+
+impl fmt::Display for MessageEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl<'a> FixValue<'a> for MessageEncoding {
+    type Error = &'static str;
+    type SerializeSettings = ();
+
+    fn serialize_with<B>(&self, buffer: &mut B, _settings: ()) -> usize
+    where
+        B: Buffer,
+    {
+        buffer.extend_from_slice(self.name().as_bytes());
+        self.name().len()
+    }
+
+    fn deserialize(data: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::for_label(data).ok_or(ERR_UNKNOWN)
+    }
+}
+
+/// The error type that can arise when converting `Encoded*` field values
+/// from and to [`MessageEncoding`]s.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EncodingError {
+    /// The `MessageEncoding <347>` value is not a known character encoding.
+    #[error("Unknown or unsupported `MessageEncoding <347>`.")]
+    UnknownEncoding,
+    /// The field value is not valid in the given character encoding.
+    #[error("Malformed byte sequence for the given character encoding.")]
+    Malformed,
+    /// The string contains characters which can't be represented in the
+    /// given character encoding.
+    #[error("Unmappable character for the given character encoding.")]
+    Unmappable,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn fix_labels_are_recognized() {
+        for (label, encoding) in [
+            (&b"ISO-8859-1"[..], MessageEncoding::ISO_8859_1),
+            (b"UTF-8", MessageEncoding::UTF_8),
+            (b"Shift_JIS", MessageEncoding::SHIFT_JIS),
+            (b"EUC-JP", MessageEncoding::EUC_JP),
+            (b"ISO-2022-JP", MessageEncoding::ISO_2022_JP),
+        ] {
+            assert_eq!(MessageEncoding::deserialize(label), Ok(encoding));
+            assert_eq!(encoding.to_bytes(), label);
+        }
+    }
+
+    #[test]
+    fn from_encoding_matches_constants() {
+        assert_eq!(
+            MessageEncoding::from_encoding(encoding_rs::SHIFT_JIS),
+            MessageEncoding::SHIFT_JIS
+        );
+        assert_eq!(
+            MessageEncoding::from_encoding(encoding_rs::GB18030).name(),
+            "gb18030"
+        );
+    }
+
+    #[test]
+    fn latin1_is_not_windows_1252() {
+        let encoding = MessageEncoding::ISO_8859_1;
+        assert_eq!(encoding.decode(b"\x80\xe9").unwrap(), "\u{80}é");
+        assert_eq!(&encoding.encode("\u{80}é").unwrap()[..], b"\x80\xe9");
+        assert_eq!(encoding.encode("€"), Err(EncodingError::Unmappable));
+    }
+
+    #[test]
+    fn ascii_is_borrowed() {
+        for encoding in [
+            MessageEncoding::ISO_8859_1,
+            MessageEncoding::UTF_8,
+            MessageEncoding::SHIFT_JIS,
+        ] {
+            assert!(matches!(encoding.decode(b"foobar"), Ok(Cow::Borrowed(_))));
+            assert!(matches!(encoding.encode("foobar"), Ok(Cow::Borrowed(_))));
+        }
+    }
+
+    #[test]
+    fn japanese_round_trip() {
+        let text = "日本語のテキスト";
+        for encoding in [
+            MessageEncoding::UTF_8,
+            MessageEncoding::SHIFT_JIS,
+            MessageEncoding::EUC_JP,
+            MessageEncoding::ISO_2022_JP,
+        ] {
+            let bytes = encoding.encode(text).unwrap();
+            assert_eq!(encoding.decode(&bytes).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn malformed_shift_jis() {
+        assert_eq!(
+            MessageEncoding::SHIFT_JIS.decode(b"\x93"),
+            Err(EncodingError::Malformed)
+        );
+        assert_eq!(
+            MessageEncoding::SHIFT_JIS.encode("한국어"),
+            Err(EncodingError::Unmappable)
+        );
+    }
+
+    #[quickcheck]
+    fn latin1_round_trip(data: Vec<u8>) -> bool {
+        let encoding = MessageEncoding::ISO_8859_1;
+        let s = encoding.decode(&data).unwrap();
+        encoding.encode(&s).unwrap()[..] == data[..]
+    }
+}
@@ -13,6 +13,7 @@
 //! | `char`                     | [`u8`] [^1].                                                                      |
 //! | `String`                   | [`Vec<u8>`], `&[u8]`.[^1]                                                          |
 //! | `data`                     | [`Vec<u8>`], `&[u8]` (also [`String`], [`str`] for UTF-8 content).                 |
+//! | `MessageEncoding <347>`    | [`MessageEncoding`].                                                               |
 //! | `MultipleCharValue`        | [`MultipleChars`] [^1].                                                            |
 //! | `MultipleValueString`      | [`MultipleStrings`] [^1].                                                          |
 //! | `Country`                  | [`Country`].                                                                       |
@@ -55,9 +56,12 @@
 //! [^1]: With the exception of datatype `data`, FIX mandates a single-byte
 //! encoding (Latin alphabet No. 1 by default), while Rust strings are UTF-8,
 //! which is a multibyte. These are *not* compatible. Watch out!
+//! [`MessageEncoding`] converts between the two, as well as to and from the
+//! encodings used by `Encoded*` fields (e.g. `EncodedText <355>`).
 
 mod checksum;
 mod date;
+mod message_encoding;
 mod monthyear;
 mod multiple_chars;
 mod multiple_strings;
@@ -78,6 +82,7 @@ mod utils_rust_decimal;
 
 pub use checksum::CheckSum;
 pub use date::Date;
+pub use message_encoding::{EncodingError, MessageEncoding};
 pub use monthyear::MonthYear;
 pub use multiple_chars::MultipleChars;
 pub use multiple_strings::MultipleStrings;
@@ -123,7 +123,7 @@ pub mod json;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "codegen")))]
 pub use fefix_core::codegen;
 
-pub use buffer::{Buffer, BufferWriter};
+pub use buffer::{Buffer, BufferWriter, Pool};
 pub use dict::Dictionary;
 pub use fefix_core::dict;
 pub use fefix_core::TagU16;
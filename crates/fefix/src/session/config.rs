@@ -1,4 +1,5 @@
 use super::{Environment, MsgSeqNumCounter, SeqNumbers};
+use crate::Dictionary;
 use std::marker::PhantomData;
 use std::num::NonZeroU64;
 use std::time::Duration;
@@ -43,6 +44,17 @@ pub trait Configure: Clone + Default {
     fn heartbeat(&self) -> Duration {
         Duration::from_secs(30)
     }
+
+    /// Returns the [`Dictionary`] of the session, e.g. for the order of
+    /// standard header fields. It must match [`Configure::begin_string`]:
+    /// FIXT.1.1 sessions need the FIXT.1.1 dictionary for header fields like
+    /// `ApplVerID <1128>`. FIX 4.4 by default.
+    ///
+    /// [`Dictionary`] is cheap to clone, so implementors should share a
+    /// single instance rather than create a new one on every call.
+    fn dictionary(&self) -> Dictionary {
+        Dictionary::fix44()
+    }
 }
 
 /// The canonical implementor of [`Configure`]. Every setting can be changed.
@@ -62,6 +74,7 @@ pub struct Config {
     pub msg_seq_num_outbound: MsgSeqNumCounter,
     pub sender_comp_id: String,
     pub target_comp_id: String,
+    pub dictionary: Dictionary,
 }
 
 impl Configure for Config {
@@ -92,6 +105,10 @@ impl Configure for Config {
     fn heartbeat(&self) -> Duration {
         self.heartbeat
     }
+
+    fn dictionary(&self) -> Dictionary {
+        self.dictionary.clone()
+    }
 }
 
 impl Default for Config {
@@ -108,6 +125,7 @@ impl Default for Config {
             msg_seq_num_outbound: MsgSeqNumCounter::default(),
             sender_comp_id: "SENDER_COMP".to_string(),
             target_comp_id: "TARGET_COMP".to_string(),
+            dictionary: Dictionary::fix44(),
         }
     }
 }
@@ -133,6 +151,10 @@ mod test {
             config.verify_test_indicator(),
             ConfigDefault.verify_test_indicator()
         );
+        assert_eq!(
+            config.dictionary().get_version(),
+            ConfigDefault.dictionary().get_version()
+        );
     }
 
     #[quickcheck]
@@ -3,8 +3,8 @@ use crate::random_field_access::RandomFieldAccess;
 use crate::session::{Environment, SeqNumbers};
 use crate::tagvalue::CowMessage;
 use crate::tagvalue::FvWrite;
-use crate::tagvalue::{DecoderBuffered, Encoder, EncoderHandle};
-use crate::FixValue;
+use crate::tagvalue::{DecoderBuffered, Encoder, EncoderHandle, HeaderTemplate};
+use crate::{FixValue, Pool, SetField};
 use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use std::borrow::Cow;
 use std::cell::RefCell;
@@ -30,10 +30,16 @@ const TEST_REQ_ID: u32 = 112;
 const REF_TAG_ID: u32 = 371;
 const REF_MSG_TYPE: u32 = 372;
 const SESSION_REJECT_REASON: u32 = 373;
+const MESSAGE_ENCODING: u32 = 347;
+const ENCODED_TEXT_LEN: u32 = 354;
+const ENCODED_TEXT: u32 = 355;
 const TEST_MESSAGE_INDICATOR: u32 = 464;
 
 const SENDING_TIME_ACCURACY_PROBLEM: u32 = 10;
 
+// Maximum number of idle buffers kept around for outbound messages.
+const OUTBOUND_BUFFER_POOL_LEN: usize = 8;
+
 // type CowMessage<'a, T> = Message<'a, Cow<'a, T>>;
 
 #[derive(Debug)]
@@ -113,7 +119,8 @@ pub struct FixConnection<B, C = Config> {
     config: C,
     backend: B,
     encoder: Rc<RefCell<Encoder>>,
-    buffer: Rc<RefCell<Vec<u8>>>,
+    header: HeaderTemplate,
+    buffers: Pool<Vec<u8>>,
     msg_seq_num_inbound: MsgSeqNumCounter,
     msg_seq_num_outbound: MsgSeqNumCounter,
 }
@@ -125,12 +132,19 @@ where
     C: Configure,
 {
     pub fn new(config: C, backend: B) -> FixConnection<B, C> {
+        let mut header = HeaderTemplate::new(config.begin_string(), &config.dictionary());
+        header.set_sender_comp_id(config.sender_comp_id());
+        header.set_target_comp_id(config.target_comp_id());
+        if let Some(encoding) = backend.message_encoding() {
+            header.set(MESSAGE_ENCODING, encoding);
+        }
         FixConnection {
             uuid: Uuid::new_v4(),
             config,
             backend,
             encoder: Rc::new(RefCell::new(Encoder::default())),
-            buffer: Rc::new(RefCell::new(vec![])),
+            header,
+            buffers: Pool::new(OUTBOUND_BUFFER_POOL_LEN),
             msg_seq_num_inbound: MsgSeqNumCounter::default(),
             msg_seq_num_outbound: MsgSeqNumCounter::default(),
         }
@@ -156,35 +170,15 @@ where
         I: AsyncRead + Unpin,
         O: AsyncWrite + Unpin,
     {
-        let logon = {
-            let encoder = self.encoder.clone();
-            let mut encoder_ref = encoder.borrow_mut();
-
-            let mut buf = self.buffer.borrow_mut();
-            let logon: Vec<u8> = {
-                let begin_string = self.config.begin_string();
-                let sender_comp_id = self.config.sender_comp_id();
-                let target_comp_id = self.config.target_comp_id();
-                let heartbeat = self.config.heartbeat().as_secs();
-                let msg_seq_num = self.msg_seq_num_outbound.next();
-                let buf: &mut Vec<u8> = buf.as_mut();
-                let mut msg = encoder_ref.start_message(begin_string, buf, b"A");
-                msg.set_fv_with_key(&SENDER_COMP_ID, sender_comp_id);
-                msg.set_fv_with_key(&TARGET_COMP_ID, target_comp_id);
-                msg.set_fv_with_key(&SENDING_TIME, chrono::Utc::now().timestamp_millis());
-                msg.set_fv_with_key(&MSG_SEQ_NUM, msg_seq_num);
-                msg.set_fv_with_key(&ENCRYPT_METHOD, 0);
-                msg.set_fv_with_key(&108, heartbeat);
-                msg.done()
-            }
-            .0
-            .into();
-
-            logon
-        };
+        let heartbeat = self.config.heartbeat().as_secs();
+        let logon = self.encode_message(b"A", |msg| {
+            msg.set_fv_with_key(&ENCRYPT_METHOD, 0);
+            msg.set_fv_with_key(&108, heartbeat);
+        });
 
         output.write_all(&logon).await.unwrap();
         self.backend.on_outbound_message(&logon).ok();
+        self.buffers.put(logon);
 
         let logon;
         loop {
@@ -224,6 +218,7 @@ where
                         Response::OutboundBytes(bytes) => {
                             output.write_all(&*bytes).await.unwrap();
                             self.on_outbound_message(&*bytes).ok();
+                            self.recycle(bytes);
                         }
                         Response::ResetHeartbeat => {
                             event_loop.ping_heartbeat();
@@ -236,20 +231,56 @@ where
                     return;
                 }
                 LlEvent::Heartbeat => {
-                    // Clone it to workaround mutable issue.
-                    let heartbeat = self
-                        .on_heartbeat_is_due()
-                        .iter()
-                        .copied()
-                        .collect::<Vec<u8>>();
+                    let heartbeat = self.on_heartbeat_is_due();
                     output.write_all(&heartbeat).await.unwrap();
                     self.on_outbound_message(&heartbeat).ok();
+                    self.recycle(heartbeat);
                 }
                 LlEvent::Logout => {}
                 LlEvent::TestRequest => {}
             }
         }
     }
+
+    /// Encodes a new session message of type `msg_type` into a buffer taken
+    /// from the pool of `self`. The standard header is written first, then
+    /// `fill` can add the body fields.
+    fn encode_message<F>(&self, msg_type: &[u8], fill: F) -> Vec<u8>
+    where
+        F: FnOnce(&mut EncoderHandle<Vec<u8>>),
+    {
+        let mut buffer = self.buffers.take();
+        buffer.clear();
+        let msg_seq_num = self.msg_seq_num_outbound.next();
+        let mut encoder = self.encoder.borrow_mut();
+        let mut msg =
+            encoder.start_message_with_header(&self.header, &mut buffer, msg_type, msg_seq_num);
+        fill(&mut msg);
+        msg.done();
+        buffer
+    }
+
+    /// Sets `Text <58>` to `text`. Non-ASCII text is also written to
+    /// `EncodedText <355>` if the backend has a
+    /// [`Backend::message_encoding`], as advertised by `MessageEncoding <347>`.
+    fn set_text(&self, msg: &mut EncoderHandle<Vec<u8>>, text: &str) {
+        msg.set_fv_with_key(&TEXT, text);
+        if let Some(encoding) = self.backend.message_encoding() {
+            if !text.is_ascii() {
+                // Unmappable characters are only available in `Text <58>`.
+                msg.set_encoded(ENCODED_TEXT_LEN, ENCODED_TEXT, text, encoding)
+                    .ok();
+            }
+        }
+    }
+
+    /// Gives the buffer of an outbound message back to the pool of `self`,
+    /// once it's been written.
+    fn recycle(&self, message: Cow<[u8]>) {
+        if let Cow::Owned(buffer) = message {
+            self.buffers.put(buffer);
+        }
+    }
 }
 
 pub trait Verify {
@@ -403,42 +434,12 @@ where
 
     fn on_logout(&self, logout_msg: Option<&[u8]>) -> Cow<[u8]> {
         let logout_msg = logout_msg.unwrap_or(b"Logout");
-
-        let mut encoder = self.encoder.borrow_mut();
-        let mut buf = self.buffer.take();
-        let fix_message = {
-            let msg_seq_num = self.msg_seq_num_outbound.next();
-            let begin_string = self.config.begin_string();
-            let mut msg = encoder.start_message(begin_string, &mut buf, b"5");
-            self.set_sender_and_target(&mut msg);
-            msg.set_fv_with_key(&MSG_SEQ_NUM, msg_seq_num);
-            msg.set_fv_with_key(&TEXT, logout_msg);
-            msg.done()
-        };
-
-        let fix_message: Vec<u8> = fix_message.0.to_vec();
-        self.buffer.replace(buf);
-
-        fix_message.into()
+        self.encode_message(b"5", |msg| msg.set_fv_with_key(&TEXT, logout_msg))
+            .into()
     }
 
     fn on_heartbeat_is_due(&self) -> Cow<[u8]> {
-        let mut encoder = self.encoder.borrow_mut();
-        let mut buf = self.buffer.take();
-        let fix_message = {
-            let begin_string = self.config.begin_string();
-            let msg_seq_num = self.msg_seq_num_outbound.next();
-            let mut msg = encoder.start_message(begin_string, &mut buf, b"0");
-            self.set_sender_and_target(&mut msg);
-            msg.set_fv_with_key(&MSG_SEQ_NUM, msg_seq_num);
-            self.set_sending_time(&mut msg);
-            msg.done()
-        };
-
-        let fix_message: Vec<u8> = fix_message.0.to_vec();
-        self.buffer.replace(buf);
-
-        fix_message.into()
+        self.encode_message(b"0", |_| {}).into()
     }
 
     fn set_sender_and_target<'a>(&self, msg: &mut impl FvWrite<'a, Key = u32>) {
@@ -461,20 +462,8 @@ where
 
     fn on_test_request(&self, msg: Rc<CowMessage<[u8]>>) -> Cow<[u8]> {
         let test_req_id = msg.fv::<&[u8]>(TEST_REQ_ID).unwrap();
-        let begin_string = self.config.begin_string();
-        let msg_seq_num = self.msg_seq_num_outbound.next();
-        let mut buf = self.buffer.take();
-        let mut encoder = self.encoder.borrow_mut();
-        let mut msg = encoder.start_message(begin_string, &mut buf, b"1");
-        self.set_sender_and_target(&mut msg);
-        msg.set_fv_with_key(&MSG_SEQ_NUM, msg_seq_num);
-        self.set_sending_time(&mut msg);
-        msg.set_fv_with_key(&TEST_REQ_ID, test_req_id);
-        // msg.done().0
-
-        let complated_message: Vec<u8> = msg.done().0.into();
-        self.buffer.replace(buf);
-        complated_message.into()
+        self.encode_message(b"1", |msg| msg.set_fv_with_key(&TEST_REQ_ID, test_req_id))
+            .into()
     }
 
     fn on_wrong_environment(&self, _message: Rc<CowMessage<[u8]>>) -> Response {
@@ -482,21 +471,9 @@ where
     }
 
     fn generate_error_seqnum_too_low(&mut self) -> Cow<[u8]> {
-        let begin_string = self.config.begin_string();
-        let msg_seq_num = self.msg_seq_num_outbound.next();
         let text = errs::msg_seq_num(self.msg_seq_num_inbound.next());
-        let mut buf = self.buffer.take();
-        let mut encoder = self.encoder.borrow_mut();
-        let mut msg = encoder.start_message(begin_string, &mut buf, b"FIXME");
-        msg.set_fv_with_key(&MSG_TYPE, "5");
-        self.set_sender_and_target(&mut msg);
-        msg.set_fv_with_key(&MSG_SEQ_NUM, msg_seq_num);
-        msg.set_fv_with_key(&TEXT, text.as_str());
-
-        let completed_message: Vec<u8> = msg.done().0.into();
-        self.buffer.replace(buf);
-
-        completed_message.into()
+        self.encode_message(b"5", |msg| self.set_text(msg, &text))
+            .into()
     }
 
     fn on_missing_seqnum(&self, _message: Rc<CowMessage<[u8]>>) -> Response {
@@ -515,27 +492,17 @@ where
         reason: u32,
         err_text: String,
     ) -> Response {
-        let begin_string = self.config.begin_string();
-        // let sender_comp_id = self.sender_comp_id();
-        // let target_comp_id = self.target_comp_id();
-        let msg_seq_num = self.msg_seq_num_outbound.next();
-        let mut buf = self.buffer.take();
-        let mut encoder = self.encoder.borrow_mut();
-        let mut msg = encoder.start_message(begin_string, &mut buf, b"3");
-        self.set_sender_and_target(&mut msg);
-        msg.set_fv_with_key(&MSG_SEQ_NUM, msg_seq_num);
-        if let Some(ref_tag) = ref_tag {
-            msg.set_fv_with_key(&REF_TAG_ID, ref_tag);
-        }
-        if let Some(ref_msg_type) = ref_msg_type {
-            msg.set_fv_with_key(&REF_MSG_TYPE, ref_msg_type);
-        }
-        msg.set_fv_with_key(&SESSION_REJECT_REASON, reason);
-        msg.set_fv_with_key(&TEXT, err_text.as_str());
-
-        let completed_message: Vec<u8> = msg.done().0.into();
-        self.buffer.replace(buf);
-        Response::OutboundBytes(completed_message.into())
+        let reject = self.encode_message(b"3", |msg| {
+            if let Some(ref_tag) = ref_tag {
+                msg.set_fv_with_key(&REF_TAG_ID, ref_tag);
+            }
+            if let Some(ref_msg_type) = ref_msg_type {
+                msg.set_fv_with_key(&REF_MSG_TYPE, ref_msg_type);
+            }
+            msg.set_fv_with_key(&SESSION_REJECT_REASON, reason);
+            self.set_text(msg, &err_text);
+        });
+        Response::OutboundBytes(reject.into())
     }
 
     fn make_reject_for_inaccurate_sending_time(&self, offender: Rc<CowMessage<[u8]>>) -> Response {
@@ -551,40 +518,16 @@ where
     }
 
     fn make_logout(&self, text: String) -> Response {
-        let mut encoder = self.encoder.borrow_mut();
-        let mut buf = self.buffer.take();
-        let fix_message = {
-            let begin_string = self.config.begin_string();
-            // let sender_comp_id = self.sender_comp_id();
-            // let target_comp_id = self.target_comp_id();
-            let msg_seq_num = self.msg_seq_num_outbound.next();
-            let mut msg = encoder.start_message(begin_string, &mut buf, b"5");
-            self.set_sender_and_target(&mut msg);
-            msg.set_fv_with_key(&MSG_SEQ_NUM, msg_seq_num);
-            msg.set_fv_with_key(&TEXT, text.as_str());
-            self.set_sending_time(&mut msg);
-            msg.done()
-        };
-
-        let completed_message: Vec<u8> = fix_message.0.into();
-        self.buffer.replace(buf);
-        Response::OutboundBytes(completed_message.into())
+        let logout = self.encode_message(b"5", |msg| self.set_text(msg, &text));
+        Response::OutboundBytes(logout.into())
     }
 
     fn make_resend_request(&self, start: u64, end: u64) -> Response {
-        let begin_string = self.config.begin_string();
-        let mut encoder = self.encoder.borrow_mut();
-        let mut buf = self.buffer.take();
-        let mut msg = encoder.start_message(begin_string, &mut buf, b"2");
-        //Self::add_comp_id(msg);
-        //self.add_sending_time(msg);
-        //self.add_seqnum(msg);
-        msg.set_fv_with_key(&BEGIN_SEQ_NO, start);
-        msg.set_fv_with_key(&END_SEQ_NO, end);
-        let completed_message: Vec<u8> = msg.done().0.into();
-        self.buffer.replace(buf);
-
-        Response::OutboundBytes(completed_message.into())
+        let resend_request = self.encode_message(b"2", |msg| {
+            msg.set_fv_with_key(&BEGIN_SEQ_NO, start);
+            msg.set_fv_with_key(&END_SEQ_NO, end);
+        });
+        Response::OutboundBytes(resend_request.into())
     }
 
     fn on_high_seqnum(&self, msg: Rc<CowMessage<[u8]>>) -> Response {
@@ -596,9 +539,10 @@ where
     fn on_logon(&self, _logon: Rc<CowMessage<[u8]>>) {
         let begin_string = self.config.begin_string();
         let mut encoder = self.encoder.borrow_mut();
-        let mut buf = self.buffer.take();
+        let mut buf = self.buffers.take();
+        buf.clear();
         encoder.start_message(begin_string, &mut buf, b"A");
-        self.buffer.replace(buf);
+        self.buffers.put(buf);
         //Self::add_comp_id(msg);
         //self.add_sending_time(msg);
         //self.add_sending_time(msg);
@@ -811,3 +755,125 @@ where
 //        assert!(responses.next().is_none());
 //    }
 //}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fix_value::MessageEncoding;
+    use crate::tagvalue::Decoder;
+    use crate::utils::counting_allocator::allocations;
+    use crate::Dictionary;
+
+    #[derive(Debug, Clone)]
+    struct NoopBackend(Option<MessageEncoding>);
+
+    impl Backend for NoopBackend {
+        type Error = u32;
+
+        fn message_encoding(&self) -> Option<MessageEncoding> {
+            self.0
+        }
+
+        fn sender_comp_id(&self) -> &[u8] {
+            b"SENDER"
+        }
+
+        fn target_comp_id(&self) -> &[u8] {
+            b"TARGET"
+        }
+
+        fn on_inbound_app_message(&mut self, _message: Rc<CowMessage<[u8]>>) -> Result<(), u32> {
+            Ok(())
+        }
+
+        fn on_outbound_message(&mut self, _message: &[u8]) -> Result<(), u32> {
+            Ok(())
+        }
+
+        fn on_resend_request(&mut self, _range: std::ops::Range<u64>) -> Result<(), u32> {
+            Ok(())
+        }
+
+        fn on_successful_handshake(&mut self) -> Result<(), u32> {
+            Ok(())
+        }
+
+        fn fetch_messages(&mut self) -> Result<&[&[u8]], u32> {
+            Ok(&[])
+        }
+
+        fn pending_message(&mut self) -> Option<&[u8]> {
+            None
+        }
+    }
+
+    fn connection() -> FixConnection<NoopBackend> {
+        FixConnection::new(Config::default(), NoopBackend(None))
+    }
+
+    #[test]
+    fn outbound_messages_dont_share_buffer_contents() {
+        let conn = connection();
+        let first = conn.on_heartbeat_is_due();
+        conn.recycle(first);
+        let second = conn.on_heartbeat_is_due();
+
+        let mut decoder = Decoder::<crate::tagvalue::Config>::new(Dictionary::fix44());
+        let message = decoder.decode(&second[..]).unwrap();
+        assert_eq!(message.fv::<&str>(MSG_TYPE), Ok("0"));
+        assert_eq!(message.fv::<u64>(MSG_SEQ_NUM), Ok(2));
+        assert_eq!(message.as_bytes().len(), second.len());
+    }
+
+    #[test]
+    fn non_ascii_text_is_encoded_with_message_encoding() {
+        let encoding = MessageEncoding::SHIFT_JIS;
+        let conn = FixConnection::new(Config::default(), NoopBackend(Some(encoding)));
+        let logout = match conn.make_logout("東京".to_string()) {
+            Response::OutboundBytes(logout) => logout,
+            _ => panic!("Expected a Logout."),
+        };
+
+        let mut decoder = Decoder::<crate::tagvalue::Config>::new(Dictionary::fix44());
+        let message = decoder.decode(&logout[..]).unwrap();
+        assert_eq!(
+            message.fv::<MessageEncoding>(MESSAGE_ENCODING),
+            Ok(encoding)
+        );
+        assert_eq!(message.fv::<usize>(ENCODED_TEXT_LEN), Ok(4));
+        let encoded_text = message.fv_raw(ENCODED_TEXT).unwrap();
+        assert_eq!(encoding.decode(encoded_text).unwrap(), "東京");
+    }
+
+    #[test]
+    fn ascii_text_is_not_encoded() {
+        let conn = FixConnection::new(
+            Config::default(),
+            NoopBackend(Some(MessageEncoding::SHIFT_JIS)),
+        );
+        let logout = match conn.make_logout("Bye".to_string()) {
+            Response::OutboundBytes(logout) => logout,
+            _ => panic!("Expected a Logout."),
+        };
+
+        let mut decoder = Decoder::<crate::tagvalue::Config>::new(Dictionary::fix44());
+        let message = decoder.decode(&logout[..]).unwrap();
+        assert_eq!(message.fv::<&str>(TEXT), Ok("Bye"));
+        assert_eq!(message.fv_raw(ENCODED_TEXT), None);
+    }
+
+    #[test]
+    fn heartbeats_dont_allocate_in_steady_state() {
+        let conn = connection();
+        let heartbeat = conn.on_heartbeat_is_due();
+        conn.recycle(heartbeat);
+        let allocations = allocations(|| {
+            for _ in 0..100 {
+                let heartbeat = conn.on_heartbeat_is_due();
+                assert!(heartbeat.starts_with(b"8=FIX.4.4"));
+                conn.recycle(heartbeat);
+            }
+        });
+        assert_eq!(allocations, 0);
+    }
+}
@@ -58,20 +58,39 @@ where
 
     pub async fn next_event(&mut self) -> Option<LlEvent<'_>> {
         let mut buf_filled_len = 0;
-        let mut buf = &mut self.decoder.supply_buffer()[buf_filled_len..];
+        let mut buf = self.decoder.supply_buffer();
 
         loop {
             if !self.is_alive {
                 return None;
             }
 
+            if buf_filled_len == buf.len() {
+                match self.decoder.parse() {
+                    Ok(Some(())) => {
+                        let msg = self.decoder.message();
+                        return Some(LlEvent::Message(msg));
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        // Garbled data gets discarded and we move on with
+                        // the next message.
+                        self.decoder.resync();
+                        return Some(LlEvent::BadMessage(err));
+                    }
+                }
+                buf_filled_len = 0;
+                buf = self.decoder.supply_buffer();
+                continue;
+            }
+
             let now = Instant::now();
             let mut timer_heartbeat = Delay::new(now - self.last_heartbeat + self.heartbeat).fuse();
             let mut timer_test_request =
                 Delay::new(now - self.last_reset + self.heartbeat_soft_tolerance).fuse();
             let mut timer_logout =
                 Delay::new(now - self.last_reset + self.heartbeat_hard_tolerance).fuse();
-            let mut read_result = self.input.read(buf).fuse();
+            let mut read_result = self.input.read(&mut buf[buf_filled_len..]).fuse();
 
             select! {
                 read_result = read_result => {
@@ -81,27 +100,6 @@ where
                         }
                         Ok(num_bytes) => {
                             buf_filled_len += num_bytes;
-                            if buf_filled_len < buf.len() {
-                                continue;
-                            }
-
-                            let result = self.decoder.parse();
-                            buf_filled_len = 0;
-                            buf = &mut self.decoder.supply_buffer()[buf_filled_len..];
-
-                            match result {
-                                Ok(Some(())) => {
-                                    let msg = self.decoder.message();
-                                    return Some(LlEvent::Message(msg));
-                                }
-                                Ok(None) => {
-                                    continue;
-                                }
-                                Err(err) => {
-                                    self.is_alive = false;
-                                    return Some(LlEvent::BadMessage(err))
-                                }
-                            }
                         }
                     };
                 },
@@ -120,6 +118,12 @@ where
         }
     }
 
+    /// Returns the bytes that were discarded after the last
+    /// [`LlEvent::BadMessage`], e.g. for logging purposes.
+    pub fn discarded(&self) -> &[u8] {
+        self.decoder.discarded()
+    }
+
     /// Resets the FIX counterparty's `Heartbeat <0>` -associated timers.
     pub fn ping_heartbeat(&mut self) {
         self.last_reset = Instant::now();
@@ -131,7 +135,9 @@ where
 pub enum LlEvent<'a> {
     /// Incoming FIX message.
     Message(Message<'a, Cow<'a, [u8]>>),
-    /// Tried to parse an incoming FIX message, but got illegal data.
+    /// Tried to parse an incoming FIX message, but got illegal data. The bytes
+    /// that were discarded to resume decoding from the next message are
+    /// available through [`LlEventLoop::discarded`].
     BadMessage(DecodeError),
     /// I/O error at the transport layer.
     IoError(io::Error),
@@ -151,6 +157,7 @@ pub enum LlEvent<'a> {
 mod test {
     use super::*;
     use crate::tagvalue::{Config, Decoder};
+    use crate::RandomFieldAccess;
     use tokio::io::AsyncWriteExt;
     use tokio::net::{TcpListener, TcpStream};
     use tokio_util::compat::*;
@@ -182,6 +189,28 @@ mod test {
         )
     }
 
+    #[tokio::test]
+    async fn garbled_input_is_skipped() {
+        let mut event_loop = new_event_loop(vec![(
+            b"noise|noise|noise|noise|8=FIX.4.4\x019=42\x0135=0\x0149=A\x0156=B\x0134=12\x0152=20100304-07:59:30\x0110=187\x01",
+            Duration::from_secs(10),
+        )])
+        .await;
+        let mut discarded = Vec::new();
+        loop {
+            match event_loop.next_event().await {
+                Some(LlEvent::BadMessage(_err)) => {}
+                Some(LlEvent::Message(msg)) => {
+                    assert_eq!(msg.fv::<u32>(34), Ok(12));
+                    break;
+                }
+                event => panic!("Unexpected event: {:?}", event),
+            }
+            discarded.extend_from_slice(event_loop.discarded());
+        }
+        assert_eq!(discarded, b"noise|noise|noise|noise|");
+    }
+
     #[tokio::test]
     async fn dead_input_triggers_logout() {
         let mut event_loop = new_event_loop(vec![(b"8", Duration::from_secs(10))]).await;
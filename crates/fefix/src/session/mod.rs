@@ -23,7 +23,7 @@ pub use heartbeat_rule::HeartbeatRule;
 pub use resend_request_range::ResendRequestRange;
 pub use seq_numbers::{SeqNumberError, SeqNumbers};
 
-use crate::{tagvalue::CowMessage, FixValue, SetField};
+use crate::{fix_value::MessageEncoding, tagvalue::CowMessage, FixValue, SetField};
 use std::{ops::Range, rc::Rc};
 
 /// The owner of a [`FixConnection`]. It can react to events, store incoming
@@ -35,7 +35,10 @@ pub trait Backend: Clone {
     fn sender_comp_id(&self) -> &[u8];
     fn target_comp_id(&self) -> &[u8];
 
-    fn message_encoding(&self) -> Option<&[u8]> {
+    /// The `MessageEncoding <347>` of the session, if any. [`FixConnection`]
+    /// writes it into the standard header of outbound messages and uses it for
+    /// `Encoded*` fields.
+    fn message_encoding(&self) -> Option<MessageEncoding> {
         None
     }
 
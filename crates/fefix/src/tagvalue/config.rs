@@ -65,6 +65,18 @@ pub trait Configure: Clone + Default {
     fn should_decode_associative(&self) -> bool {
         true
     }
+
+    /// Determines whether or not `BodyLength <9>` is zero-padded to eight
+    /// digits (e.g. `9=00000123`) when encoding. Zero-padding lets the encoder
+    /// write the body right after `BodyLength <9>` and fill in its value at the
+    /// end, which is the fastest option. Some counterparties reject it though,
+    /// in which case the body must be shifted once the message is complete.
+    ///
+    /// This setting has no effect when decoding FIX messages.
+    #[inline]
+    fn zero_padded_body_length(&self) -> bool {
+        true
+    }
 }
 
 /// A `struct` that has settable fields and implements [`Configure`].
@@ -79,6 +91,7 @@ pub struct Config {
     max_message_size: Option<usize>,
     verify_checksum: bool,
     should_decode_associative: bool,
+    zero_padded_body_length: bool,
 }
 
 impl Config {
@@ -130,6 +143,28 @@ impl Config {
     pub fn set_decode_assoc(&mut self, should: bool) {
         self.should_decode_associative = should;
     }
+
+    /// Changes the value of [`Configure::zero_padded_body_length`]. On by
+    /// default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::tagvalue::{Config, Configure, Encoder};
+    /// use fefix::prelude::*;
+    ///
+    /// let mut config = Config::default();
+    /// config.set_separator(b'|');
+    /// config.set_zero_padded_body_length(false);
+    /// let mut encoder = Encoder::new(config);
+    /// let mut buffer = Vec::new();
+    /// let msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"0");
+    /// let (data, _) = msg.done();
+    /// assert_eq!(data, b"8=FIX.4.4|9=5|35=0|10=020|");
+    /// ```
+    pub fn set_zero_padded_body_length(&mut self, zero_padded: bool) {
+        self.zero_padded_body_length = zero_padded;
+    }
 }
 
 impl Configure for Config {
@@ -152,6 +187,11 @@ impl Configure for Config {
     fn should_decode_associative(&self) -> bool {
         self.should_decode_associative
     }
+
+    #[inline]
+    fn zero_padded_body_length(&self) -> bool {
+        self.zero_padded_body_length
+    }
 }
 
 impl Default for Config {
@@ -161,6 +201,7 @@ impl Default for Config {
             separator: SOH,
             verify_checksum: true,
             should_decode_associative: true,
+            zero_padded_body_length: true,
         }
     }
 }
@@ -198,4 +239,12 @@ mod test {
         config.set_verify_checksum(true);
         assert!(config.verify_checksum());
     }
+
+    #[test]
+    fn config_zero_pads_body_length_by_default() {
+        let mut config = Config::default();
+        assert!(config.zero_padded_body_length());
+        config.set_zero_padded_body_length(false);
+        assert!(!config.zero_padded_body_length());
+    }
 }
@@ -1,14 +1,13 @@
 use super::{
-    Config, Configure, DecodeError, FieldLocator, FieldLocatorContext, RawDecoder,
-    RawDecoderBuffered, RawFrame,
+    Config, Configure, DecodeError, DecoderAsyncReader, DecoderReader, FieldLocator,
+    FieldLocatorContext, RawDecoder, RawDecoderBuffered, RawFrame, Validator,
 };
 use crate::dict::IsFieldDefinition;
-use crate::{
-    dict::FixDatatype, Dictionary, FixValue, GetConfig, RandomFieldAccess, RepeatingGroup, TagU16,
-};
-use nohash_hasher::IntMap;
+use crate::fix_value::{EncodingError, MessageEncoding};
+#[cfg(feature = "utils-bytes")]
+use crate::Pool;
+use crate::{Dictionary, FixValue, GetConfig, RandomFieldAccess, RepeatingGroup, TagU16};
 use std::borrow::Cow;
-use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use std::fmt::Debug;
 use std::iter::FusedIterator;
@@ -20,14 +19,32 @@ use std::marker::PhantomData;
 //   8=FIX.4.2|...
 const BEGIN_STRING_OFFSET: usize = 2;
 
+// Maximum number of idle message builders kept around for `OwnedMessage`s.
+#[cfg(feature = "utils-bytes")]
+const MESSAGE_BUILDER_POOL_LEN: usize = 64;
+
+const APPL_VER_ID: u16 = 1128;
+
 /// FIX message decoder.
 ///
 /// One should create a [`Decoder`] per stream of FIX messages.
+///
+/// A single [`Decoder`] can decode messages of multiple FIX versions: see
+/// [`Decoder::add_dictionary`].
 #[derive(Debug)]
 pub struct Decoder<C = Config> {
     builder: MessageBuilder<'static>,
     raw_decoder: RawDecoder<C>,
-    tag_lookup: IntMap<u16, FixDatatype>,
+    // The first item belongs to the default dictionary.
+    dictionaries: Vec<Dictionary>,
+    by_begin_string: Vec<(Vec<u8>, usize)>,
+    by_appl_ver_id: Vec<(Vec<u8>, usize)>,
+    // The dictionary in use for the message being decoded.
+    active_dictionary: usize,
+    validator: Option<Validator>,
+    // The builders of dropped `OwnedMessage`s, ready for reuse.
+    #[cfg(feature = "utils-bytes")]
+    builders: Pool<MessageBuilder<'static>>,
 }
 
 impl<C> Decoder<C>
@@ -40,18 +57,127 @@ where
         Self {
             builder: MessageBuilder::default(),
             raw_decoder: RawDecoder::default(),
-            tag_lookup: dict
-                .iter_fields()
-                .filter_map(|field| {
-                    let fix_type = field.data_type().basetype();
-                    if fix_type == FixDatatype::Length || fix_type == FixDatatype::NumInGroup {
-                        Some((field.tag().get(), fix_type))
-                    } else {
-                        None
-                    }
-                })
-                .collect(),
+            dictionaries: vec![dict],
+            by_begin_string: Vec::new(),
+            by_appl_ver_id: Vec::new(),
+            active_dictionary: 0,
+            validator: None,
+            #[cfg(feature = "utils-bytes")]
+            builders: Pool::new(MESSAGE_BUILDER_POOL_LEN),
+        }
+    }
+
+    /// Adds `dict` to the dictionaries of `self`, so that messages of
+    /// different FIX versions can be decoded by the same [`Decoder`]. The
+    /// dictionary is selected by the version of `dict`
+    /// ([`Dictionary::get_version`]):
+    ///
+    /// - FIX 4.x and FIXT dictionaries are used for messages with the same
+    ///   `BeginString <8>`.
+    /// - FIX 4.x and FIX 5.0+ dictionaries are used for messages with the
+    ///   corresponding `ApplVerID <1128>` (e.g. `9` for FIX 5.0 SP2), from
+    ///   that field onwards.
+    ///
+    /// Messages that match no dictionary are decoded with the dictionary
+    /// given to [`Decoder::new`]. Dictionaries for custom versions can be
+    /// added with [`Decoder::add_dictionary_for_begin_string`] and
+    /// [`Decoder::add_dictionary_for_appl_ver_id`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::tagvalue::{Config, Decoder};
+    /// use fefix::prelude::*;
+    ///
+    /// let mut decoder = Decoder::<Config>::new(Dictionary::empty());
+    /// decoder.add_dictionary(Dictionary::fix44());
+    /// decoder.config_mut().set_separator(b'|');
+    ///
+    /// let data = b"8=FIX.4.4|9=31|35=V|262=1|146=1|55=AAPL|263=0|10=218|";
+    /// let message = decoder.decode(&data[..]).unwrap();
+    /// assert_eq!(message.group(146).unwrap().len(), 1);
+    /// ```
+    pub fn add_dictionary(&mut self, dict: Dictionary) {
+        let version = dict.get_version().to_string();
+        let i = self.push_dictionary(dict);
+        if !version.starts_with("FIX.5") {
+            insert_or_replace(&mut self.by_begin_string, version.as_bytes(), i);
         }
+        if let Some(appl_ver_id) = appl_ver_id(&version) {
+            insert_or_replace(&mut self.by_appl_ver_id, appl_ver_id, i);
+        }
+    }
+
+    /// Adds `dict` to the dictionaries of `self`, for messages with the given
+    /// `BeginString <8>`. See [`Decoder::add_dictionary`].
+    pub fn add_dictionary_for_begin_string(&mut self, begin_string: &[u8], dict: Dictionary) {
+        let i = self.push_dictionary(dict);
+        insert_or_replace(&mut self.by_begin_string, begin_string, i);
+    }
+
+    /// Adds `dict` to the dictionaries of `self`, for messages with the given
+    /// `ApplVerID <1128>`. See [`Decoder::add_dictionary`].
+    pub fn add_dictionary_for_appl_ver_id(&mut self, appl_ver_id: &[u8], dict: Dictionary) {
+        let i = self.push_dictionary(dict);
+        insert_or_replace(&mut self.by_appl_ver_id, appl_ver_id, i);
+    }
+
+    /// Sets the [`Validator`] which decoded messages must pass, if any. Messages
+    /// which don't pass it are rejected with [`DecodeError::Validation`].
+    /// There's no validation by default.
+    ///
+    /// Validation needs associative access to fields, so it's skipped if
+    /// [`Configure::should_decode_associative`] is turned off.
+    ///
+    /// The validator always checks messages against its own [`Dictionary`],
+    /// regardless of the dictionary that `BeginString <8>` and
+    /// `ApplVerID <1128>` select for decoding (see
+    /// [`Decoder::add_dictionary`]). Decoders with several dictionaries
+    /// should thus only validate messages of a single FIX version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::tagvalue::{Config, Decoder, DecodeError, ValidationError, Validator};
+    /// use fefix::prelude::*;
+    ///
+    /// let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+    /// decoder.config_mut().set_separator(b'|');
+    /// decoder.set_validator(Some(Validator::new(Dictionary::fix44())));
+    ///
+    /// let data = b"8=FIX.4.4|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=185|";
+    /// assert!(decoder.decode(data).is_ok());
+    /// let data = b"8=FIX.4.4|9=36|35=0|49=A|56=B|52=20100304-07:59:30|10=152|";
+    /// assert!(matches!(
+    ///     decoder.decode(data),
+    ///     Err(DecodeError::Validation(ValidationError::RequiredFieldMissing { .. }))
+    /// ));
+    /// ```
+    pub fn set_validator(&mut self, validator: Option<Validator>) {
+        self.validator = validator;
+    }
+
+    fn push_dictionary(&mut self, dict: Dictionary) -> usize {
+        self.dictionaries.push(dict);
+        self.dictionaries.len() - 1
+    }
+
+    /// Turns `self` into a [`DecoderReader`] that decodes messages from
+    /// `reader`.
+    pub fn reader<R>(self, reader: R) -> DecoderReader<R, C>
+    where
+        R: std::io::Read,
+    {
+        DecoderReader::new(self, reader)
+    }
+
+    /// Turns `self` into a [`DecoderAsyncReader`] that decodes messages from
+    /// the asynchronous `reader`.
+    pub fn async_reader<R>(self, reader: R) -> DecoderAsyncReader<R, C>
+    where
+        R: futures::AsyncRead + Unpin,
+    {
+        DecoderAsyncReader::new(self, reader)
     }
 
     pub fn buffered(self) -> DecoderBuffered<C> {
@@ -61,6 +187,7 @@ where
             decoder: self,
             raw_decoder,
             is_ready: false,
+            has_failed: false,
         }
     }
 
@@ -91,6 +218,49 @@ where
         self.construct_message_from_frame(frame)
     }
 
+    /// Decodes `bytes` and returns an [`OwnedMessage`], which, unlike
+    /// [`Message`], doesn't borrow from `self`.
+    ///
+    /// The memory used by [`OwnedMessage`]s is given back to `self` when they
+    /// are dropped, so that decoding performs no heap allocations once
+    /// enough messages have gone through.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::tagvalue::{Config, Decoder};
+    /// use fefix::prelude::*;
+    /// use bytes::Bytes;
+    ///
+    /// let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+    /// decoder.config_mut().set_separator(b'|');
+    /// let data = Bytes::from_static(b"8=FIX.4.4|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=185|");
+    /// let message = decoder.decode_owned(data).unwrap();
+    ///
+    /// assert_eq!(message.message().fv(34), Ok(12));
+    /// ```
+    #[cfg(feature = "utils-bytes")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "utils-bytes")))]
+    pub fn decode_owned(&mut self, bytes: bytes::Bytes) -> Result<OwnedMessage, DecodeError> {
+        self.decode(&bytes[..])?;
+        Ok(OwnedMessage {
+            // The builder now holds references to `bytes`, which we keep
+            // alive alongside it.
+            builder: std::mem::replace(&mut self.builder, self.builders.take()),
+            bytes,
+            builders: self.builders.clone(),
+        })
+    }
+
+    /// Returns a view over the last message decoded by `self`.
+    pub(super) fn message<T>(&self) -> Message<'_, T> {
+        Message {
+            builder: &self.builder,
+            phantom: PhantomData::default(),
+            field_locator_context: FieldLocatorContext::TopLevel,
+        }
+    }
+
     fn message_builder_mut(&mut self) -> &mut MessageBuilder<'_> {
         unsafe { std::mem::transmute(&mut self.builder) }
     }
@@ -104,6 +274,7 @@ where
     {
         self.builder.clear();
         self.message_builder_mut().bytes = frame.as_bytes();
+        self.active_dictionary = lookup(&self.by_begin_string, frame.begin_string()).unwrap_or(0);
         let separator = self.config().separator();
         let payload = frame.payload();
         self.store_field(
@@ -114,29 +285,11 @@ where
         );
         let mut i = 0;
         while i < payload.len() {
-            let index_of_next_equal_sign = {
-                let i_eq = (&payload[i..])
-                    .iter()
-                    .copied()
-                    .position(|byte| byte == b'=')
-                    .map(|pos| pos + i);
-                if i_eq.is_none() {
-                    break;
-                }
-                i_eq.unwrap()
-            };
-            let field_value_len = if let Some(len) = self.builder.state.data_field_length {
-                self.builder.state.data_field_length = None;
-                len
-            } else {
-                let len = (&payload[index_of_next_equal_sign + 1..])
-                    .iter()
-                    .copied()
-                    .position(|byte| byte == separator);
-                if len.is_none() {
-                    break;
-                }
-                len.unwrap()
+            // `memchr` searches many bytes at a time (with SIMD instructions,
+            // where available), which is much faster than a byte-by-byte scan.
+            let index_of_next_equal_sign = match memchr::memchr(b'=', &payload[i..]) {
+                Some(pos) => pos + i,
+                None => break,
             };
             let tag_num = {
                 let mut tag = 0u32;
@@ -149,6 +302,20 @@ where
                     break;
                 }
             };
+            let field_value_len = match self.builder.state.data_field.take() {
+                // Data fields may contain the separator, so we must rely on
+                // the value of their `Length` field.
+                Some((data_tag, len)) if data_tag == tag_num => {
+                    if index_of_next_equal_sign + 1 + len > payload.len() {
+                        break;
+                    }
+                    len
+                }
+                _ => match memchr::memchr(separator, &payload[index_of_next_equal_sign + 1..]) {
+                    Some(len) => len,
+                    None => break,
+                },
+            };
             self.store_field(
                 tag_num,
                 frame.payload(),
@@ -159,6 +326,12 @@ where
             // Separator                                       ~~~
             i = index_of_next_equal_sign + 1 + field_value_len + 1;
         }
+        if self.config().should_decode_associative() {
+            self.builder.build_index();
+            if let Some(validator) = &self.validator {
+                validator.validate(&self.message::<&[u8]>())?;
+            }
+        }
         Ok(Message {
             builder: self.message_builder_mut(),
             phantom: PhantomData::default(),
@@ -173,7 +346,6 @@ where
         field_value_start: usize,
         field_value_len: usize,
     ) {
-        let config_assoc = self.config().should_decode_associative();
         let field_value = &raw_message[field_value_start..][..field_value_len];
         if self.builder.state.new_group.is_some() {
             // We are entering a new group, but we still don't know which tag
@@ -187,29 +359,53 @@ where
             }
         }
         self.message_builder_mut()
-            .add_field(
-                tag,
-                &raw_message[field_value_start..][..field_value_len],
-                config_assoc,
-            )
-            .unwrap();
-        let fix_type = self.tag_lookup.get(&tag.get());
-        if fix_type == Some(&FixDatatype::NumInGroup) {
+            .add_field(tag, &raw_message[field_value_start..][..field_value_len]);
+        let tables = self.dictionaries[self.active_dictionary].lookup_tables();
+        if tables.num_in_group_tags().contains(tag.get().into()) {
             self.builder
                 .state
-                .add_group(tag, self.builder.field_locators.len() - 1, field_value);
-        } else if fix_type == Some(&FixDatatype::Length) {
-            // FIXME
-            let last_field_locator = self.builder.field_locators.last().unwrap();
-            let last_field = self.builder.fields.get(last_field_locator).unwrap();
-            let last_field_value = last_field.1;
-            let s = std::str::from_utf8(last_field_value).unwrap();
-            let data_field_length = str::parse(s).unwrap();
-            self.builder.state.data_field_length = Some(data_field_length);
+                .add_group(tag, self.builder.fields.len() - 1, field_value);
+        } else if let Some(data_tag) = tables.data_field_tag(tag.get().into()) {
+            // Invalid lengths are simply ignored, and the data field is then
+            // delimited by the separator like any other field.
+            if let Ok(len) = usize::deserialize(field_value) {
+                self.builder.state.data_field = Some((data_tag, len));
+            }
+        } else if tag.get() == APPL_VER_ID {
+            // The rest of the message follows the application version.
+            if let Some(i) = lookup(&self.by_appl_ver_id, field_value) {
+                self.active_dictionary = i;
+            }
         }
     }
 }
 
+/// Returns the `ApplVerID <1128>` value of the FIX version `version`, if any.
+fn appl_ver_id(version: &str) -> Option<&'static [u8]> {
+    Some(match version {
+        "FIX.4.0" => b"2",
+        "FIX.4.1" => b"3",
+        "FIX.4.2" => b"4",
+        "FIX.4.3" => b"5",
+        "FIX.4.4" => b"6",
+        "FIX.5.0" => b"7",
+        "FIX.5.0-SP1" => b"8",
+        "FIX.5.0-SP2" => b"9",
+        _ => return None,
+    })
+}
+
+fn lookup(keys: &[(Vec<u8>, usize)], key: &[u8]) -> Option<usize> {
+    keys.iter().find(|(k, _)| k == key).map(|(_, i)| *i)
+}
+
+fn insert_or_replace(keys: &mut Vec<(Vec<u8>, usize)>, key: &[u8], i: usize) {
+    match keys.iter_mut().find(|(k, _)| k == key) {
+        Some(entry) => entry.1 = i,
+        None => keys.push((key.to_vec(), i)),
+    }
+}
+
 impl<C> GetConfig for Decoder<C> {
     type Config = C;
 
@@ -237,6 +433,7 @@ pub struct DecoderBuffered<C = Config> {
     decoder: Decoder<C>,
     raw_decoder: RawDecoderBuffered<C>,
     is_ready: bool,
+    has_failed: bool,
 }
 
 impl<C> DecoderBuffered<C>
@@ -246,22 +443,44 @@ where
     /// Provides a buffer that must be filled before re-attempting to deserialize
     /// the next [`Message`].
     ///
-    /// [`DecoderBuffered::supply_buffer`] is *guaranteed* to be non-empty.
+    /// The provided buffer is empty if `self` already holds enough data
+    /// (e.g. after [`DecoderBuffered::resync`]), in which case you should call
+    /// [`DecoderBuffered::parse`] right away.
     ///
-    /// # Panics
-    ///
-    /// Panics if the last call to [`DecoderBuffered::parse`]
-    /// returned an [`Err`].
+    /// If the last call to [`DecoderBuffered::parse`] returned an [`Err`],
+    /// this calls [`DecoderBuffered::resync`] first.
     #[inline]
     pub fn supply_buffer(&mut self) -> &mut [u8] {
+        if self.has_failed {
+            self.resync();
+        }
+        self.is_ready = false;
         self.raw_decoder.supply_buffer()
     }
 
+    /// Discards all buffered data up to the next `8=FIX` start sequence, so
+    /// that decoding can go on after [`DecoderBuffered::parse`] returned an
+    /// [`Err`]. Returns the discarded bytes, e.g. for logging purposes.
+    #[inline]
+    pub fn resync(&mut self) -> &[u8] {
+        self.is_ready = false;
+        self.has_failed = false;
+        self.raw_decoder.resync()
+    }
+
+    /// Returns the bytes that were discarded by the last call to
+    /// [`DecoderBuffered::resync`].
+    #[inline]
+    pub fn discarded(&self) -> &[u8] {
+        self.raw_decoder.discarded()
+    }
+
     /// Completes erases the contents of the internal buffer of `self`.
     #[inline]
     pub fn clear(&mut self) {
         self.raw_decoder.clear();
         self.is_ready = false;
+        self.has_failed = false;
     }
 
     /// After filling the buffer provided by [`DecoderBuffered::supply_buffer`],
@@ -275,14 +494,17 @@ where
     #[inline]
     pub fn parse(&mut self) -> Result<Option<()>, DecodeError> {
         self.raw_decoder.parse();
-        match self.raw_decoder.raw_frame() {
-            Ok(Some(frame)) => {
-                self.decoder.construct_message_from_frame(frame)?;
-                Ok(Some(()))
-            }
+        let result = match self.raw_decoder.raw_frame() {
+            Ok(Some(frame)) => self
+                .decoder
+                .construct_message_from_frame(frame)
+                .map(|_| Some(())),
             Ok(None) => Ok(None),
             Err(e) => Err(e),
-        }
+        };
+        self.is_ready = matches!(result, Ok(Some(())));
+        self.has_failed = result.is_err();
+        result
     }
 
     /// # Panics
@@ -292,11 +514,7 @@ where
     pub fn message(&self) -> Message<Cow<[u8]>> {
         assert!(self.is_ready);
 
-        Message {
-            builder: &self.decoder.builder,
-            phantom: PhantomData::default(),
-            field_locator_context: FieldLocatorContext::TopLevel,
-        }
+        self.decoder.message()
     }
 }
 
@@ -425,13 +643,151 @@ impl<'a, T> Message<'a, T> {
     /// assert_eq!(message.len(), message.fields().count());
     /// ```
     pub fn len(&self) -> usize {
-        self.builder.field_locators.len()
+        self.builder.fields.len()
     }
 
     /// Is the FIX tags contained in `self` none.
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Returns the character encoding of the `Encoded*` fields in `self`, as
+    /// specified by `MessageEncoding <347>`. ISO-8859-1 is assumed if
+    /// `MessageEncoding <347>` is missing. Group entries share the encoding of
+    /// the whole message.
+    pub fn message_encoding(&self) -> Result<MessageEncoding, EncodingError> {
+        let field_locator = FieldLocator {
+            tag: TagU16::new(347).unwrap(),
+            context: FieldLocatorContext::TopLevel,
+        };
+        match self.builder.get(&field_locator) {
+            Some(i) => MessageEncoding::for_label(self.builder.fields[i].1)
+                .ok_or(EncodingError::UnknownEncoding),
+            None => Ok(MessageEncoding::default()),
+        }
+    }
+
+    /// Queries `self` for the `Encoded*` field `tag` (e.g. `EncodedText <355>`)
+    /// and decodes it according to [`Message::message_encoding`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::tagvalue::{Config, Decoder};
+    /// use fefix::prelude::*;
+    ///
+    /// const DATA: &[u8] =
+    ///     b"8=FIX.4.4|9=40|35=B|347=Shift_JIS|148=X|354=4|355=\x93\x8c\x8b\x9e|10=228|";
+    ///
+    /// let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+    /// decoder.config_mut().set_separator(b'|');
+    ///
+    /// let message = decoder.decode(DATA).unwrap();
+    /// assert_eq!(message.fv_encoded(355).unwrap().unwrap(), "東京");
+    /// ```
+    pub fn fv_encoded(&self, tag: u32) -> Option<Result<Cow<'a, str>, EncodingError>> {
+        let field_locator = FieldLocator {
+            tag: TagU16::new(u16::try_from(tag).ok()?)?,
+            context: self.field_locator_context,
+        };
+        let value = self.builder.fields[self.builder.get(&field_locator)?].1;
+        Some(
+            self.message_encoding()
+                .and_then(|encoding| encoding.decode(value)),
+        )
+    }
+}
+
+/// A FIX message that owns its contents, as returned by
+/// [`Decoder::decode_owned`].
+///
+/// Unlike [`Message`], [`OwnedMessage`] doesn't borrow from the [`Decoder`]
+/// that produced it, so it can be stored, sent to other tasks, and produced by
+/// [`TokioDecoder`](super::TokioDecoder).
+#[derive(Debug, Clone)]
+#[cfg(feature = "utils-bytes")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "utils-bytes")))]
+pub struct OwnedMessage {
+    // All references within `builder` point to the heap-allocated contents of
+    // `bytes`, which never move and live as long as `self`.
+    builder: MessageBuilder<'static>,
+    bytes: bytes::Bytes,
+    builders: Pool<MessageBuilder<'static>>,
+}
+
+#[cfg(feature = "utils-bytes")]
+impl OwnedMessage {
+    /// Returns a [`Message`] view over `self`, with field and group access.
+    pub fn message(&self) -> Message<bytes::Bytes> {
+        Message {
+            builder: &self.builder,
+            phantom: PhantomData::default(),
+            field_locator_context: FieldLocatorContext::TopLevel,
+        }
+    }
+
+    /// Returns the value of the top-level field `tag`, if any, as
+    /// [`bytes::Bytes`]. No copy takes place: the value shares the underlying
+    /// memory of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::tagvalue::{Config, Decoder};
+    /// use fefix::prelude::*;
+    /// use bytes::Bytes;
+    ///
+    /// let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+    /// decoder.config_mut().set_separator(b'|');
+    /// let data = Bytes::from_static(b"8=FIX.4.4|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=185|");
+    /// let message = decoder.decode_owned(data).unwrap();
+    ///
+    /// assert_eq!(message.fv_bytes(52), Some(Bytes::from_static(b"20100304-07:59:30")));
+    /// ```
+    pub fn fv_bytes(&self, tag: u32) -> Option<bytes::Bytes> {
+        self.message()
+            .fv_raw(tag)
+            .map(|value| self.slice_ref(value))
+    }
+
+    /// Returns an [`Iterator`] over all fields in `self`, like
+    /// [`Message::fields`], but with values as [`bytes::Bytes`].
+    pub fn fields_bytes(&self) -> impl Iterator<Item = (TagU16, bytes::Bytes)> + '_ {
+        self.builder
+            .fields
+            .iter()
+            .map(move |(field_locator, value)| (field_locator.tag, self.slice_ref(value)))
+    }
+
+    /// Converts `subset`, which must be a slice of `self` (e.g. a field
+    /// value within a repeating group), into [`bytes::Bytes`] without copying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `subset` is not contained within [`OwnedMessage::as_bytes`].
+    pub fn slice_ref(&self, subset: &[u8]) -> bytes::Bytes {
+        self.bytes.slice_ref(subset)
+    }
+
+    /// Returns the underlying byte contents of `self`.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..]
+    }
+
+    /// Consumes `self` and returns its underlying byte contents.
+    pub fn into_bytes(mut self) -> bytes::Bytes {
+        std::mem::take(&mut self.bytes)
+    }
+}
+
+#[cfg(feature = "utils-bytes")]
+impl Drop for OwnedMessage {
+    fn drop(&mut self) {
+        let mut builder = std::mem::take(&mut self.builder);
+        // No references to `bytes` must outlive `self`.
+        builder.clear();
+        self.builders.put(builder);
+    }
 }
 
 impl<'a, T> PartialEq for Message<'a, T> {
@@ -464,7 +820,7 @@ struct DecoderStateNewGroup {
 struct DecoderState {
     group_information: Vec<DecoderGroupState>,
     new_group: Option<DecoderStateNewGroup>,
-    data_field_length: Option<usize>,
+    data_field: Option<(TagU16, usize)>,
 }
 
 impl DecoderState {
@@ -506,12 +862,17 @@ impl DecoderState {
 }
 
 /// FIX message data structure with fast associative and sequential access.
+///
+/// Fields are stored in a flat [`Vec`], in the same order as they appear in the
+/// message. Associative access goes through `index`, which holds positions
+/// within `fields` sorted by [`FieldLocator`]. Both are reused across messages
+/// and, unlike a hash map, are contiguous in memory.
 #[derive(Debug, Clone)]
 struct MessageBuilder<'a> {
     state: DecoderState,
     raw: &'a [u8],
-    fields: HashMap<FieldLocator, (TagU16, &'a [u8], usize)>,
-    field_locators: Vec<FieldLocator>,
+    fields: Vec<(FieldLocator, &'a [u8])>,
+    index: Vec<u32>,
     i_first_cell: usize,
     i_last_cell: usize,
     len_end_header: usize,
@@ -526,11 +887,11 @@ impl<'a> Default for MessageBuilder<'a> {
             state: DecoderState {
                 group_information: Vec::new(),
                 new_group: None,
-                data_field_length: None,
+                data_field: None,
             },
             raw: b"",
-            field_locators: Vec::new(),
-            fields: HashMap::new(),
+            fields: Vec::new(),
+            index: Vec::new(),
             i_first_cell: 0,
             i_last_cell: 0,
             len_end_body: 0,
@@ -544,23 +905,41 @@ impl<'a> Default for MessageBuilder<'a> {
 impl<'a> MessageBuilder<'a> {
     fn clear(&mut self) {
         self.raw = b"";
+        self.bytes = b"";
         self.fields.clear();
-        self.field_locators.clear();
+        self.index.clear();
+        // Truncated messages may leave groups open.
+        self.state.group_information.clear();
+        self.state.new_group = None;
+        self.state.data_field = None;
     }
 
-    fn add_field(
-        &mut self,
-        tag: TagU16,
-        field_value: &'a [u8],
-        associative: bool,
-    ) -> Result<(), DecodeError> {
+    fn add_field(&mut self, tag: TagU16, field_value: &'a [u8]) {
         let field_locator = self.state.current_field_locator(tag);
-        let i = self.field_locators.len();
-        if associative {
-            self.fields.insert(field_locator, (tag, field_value, i));
+        self.fields.push((field_locator, field_value));
+    }
+
+    fn build_index(&mut self) {
+        let fields = &self.fields;
+        self.index.extend(0..fields.len() as u32);
+        // Ties are broken by position, so that the last occurrence of a
+        // duplicate field wins.
+        self.index
+            .sort_unstable_by_key(|i| (fields[*i as usize].0, *i));
+    }
+
+    /// Returns the position within `self.fields` of the field located by
+    /// `field_locator`, if any.
+    fn get(&self, field_locator: &FieldLocator) -> Option<usize> {
+        let end = self
+            .index
+            .partition_point(|i| self.fields[*i as usize].0 <= *field_locator);
+        let i = *self.index.get(end.checked_sub(1)?)? as usize;
+        if self.fields[i].0 == *field_locator {
+            Some(i)
+        } else {
+            None
         }
-        self.field_locators.push(field_locator);
-        Ok(())
     }
 }
 
@@ -586,10 +965,9 @@ impl<'a, T> Iterator for Fields<'a, T> {
         if self.i == self.message.len() {
             None
         } else {
-            let context = self.message.builder.field_locators[self.i];
-            let field = self.message.builder.fields.get(&context).unwrap();
+            let (field_locator, value) = self.message.builder.fields[self.i];
             self.i += 1;
-            Some((field.0, field.1))
+            Some((field_locator.tag, value))
         }
     }
 }
@@ -606,9 +984,10 @@ where
             tag,
             context: self.field_locator_context,
         };
-        let num_in_group = self.builder.fields.get(&field_locator_of_group_tag)?;
-        let index_of_group_tag = num_in_group.2 as u32;
-        let field_value_str = std::str::from_utf8(num_in_group.1).ok()?;
+        let index_of_group_tag = self.builder.get(&field_locator_of_group_tag)?;
+        let num_in_group = self.builder.fields[index_of_group_tag].1;
+        let index_of_group_tag = index_of_group_tag as u32;
+        let field_value_str = std::str::from_utf8(num_in_group).ok()?;
         let num_entries = str::parse(field_value_str).unwrap();
         Some(Ok(MessageGroup {
             message: Message {
@@ -627,7 +1006,9 @@ where
             tag,
             context: self.field_locator_context,
         };
-        self.builder.fields.get(&field_locator).map(|field| field.1)
+        self.builder
+            .get(&field_locator)
+            .map(|i| self.builder.fields[i].1)
     }
 }
 
@@ -692,7 +1073,10 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::tagvalue::Config;
+    use crate::tagvalue::{Config, Encoder};
+    use crate::utils::counting_allocator::allocations;
+    use crate::SetField;
+    use std::fmt::Write;
 
     // Use http://www.validfix.com/fix-analyzer.html for testing.
 
@@ -782,6 +1166,87 @@ mod test {
         assert_eq!(message.fv_raw(34), Some(b"12" as &[u8]));
     }
 
+    #[test]
+    fn buffered_decoder_yields_message() {
+        let mut decoder = decoder().buffered();
+        let mut stream = RANDOM_MESSAGES[0].as_bytes();
+        loop {
+            let buffer = decoder.supply_buffer();
+            let (head, tail) = stream.split_at(buffer.len());
+            buffer.copy_from_slice(head);
+            stream = tail;
+            if decoder.parse().unwrap().is_some() {
+                break;
+            }
+        }
+        assert!(stream.is_empty());
+        assert_eq!(decoder.message().fv(34), Ok(12));
+    }
+
+    #[test]
+    fn buffered_decoder_resyncs_after_garbled_data() {
+        let good = "8=FIX.4.4|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=187|";
+        let bad = "8=FIX.4.4|9=5|35=0|49=A|56=B|10=000|";
+        let stream = ["noise|noise|noise|noise|", good, bad, good]
+            .concat()
+            .replace('|', "\x01");
+        let mut decoder = Decoder::<Config>::new(Dictionary::fix44()).buffered();
+        decoder.config_mut().set_separator(0x1);
+        decoder.config_mut().set_verify_checksum(true);
+        let mut stream = stream.as_bytes();
+        let mut discarded = Vec::new();
+        let mut seq_nums = Vec::new();
+        while !stream.is_empty() {
+            let buffer = decoder.supply_buffer();
+            let (head, tail) = stream.split_at(buffer.len());
+            buffer.copy_from_slice(head);
+            stream = tail;
+            match decoder.parse() {
+                Ok(Some(())) => seq_nums.push(decoder.message().fv::<u32>(34).unwrap()),
+                Ok(None) => {}
+                Err(_) => discarded.extend_from_slice(decoder.resync()),
+            }
+        }
+        assert_eq!(seq_nums, vec![12, 12]);
+        assert_eq!(
+            discarded,
+            ["noise|noise|noise|noise|", bad]
+                .concat()
+                .replace('|', "\x01")
+                .as_bytes()
+        );
+    }
+
+    #[test]
+    fn fields_without_associative_decoding() {
+        let mut decoder = decoder();
+        decoder.config_mut().set_decode_assoc(false);
+        let message = decoder.decode(RANDOM_MESSAGES[0].as_bytes()).unwrap();
+        assert_eq!(message.fv_raw(35), None);
+        assert_eq!(
+            message.fields().nth(1),
+            Some((TagU16::new(35).unwrap(), &b"0"[..]))
+        );
+    }
+
+    #[test]
+    fn many_group_entries() {
+        let mut body = "35=W|55=EUR/USD|268=500|".to_string();
+        for i in 0..500 {
+            write!(body, "269={}|270={}|", i % 2, i).unwrap();
+        }
+        let msg = format!("8=FIX.4.4|9={}|{}10=000|", body.len(), body);
+        let mut decoder = decoder();
+        let message = decoder.decode(msg.as_bytes()).unwrap();
+        assert_eq!(message.fv::<&str>(55), Ok("EUR/USD"));
+        let group = message.group(268).unwrap();
+        assert_eq!(group.len(), 500);
+        for (i, entry) in group.entries().enumerate() {
+            assert_eq!(entry.fv::<usize>(270), Ok(i));
+            assert_eq!(entry.fv::<usize>(269), Ok(i % 2));
+        }
+    }
+
     #[test]
     fn message_without_final_separator() {
         let mut codec = decoder();
@@ -816,6 +1281,17 @@ mod test {
         assert!(matches!(result.fv_raw(89), Some(b"foo|\x01bar")));
     }
 
+    #[test]
+    fn length_field_without_data_field() {
+        // `MaxMessageSize <383>` is a `Length` field, but not the length of
+        // the field that follows it.
+        let msg = "8=FIX.4.4|9=29|35=A|98=0|108=30|383=2|384=0|10=000|";
+        let mut codec = decoder();
+        let result = codec.decode(msg.as_bytes()).unwrap();
+        assert_eq!(result.fv(383), Ok(2));
+        assert_eq!(result.fv(384), Ok(0));
+    }
+
     #[test]
     fn message_without_standard_header() {
         let msg = "35=D|49=AFUNDMGR|56=ABROKERt|15=USD|59=0|10=000|";
@@ -831,4 +1307,185 @@ mod test {
         let result = codec.decode(msg.as_bytes());
         assert!(matches!(result, Err(DecodeError::Invalid)));
     }
+
+    #[test]
+    fn encoded_text_round_trip() {
+        // "ポ" is `0x83 0x7C` in Shift_JIS, i.e. it contains a '|' separator.
+        let text = "ポートフォリオ";
+        let mut encoder = Encoder::<Config>::default();
+        encoder.config_mut().set_separator(b'|');
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"B");
+        msg.set(347, MessageEncoding::SHIFT_JIS);
+        msg.set(148, "Headline");
+        msg.set_encoded(354, 355, text, MessageEncoding::SHIFT_JIS)
+            .unwrap();
+        msg.set(58, "ASCII");
+        let (data, _) = msg.done();
+        assert!(data.windows(2).any(|w| w == b"\x83|"));
+
+        let mut codec = decoder();
+        let message = codec.decode(data).unwrap();
+        assert_eq!(message.message_encoding(), Ok(MessageEncoding::SHIFT_JIS));
+        assert_eq!(message.fv(354), Ok(14));
+        assert_eq!(message.fv_encoded(355), Some(Ok(text.into())));
+        assert_eq!(message.fv_encoded(58), Some(Ok(Cow::Borrowed("ASCII"))));
+        assert_eq!(message.fv_encoded(356), None);
+    }
+
+    #[test]
+    fn encoded_text_defaults_to_latin1() {
+        let msg = b"8=FIX.4.4|9=26|35=B|148=X|354=4|355=caf\xe9|10=000|";
+        let mut codec = decoder();
+        let message = codec.decode(&msg[..]).unwrap();
+        assert_eq!(message.message_encoding(), Ok(MessageEncoding::ISO_8859_1));
+        assert_eq!(message.fv_encoded(355), Some(Ok("caf\u{e9}".into())));
+    }
+
+    #[test]
+    fn unknown_message_encoding() {
+        let msg = "8=FIX.4.4|9=34|35=B|347=FOOBAR|148=X|354=1|355=a|10=000|";
+        let mut codec = decoder();
+        let message = codec.decode(msg.as_bytes()).unwrap();
+        assert_eq!(
+            message.message_encoding(),
+            Err(EncodingError::UnknownEncoding)
+        );
+        assert_eq!(
+            message.fv_encoded(355),
+            Some(Err(EncodingError::UnknownEncoding))
+        );
+    }
+
+    #[cfg(feature = "utils-bytes")]
+    #[test]
+    fn owned_message_values_as_bytes() {
+        let msg = "8=FIX.4.2|9=196|35=X|49=A|56=B|34=12|52=20100318-03:21:11.364|262=A|268=2|279=0|269=0|278=BID|55=EUR/USD|270=1.37215|15=EUR|271=2500000|346=1|279=0|269=1|278=OFFER|55=EUR/USD|270=1.37224|15=EUR|271=2503200|346=1|10=171|";
+        let data = bytes::Bytes::from(msg);
+        let mut codec = decoder();
+        let message = codec.decode_owned(data.clone()).unwrap();
+        assert_eq!(message.fv_bytes(262), Some(bytes::Bytes::from_static(b"A")));
+        assert_eq!(message.fv_bytes(9999), None);
+
+        let entry = message.message().group(268).unwrap().entry(1);
+        let offer = message.slice_ref(entry.fv_raw(278).unwrap());
+        assert_eq!(&offer[..], b"OFFER");
+        assert!(data.as_ptr_range().contains(&offer.as_ptr()));
+
+        let fields: Vec<(u16, bytes::Bytes)> = message
+            .fields_bytes()
+            .map(|(tag, value)| (tag.get(), value))
+            .collect();
+        let expected: Vec<(u16, bytes::Bytes)> = message
+            .message()
+            .fields()
+            .map(|(tag, value)| (tag.get(), bytes::Bytes::copy_from_slice(value)))
+            .collect();
+        assert_eq!(fields, expected);
+    }
+
+    fn multi_version_decoder() -> Decoder<Config> {
+        let mut decoder = Decoder::<Config>::new(Dictionary::empty());
+        decoder.add_dictionary(Dictionary::fix44());
+        decoder.config_mut().set_separator(b'|');
+        decoder
+    }
+
+    #[test]
+    fn dictionary_is_selected_by_begin_string() {
+        let mut codec = multi_version_decoder();
+        let msg = "8=FIX.4.4|9=39|35=V|262=1|146=2|55=AAPL|55=MSFT|263=0|10=197|";
+        let message = codec.decode(msg.as_bytes()).unwrap();
+        assert_eq!(
+            message.group(146).unwrap().entry(1).fv::<&str>(55),
+            Ok("MSFT")
+        );
+        assert_eq!(message.fv_raw(55), None);
+
+        // Falls back to the default dictionary, which has no groups.
+        let msg = "8=FIX.4.2|9=39|35=V|262=1|146=2|55=AAPL|55=MSFT|263=0|10=195|";
+        let message = codec.decode(msg.as_bytes()).unwrap();
+        assert_eq!(message.fv::<&str>(55), Ok("MSFT"));
+    }
+
+    #[test]
+    fn dictionary_is_selected_by_appl_ver_id() {
+        let mut codec = multi_version_decoder();
+        let msg = "8=FIXT.1.1|9=46|35=V|1128=6|262=1|146=2|55=AAPL|55=MSFT|263=0|10=081|";
+        let message = codec.decode(msg.as_bytes()).unwrap();
+        assert_eq!(
+            message.group(146).unwrap().entry(1).fv::<&str>(55),
+            Ok("MSFT")
+        );
+
+        let msg = "8=FIXT.1.1|9=46|35=V|1128=9|262=1|146=2|55=AAPL|55=MSFT|263=0|10=084|";
+        let message = codec.decode(msg.as_bytes()).unwrap();
+        assert_eq!(message.fv::<&str>(55), Ok("MSFT"));
+
+        codec.add_dictionary_for_appl_ver_id(b"9", Dictionary::fix44());
+        let message = codec.decode(msg.as_bytes()).unwrap();
+        assert_eq!(
+            message.group(146).unwrap().entry(0).fv::<&str>(55),
+            Ok("AAPL")
+        );
+        assert_eq!(message.fv_raw(55), None);
+    }
+
+    #[test]
+    fn dictionary_is_selected_by_custom_begin_string() {
+        let mut codec = multi_version_decoder();
+        codec.add_dictionary_for_begin_string(b"FIX.4.2", Dictionary::fix44());
+        let msg = "8=FIX.4.2|9=39|35=V|262=1|146=2|55=AAPL|55=MSFT|263=0|10=195|";
+        let message = codec.decode(msg.as_bytes()).unwrap();
+        assert_eq!(message.fv_raw(55), None);
+    }
+
+    #[test]
+    fn decoding_does_not_allocate_in_steady_state() {
+        let messages: Vec<String> = RANDOM_MESSAGES.iter().map(|msg| with_soh(msg)).collect();
+        let mut codec = Decoder::<Config>::new(Dictionary::fix44());
+        codec.config_mut().set_verify_checksum(false);
+        for msg in messages.iter() {
+            codec.decode(msg.as_bytes()).unwrap();
+        }
+        let allocations = allocations(|| {
+            for _ in 0..100 {
+                for msg in messages.iter() {
+                    let message = codec.decode(msg.as_bytes()).unwrap();
+                    assert!(message.fv_raw(35).is_some());
+                }
+            }
+        });
+        assert_eq!(allocations, 0);
+    }
+
+    #[cfg(feature = "utils-bytes")]
+    #[test]
+    fn owned_messages_are_pooled() {
+        let messages: Vec<bytes::Bytes> = RANDOM_MESSAGES
+            .iter()
+            .map(|msg| bytes::Bytes::from(with_soh(msg)))
+            .collect();
+        let mut codec = Decoder::<Config>::new(Dictionary::fix44());
+        codec.config_mut().set_verify_checksum(false);
+        let mut in_flight = Vec::with_capacity(messages.len());
+        // The decoder needs one more builder than there are messages in
+        // flight.
+        for _ in 0..2 {
+            for msg in messages.iter() {
+                in_flight.push(codec.decode_owned(msg.clone()).unwrap());
+            }
+            in_flight.clear();
+        }
+        let allocations = allocations(|| {
+            for _ in 0..100 {
+                for msg in messages.iter() {
+                    in_flight.push(codec.decode_owned(msg.clone()).unwrap());
+                }
+                assert_eq!(in_flight[2].message().fv::<&str>(35), Ok("AD"));
+                in_flight.clear();
+            }
+        });
+        assert_eq!(allocations, 0);
+    }
 }
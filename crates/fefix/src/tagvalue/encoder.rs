@@ -1,11 +1,11 @@
-use super::{Config, Configure, FvWrite};
+use super::{Config, Configure, FvWrite, HeaderTemplate, TemplateBuilder};
 use crate::buffer::Buffer;
-use crate::dict::IsFieldDefinition;
-use crate::fix_value::{CheckSum, FixValue};
+use crate::dict::{IsFieldDefinition, LookupTables};
+use crate::fix_value::{CheckSum, EncodingError, FixValue, MessageEncoding};
 use crate::BufferWriter;
 use crate::GetConfig;
 use crate::SetField;
-use crate::TagU16;
+use crate::{Dictionary, TagU16};
 use std::fmt::Write;
 use std::ops::Range;
 
@@ -30,6 +30,7 @@ use std::ops::Range;
 #[derive(Debug, Clone, Default)]
 pub struct Encoder<C = Config> {
     config: C,
+    dict: Option<Dictionary>,
 }
 
 impl<C> Encoder<C>
@@ -50,7 +51,18 @@ where
     /// assert_eq!(encoder.config().separator(), b'|');
     /// ```
     pub fn new(config: C) -> Self {
-        Self { config }
+        Self { config, dict: None }
+    }
+
+    /// Creates a new [`Encoder`] from the given `config` options. Repeating
+    /// groups written with [`EncoderHandle::start_group`] are validated
+    /// against the definitions in `dict`, and the `Length` fields of data
+    /// fields written with [`EncoderHandle::set_data`] are taken from `dict`.
+    pub fn with_dictionary(config: C, dict: &Dictionary) -> Self {
+        Self {
+            config,
+            dict: Some(dict.clone()),
+        }
     }
 
     /// Creates a new [`EncoderHandle`] that allows to set the field values of a
@@ -85,7 +97,9 @@ where
         //
         // Luckily, FIX allows for zero-padded integer values and we can
         // leverage this to reserve some space for the value. We waste
-        // some bytes but the benefits largely outweight the costs.
+        // some bytes but the benefits largely outweight the costs. If
+        // zero-padding is turned off, the body is shifted back over the
+        // unused digits once it's complete.
         //
         // Eight digits (~100MB) are enough for every message.
         state.set(9, b"00000000" as &[u8]);
@@ -93,6 +107,30 @@ where
         state.set(35, msg_type);
         state
     }
+
+    /// Like [`Encoder::start_message`], but it also writes all standard header
+    /// fields in `header`, followed by the body. `SendingTime <52>` is the
+    /// current system time.
+    pub fn start_message_with_header<'a, B>(
+        &'a mut self,
+        header: &HeaderTemplate,
+        buffer: &'a mut B,
+        msg_type: &[u8],
+        msg_seq_num: u64,
+    ) -> EncoderHandle<'a, B, C>
+    where
+        B: Buffer,
+    {
+        let mut msg = self.start_message(header.begin_string(), buffer, msg_type);
+        header.write_fields(&mut msg, msg_seq_num, chrono::Utc::now());
+        msg
+    }
+
+    /// Creates a new [`TemplateBuilder`] for a pre-serialized message with
+    /// fixed-width slots. See [`MessageTemplate`](super::MessageTemplate).
+    pub fn start_template(&self, begin_string: &[u8], msg_type: &[u8]) -> TemplateBuilder {
+        TemplateBuilder::new(&self.config, begin_string, msg_type)
+    }
 }
 
 impl<C> GetConfig for Encoder<C> {
@@ -135,6 +173,99 @@ where
         (self.buffer.as_slice(), self.initial_buffer_len)
     }
 
+    /// Starts writing a repeating group with the `NumInGroup` field
+    /// `num_in_group_tag` and returns a [`GroupEncoder`] to write its entries.
+    /// `delimiter_tag` is the first field of every entry. See [`GroupEncoder`]
+    /// for more information.
+    pub fn start_group(
+        &mut self,
+        num_in_group_tag: u32,
+        delimiter_tag: u32,
+    ) -> GroupEncoder<'_, B> {
+        GroupEncoder::new(
+            &mut *self.buffer,
+            self.encoder.config.separator(),
+            self.encoder.dict.as_ref().map(Dictionary::lookup_tables),
+            num_in_group_tag,
+            delimiter_tag,
+            None,
+        )
+    }
+
+    /// Writes the `Encoded*` field `tag` (e.g. `EncodedText <355>`) with the
+    /// contents of `value` converted to `encoding`, preceded by its `Length`
+    /// field `len_tag` (e.g. `EncodedTextLen <354>`). `encoding` should be the
+    /// same as the value of `MessageEncoding <347>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::tagvalue::{Config, Encoder};
+    /// use fefix::fix_value::MessageEncoding;
+    /// use fefix::prelude::*;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut encoder = Encoder::<Config>::default();
+    /// encoder.config_mut().set_separator(b'|');
+    /// let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"B");
+    /// msg.set(347, MessageEncoding::SHIFT_JIS);
+    /// msg.set(148, "X");
+    /// msg.set_encoded(354, 355, "東京", MessageEncoding::SHIFT_JIS).unwrap();
+    /// let (data, _) = msg.done();
+    ///
+    /// assert!(data.ends_with(b"|354=4|355=\x93\x8c\x8b\x9e|10=228|"));
+    /// ```
+    pub fn set_encoded(
+        &mut self,
+        len_tag: u32,
+        tag: u32,
+        value: &str,
+        encoding: MessageEncoding,
+    ) -> Result<(), EncodingError> {
+        let bytes = encoding.encode(value)?;
+        self.set_data_with_len(len_tag, tag, &bytes[..]);
+        Ok(())
+    }
+
+    /// Writes the data field `tag` (e.g. `RawData <96>`, `XmlData <213>`)
+    /// preceded by its associated `Length` field, which is taken from the
+    /// [`Dictionary`] given to [`Encoder::with_dictionary`]. `data` may
+    /// contain any byte, including the separator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::tagvalue::{Config, Encoder};
+    /// use fefix::prelude::*;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut encoder = Encoder::with_dictionary(Config::default(), &Dictionary::fix44());
+    /// let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"A");
+    /// msg.set(98, 0u32);
+    /// msg.set(108, 30u32);
+    /// msg.set_data(96, b"\x01secret\x01").unwrap();
+    /// let (data, _) = msg.done();
+    ///
+    /// assert!(data.ends_with(b"\x0195=8\x0196=\x01secret\x01\x0110=056\x01"));
+    /// ```
+    pub fn set_data(&mut self, tag: u32, data: &[u8]) -> Result<(), UnknownDataField> {
+        let len_tag = self
+            .encoder
+            .dict
+            .as_ref()
+            .and_then(|dict| dict.lookup_tables().length_field_tag(tag))
+            .ok_or(UnknownDataField(tag))?;
+        self.set_data_with_len(len_tag.get() as u32, tag, data);
+        Ok(())
+    }
+
+    /// Like [`EncoderHandle::set_data`], but with an explicit `Length` field
+    /// `len_tag`. It doesn't need a [`Dictionary`].
+    pub fn set_data_with_len(&mut self, len_tag: u32, tag: u32, data: &[u8]) {
+        self.set(len_tag, data.len());
+        self.set(tag, data);
+    }
+
     fn body_length_writable_range(&self) -> Range<usize> {
         self.body_start_i - 9..self.body_start_i - 1
     }
@@ -144,6 +275,10 @@ where
     }
 
     fn write_body_length(&mut self) {
+        if !self.encoder.config().zero_padded_body_length() {
+            self.write_canonical_body_length();
+            return;
+        }
         let body_length = self.body_length() as u32;
         let body_length_range = self.body_length_writable_range();
         let slice = &mut self.buffer.as_mut_slice()[body_length_range];
@@ -157,12 +292,75 @@ where
         slice[7] = to_digit(body_length as u8 % 10);
     }
 
+    fn write_canonical_body_length(&mut self) {
+        let mut digits = [0u8; 8];
+        let mut body_length = self.body_length();
+        let mut num_digits = 0;
+        loop {
+            num_digits += 1;
+            digits[8 - num_digits] = to_digit((body_length % 10) as u8);
+            body_length /= 10;
+            if body_length == 0 {
+                break;
+            }
+        }
+        let shift = 8 - num_digits;
+        let body_length_start_i = self.body_length_writable_range().start;
+        let buffer_len = self.buffer.len();
+        let slice = self.buffer.as_mut_slice();
+        slice[body_length_start_i..][..num_digits].copy_from_slice(&digits[shift..]);
+        slice.copy_within(
+            body_length_start_i + 8..buffer_len,
+            body_length_start_i + num_digits,
+        );
+        self.buffer.resize(buffer_len - shift, 0);
+        self.body_start_i -= shift;
+    }
+
     fn write_checksum(&mut self) {
-        let checksum = CheckSum::compute(self.buffer.as_slice());
+        // `buffer` may contain other data before the current message.
+        let checksum = CheckSum::compute(&self.buffer.as_slice()[self.initial_buffer_len..]);
         self.set(10, checksum);
     }
 }
 
+#[cfg(feature = "utils-bytes")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "utils-bytes")))]
+impl<'a, C> EncoderHandle<'a, bytes::BytesMut, C>
+where
+    C: Configure,
+{
+    /// Closes the current message writing operation, splits it off the buffer,
+    /// and freezes it into [`bytes::Bytes`], all without copying. The buffer
+    /// keeps any contents that came before the message.
+    ///
+    /// The resulting [`bytes::Bytes`] is cheaply cloneable, e.g. to send the
+    /// same message to multiple sessions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::tagvalue::{Config, Encoder};
+    /// use fefix::prelude::*;
+    /// use bytes::BytesMut;
+    ///
+    /// let mut buffer = BytesMut::new();
+    /// let mut encoder = Encoder::<Config>::default();
+    /// encoder.config_mut().set_separator(b'|');
+    /// let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"0");
+    /// msg.set(34, 12u32);
+    /// let message = msg.freeze();
+    ///
+    /// assert_eq!(&message[..], b"8=FIX.4.4|9=00000011|35=0|34=12|10=228|");
+    /// assert!(buffer.is_empty());
+    /// ```
+    pub fn freeze(mut self) -> bytes::Bytes {
+        self.write_body_length();
+        self.write_checksum();
+        self.buffer.split_off(self.initial_buffer_len).freeze()
+    }
+}
+
 fn to_digit(byte: u8) -> u8 {
     byte + b'0'
 }
@@ -216,6 +414,304 @@ where
     C: Configure,
 {
     type Key = u32;
+    type Group<'b>
+        = GroupEncoder<'b, B>
+    where
+        Self: 'b;
+
+    fn set_fv_with_key<'b, T>(&'b mut self, key: &Self::Key, value: T)
+    where
+        T: FixValue<'b>,
+    {
+        self.set_with(*key, value, T::SerializeSettings::default());
+    }
+
+    fn set_fv<'b, V, F>(&'b mut self, field: &F, value: V)
+    where
+        V: FixValue<'b>,
+        F: IsFieldDefinition,
+    {
+        self.set_fv_with_key(&(field.tag().get() as u32), value);
+    }
+
+    fn start_group<'b>(
+        &'b mut self,
+        num_in_group: &Self::Key,
+        delimiter: &Self::Key,
+    ) -> Self::Group<'b> {
+        EncoderHandle::start_group(self, *num_in_group, *delimiter)
+    }
+}
+
+/// A type returned by [`EncoderHandle::start_group`] to encode the entries of
+/// a FIX repeating group.
+///
+/// Every entry starts with the delimiter field of the group: setting it begins
+/// a new entry, and setting any other field before it is an error. The
+/// `NumInGroup` field is written automatically once the group is done (or
+/// dropped), so there's no need to know the number of entries in advance.
+/// Groups without any entries are omitted altogether.
+///
+/// If the [`Encoder`] was created with [`Encoder::with_dictionary`], member
+/// tags are also validated against the group definition.
+///
+/// # Examples
+///
+/// ```
+/// use fefix::tagvalue::{Config, Encoder};
+/// use fefix::prelude::*;
+///
+/// let mut buffer = Vec::new();
+/// let mut encoder = Encoder::<Config>::default();
+/// encoder.config_mut().set_separator(b'|');
+/// let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"D");
+/// msg.set(11, "ORD-1");
+/// let mut parties = msg.start_group(453, 448);
+/// parties.set(448, "BROKER");
+/// parties.set(452, 1u32);
+/// let mut sub_ids = parties.start_group(802, 523);
+/// sub_ids.set(523, "DESK-7");
+/// sub_ids.done().unwrap();
+/// parties.set(448, "CLIENT");
+/// parties.set(452, 3u32);
+/// parties.done().unwrap();
+/// msg.set(55, "AAPL");
+/// let (data, _) = msg.done();
+///
+/// assert!(data.starts_with(
+///     b"8=FIX.4.4|9=00000079|35=D|11=ORD-1|453=2|448=BROKER|452=1|802=1|523=DESK-7|448=CLIENT|452=3|55=AAPL|"
+/// ));
+/// ```
+#[derive(Debug)]
+pub struct GroupEncoder<'a, B>
+where
+    B: Buffer,
+{
+    buffer: &'a mut B,
+    separator: u8,
+    tables: Option<&'a LookupTables>,
+    num_in_group_tag: u32,
+    delimiter_tag: u32,
+    // Where the `NumInGroup` field will be inserted.
+    start_i: usize,
+    num_entries: usize,
+    error: Option<GroupError>,
+    parent_error: Option<&'a mut Option<GroupError>>,
+    is_finished: bool,
+}
+
+impl<'a, B> GroupEncoder<'a, B>
+where
+    B: Buffer,
+{
+    fn new(
+        buffer: &'a mut B,
+        separator: u8,
+        tables: Option<&'a LookupTables>,
+        num_in_group_tag: u32,
+        delimiter_tag: u32,
+        parent_error: Option<&'a mut Option<GroupError>>,
+    ) -> Self {
+        let start_i = buffer.len();
+        let mut group = Self {
+            buffer,
+            separator,
+            tables,
+            num_in_group_tag,
+            delimiter_tag,
+            start_i,
+            num_entries: 0,
+            error: None,
+            parent_error,
+            is_finished: false,
+        };
+        if let Some(tables) = tables {
+            match tables.group(num_in_group_tag) {
+                None => group.fail(GroupError::UnknownGroup(num_in_group_tag)),
+                Some(definition) if definition.delimiter_tag().get() as u32 != delimiter_tag => {
+                    group.fail(GroupError::DelimiterMismatch {
+                        expected: definition.delimiter_tag().get() as u32,
+                        found: delimiter_tag,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        group
+    }
+
+    /// Returns the number of entries written so far.
+    pub fn len(&self) -> usize {
+        self.num_entries
+    }
+
+    /// Returns `true` if no entries have been written so far.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Starts writing a repeating group nested in the current entry of
+    /// `self`. See [`EncoderHandle::start_group`].
+    pub fn start_group(
+        &mut self,
+        num_in_group_tag: u32,
+        delimiter_tag: u32,
+    ) -> GroupEncoder<'_, B> {
+        self.check_member(num_in_group_tag);
+        GroupEncoder::new(
+            &mut *self.buffer,
+            self.separator,
+            self.tables,
+            num_in_group_tag,
+            delimiter_tag,
+            Some(&mut self.error),
+        )
+    }
+
+    /// Writes a data field within the current entry of `self`. See
+    /// [`EncoderHandle::set_data`].
+    pub fn set_data(&mut self, tag: u32, data: &[u8]) -> Result<(), UnknownDataField> {
+        let len_tag = self
+            .tables
+            .and_then(|tables| tables.length_field_tag(tag))
+            .ok_or(UnknownDataField(tag))?;
+        self.set_data_with_len(len_tag.get() as u32, tag, data);
+        Ok(())
+    }
+
+    /// Writes a data field within the current entry of `self`. See
+    /// [`EncoderHandle::set_data_with_len`].
+    pub fn set_data_with_len(&mut self, len_tag: u32, tag: u32, data: &[u8]) {
+        self.set(len_tag, data.len());
+        self.set(tag, data);
+    }
+
+    /// Writes the `NumInGroup` field of `self` and returns the first error that
+    /// was encountered, if any, including errors of nested groups which were
+    /// dropped without calling [`GroupEncoder::done`].
+    pub fn done(mut self) -> Result<(), GroupError> {
+        self.finish();
+        match self.error.take() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    fn fail(&mut self, err: GroupError) {
+        self.error.get_or_insert(err);
+    }
+
+    fn check_member(&mut self, tag: u32) -> bool {
+        if self.num_entries == 0 {
+            self.fail(GroupError::MissingDelimiter {
+                group: self.num_in_group_tag,
+                tag,
+            });
+            return false;
+        }
+        let definition = self
+            .tables
+            .and_then(|tables| tables.group(self.num_in_group_tag));
+        match definition {
+            Some(definition) if !definition.member_tags().contains(tag) => {
+                self.fail(GroupError::NotAMember {
+                    group: self.num_in_group_tag,
+                    tag,
+                });
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.is_finished {
+            return;
+        }
+        self.is_finished = true;
+        if self.num_entries == 0 {
+            return;
+        }
+        let entries_len = self.buffer.len() - self.start_i;
+        write!(
+            BufferWriter(self.buffer),
+            "{}={}",
+            self.num_in_group_tag,
+            self.num_entries
+        )
+        .unwrap();
+        self.buffer.extend_from_slice(&[self.separator]);
+        // Move the `NumInGroup` field before all entries.
+        let field_len = self.buffer.len() - self.start_i - entries_len;
+        self.buffer.as_mut_slice()[self.start_i..].rotate_right(field_len);
+    }
+}
+
+impl<'a, B> Drop for GroupEncoder<'a, B>
+where
+    B: Buffer,
+{
+    fn drop(&mut self) {
+        self.finish();
+        if let (Some(err), Some(parent_error)) = (self.error.take(), self.parent_error.as_mut()) {
+            parent_error.get_or_insert(err);
+        }
+    }
+}
+
+impl<'a, B> SetField<u32> for GroupEncoder<'a, B>
+where
+    B: Buffer,
+{
+    fn set_with<'s, V>(&'s mut self, tag: u32, value: V, settings: V::SerializeSettings)
+    where
+        V: FixValue<'s>,
+    {
+        if tag == self.delimiter_tag {
+            self.num_entries += 1;
+        } else if !self.check_member(tag) {
+            return;
+        }
+        write!(BufferWriter(self.buffer), "{}=", tag).unwrap();
+        value.serialize_with(self.buffer, settings);
+        self.buffer.extend_from_slice(&[self.separator]);
+    }
+}
+
+impl<'a, B> SetField<TagU16> for GroupEncoder<'a, B>
+where
+    B: Buffer,
+{
+    fn set_with<'s, V>(&'s mut self, tag: TagU16, value: V, settings: V::SerializeSettings)
+    where
+        V: FixValue<'s>,
+    {
+        self.set_with(tag.get() as u32, value, settings)
+    }
+}
+
+impl<'a, B, F> SetField<&F> for GroupEncoder<'a, B>
+where
+    B: Buffer,
+    F: IsFieldDefinition,
+{
+    fn set_with<'s, V>(&'s mut self, field: &F, value: V, settings: V::SerializeSettings)
+    where
+        V: FixValue<'s>,
+    {
+        self.set_with(field.tag(), value, settings)
+    }
+}
+
+impl<'a, B> FvWrite<'a> for GroupEncoder<'a, B>
+where
+    B: Buffer,
+{
+    type Key = u32;
+    type Group<'b>
+        = GroupEncoder<'b, B>
+    where
+        Self: 'b;
 
     fn set_fv_with_key<'b, T>(&'b mut self, key: &Self::Key, value: T)
     where
@@ -231,4 +727,318 @@ where
     {
         self.set_fv_with_key(&(field.tag().get() as u32), value);
     }
+
+    fn start_group<'b>(
+        &'b mut self,
+        num_in_group: &Self::Key,
+        delimiter: &Self::Key,
+    ) -> Self::Group<'b> {
+        GroupEncoder::start_group(self, *num_in_group, *delimiter)
+    }
+}
+
+/// The type returned in the event of an error while encoding a FIX repeating
+/// group with [`GroupEncoder`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GroupError {
+    /// The tag is not the `NumInGroup` field of any repeating group in the
+    /// dictionary.
+    #[error("Tag {0} is not the `NumInGroup` field of a known repeating group.")]
+    UnknownGroup(u32),
+    /// The delimiter tag doesn't match the first field of the group definition.
+    #[error("Expected delimiter tag {expected}, found {found}.")]
+    DelimiterMismatch { expected: u32, found: u32 },
+    /// A field was written before the delimiter field of the first entry.
+    #[error("Tag {tag} of group {group} was set before the delimiter field.")]
+    MissingDelimiter { group: u32, tag: u32 },
+    /// The tag is not a member of the repeating group.
+    #[error("Tag {tag} is not a member of group {group}.")]
+    NotAMember { group: u32, tag: u32 },
+}
+
+/// The error type returned by [`EncoderHandle::set_data`] when the `Length`
+/// field of a data field is unknown, either because the tag is not a data
+/// field or because the [`Encoder`] was created without a [`Dictionary`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Tag {0} is not a data field with a known `Length` field.")]
+pub struct UnknownDataField(pub u32);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tagvalue::Decoder;
+    use crate::RandomFieldAccess;
+    use crate::RepeatingGroup;
+
+    fn encoder() -> Encoder {
+        let mut encoder = Encoder::<Config>::default();
+        encoder.config_mut().set_separator(b'|');
+        encoder
+    }
+
+    fn encoder_with_dictionary() -> Encoder {
+        let mut config = Config::default();
+        config.set_separator(b'|');
+        Encoder::with_dictionary(config, &Dictionary::fix44())
+    }
+
+    fn body(data: &[u8]) -> &str {
+        let data = std::str::from_utf8(data).unwrap();
+        let start = data.find("|35=").unwrap() + 1;
+        let end = data.rfind("10=").unwrap();
+        &data[start..end]
+    }
+
+    #[test]
+    fn num_in_group_is_written_before_entries() {
+        let mut encoder = encoder();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"W");
+        msg.set(55, "EUR/USD");
+        let mut entries = msg.start_group(268, 269);
+        for (entry_type, price) in [("0", "1.37215"), ("1", "1.37224"), ("2", "1.3722")] {
+            entries.set(269, entry_type);
+            entries.set(270, price);
+        }
+        assert_eq!(entries.len(), 3);
+        entries.done().unwrap();
+        msg.set(58, "text");
+        let (data, _) = msg.done();
+        assert_eq!(
+            body(data),
+            "35=W|55=EUR/USD|268=3|269=0|270=1.37215|269=1|270=1.37224|269=2|270=1.3722|58=text|"
+        );
+
+        let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+        decoder.config_mut().set_separator(b'|');
+        let message = decoder.decode(data).unwrap();
+        let group = message.group(268).unwrap();
+        assert_eq!(group.len(), 3);
+        assert_eq!(group.entry(2).fv::<&str>(270), Ok("1.3722"));
+        assert_eq!(message.fv::<&str>(55), Ok("EUR/USD"));
+    }
+
+    #[test]
+    fn nested_groups() {
+        let mut encoder = encoder_with_dictionary();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"D");
+        let mut parties = msg.start_group(453, 448);
+        parties.set(448, "A");
+        let mut sub_ids = parties.start_group(802, 523);
+        sub_ids.set(523, "A1");
+        sub_ids.set(803, 1u32);
+        sub_ids.set(523, "A2");
+        sub_ids.set(803, 2u32);
+        drop(sub_ids);
+        parties.set(448, "B");
+        parties.set(452, 3u32);
+        parties.done().unwrap();
+        let (data, _) = msg.done();
+        assert_eq!(
+            body(data),
+            "35=D|453=2|448=A|802=2|523=A1|803=1|523=A2|803=2|448=B|452=3|"
+        );
+    }
+
+    #[test]
+    fn empty_groups_are_omitted() {
+        let mut encoder = encoder();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"W");
+        assert_eq!(msg.start_group(268, 269).done(), Ok(()));
+        let (data, _) = msg.done();
+        assert_eq!(body(data), "35=W|");
+    }
+
+    #[test]
+    fn entries_must_start_with_delimiter() {
+        let mut encoder = encoder();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"W");
+        let mut entries = msg.start_group(268, 269);
+        entries.set(270, "1.0");
+        entries.set(269, "0");
+        assert_eq!(
+            entries.done(),
+            Err(GroupError::MissingDelimiter {
+                group: 268,
+                tag: 270
+            })
+        );
+        let (data, _) = msg.done();
+        assert_eq!(body(data), "35=W|268=1|269=0|");
+    }
+
+    #[test]
+    fn dictionary_validation() {
+        let mut encoder = encoder_with_dictionary();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"W");
+        let mut entries = msg.start_group(268, 269);
+        entries.set(269, "0");
+        entries.set(270, "1.0");
+        entries.set(11, "not a member");
+        assert_eq!(
+            entries.done(),
+            Err(GroupError::NotAMember {
+                group: 268,
+                tag: 11
+            })
+        );
+        assert_eq!(
+            msg.start_group(55, 269).done(),
+            Err(GroupError::UnknownGroup(55))
+        );
+        assert_eq!(
+            msg.start_group(268, 270).done(),
+            Err(GroupError::DelimiterMismatch {
+                expected: 269,
+                found: 270
+            })
+        );
+    }
+
+    #[test]
+    fn nested_errors_are_reported_to_parent() {
+        let mut encoder = encoder_with_dictionary();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"D");
+        let mut parties = msg.start_group(453, 448);
+        parties.set(448, "A");
+        parties.start_group(802, 523).set(803, 1u32);
+        assert_eq!(
+            parties.done(),
+            Err(GroupError::MissingDelimiter {
+                group: 802,
+                tag: 803
+            })
+        );
+    }
+
+    #[test]
+    fn canonical_body_length_round_trip() {
+        let mut config = Config::default();
+        config.set_zero_padded_body_length(false);
+        let mut encoder = Encoder::new(config);
+        let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+        for text_len in [0, 1, 4, 5, 94, 95, 100, 994, 995, 10_000] {
+            let text = "x".repeat(text_len);
+            let mut buffer = Vec::new();
+            let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"0");
+            if text_len > 0 {
+                msg.set(58, text.as_str());
+            }
+            let (data, _) = msg.done();
+            let body_length = if text_len > 0 { text_len + 9 } else { 5 };
+            let prefix = format!("8=FIX.4.4\x019={}\x0135=0\x01", body_length);
+            assert!(data.starts_with(prefix.as_bytes()));
+            let message = decoder.decode(data).unwrap();
+            if text_len > 0 {
+                assert_eq!(message.fv::<&str>(58), Ok(text.as_str()));
+            }
+        }
+    }
+
+    fn decoder() -> Decoder {
+        let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+        decoder.config_mut().set_separator(b'|');
+        decoder
+    }
+
+    #[test]
+    fn data_fields_with_separators_round_trip() {
+        let raw_data = b"|\x01raw|data\x01|";
+        let xml_data = b"<Logon Text=\"a|b\"/>";
+        let mut encoder = encoder_with_dictionary();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"A");
+        msg.set_data(213, xml_data).unwrap();
+        msg.set(98, 0u32);
+        msg.set(108, 30u32);
+        msg.set_data(96, raw_data).unwrap();
+        msg.set(383, 4096u32);
+        msg.set(58, "text");
+        let (data, _) = msg.done();
+        assert!(body(data).contains("|95=12|96=|\x01raw|data\x01||383=4096|"));
+
+        let mut decoder = decoder();
+        let message = decoder.decode(data).unwrap();
+        assert_eq!(message.fv::<usize>(212), Ok(xml_data.len()));
+        assert_eq!(message.fv_raw(213), Some(&xml_data[..]));
+        assert_eq!(message.fv::<usize>(95), Ok(raw_data.len()));
+        assert_eq!(message.fv_raw(96), Some(&raw_data[..]));
+        assert_eq!(message.fv::<u32>(383), Ok(4096));
+        assert_eq!(message.fv::<&str>(58), Ok("text"));
+    }
+
+    #[test]
+    fn data_fields_within_groups_round_trip() {
+        let mut encoder = encoder_with_dictionary();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"AB");
+        msg.set(11, "ORD-1");
+        let mut legs = msg.start_group(555, 600);
+        legs.set(600, "LEG-A");
+        legs.set_data(619, b"A|\x01").unwrap();
+        legs.set(600, "LEG-B");
+        legs.set_data(619, b"\x01|B").unwrap();
+        legs.done().unwrap();
+        let (data, _) = msg.done();
+
+        let mut decoder = decoder();
+        let message = decoder.decode(data).unwrap();
+        let legs = message.group(555).unwrap();
+        assert_eq!(legs.len(), 2);
+        assert_eq!(legs.entry(0).fv_raw(619), Some(&b"A|\x01"[..]));
+        assert_eq!(legs.entry(1).fv::<&str>(600), Ok("LEG-B"));
+        assert_eq!(legs.entry(1).fv_raw(619), Some(&b"\x01|B"[..]));
+    }
+
+    #[test]
+    fn data_fields_need_a_length_field() {
+        let mut encoder = encoder();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"A");
+        assert_eq!(msg.set_data(96, b"data"), Err(UnknownDataField(96)));
+        msg.set_data_with_len(95, 96, b"da|ta");
+        let (data, _) = msg.done();
+        assert_eq!(body(data), "35=A|95=5|96=da|ta|");
+
+        let mut encoder = encoder_with_dictionary();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"A");
+        assert_eq!(msg.set_data(58, b"text"), Err(UnknownDataField(58)));
+    }
+
+    #[test]
+    fn checksum_ignores_previous_buffer_contents() {
+        let mut encoder = encoder();
+        let mut buffer = b"previous contents".to_vec();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"0");
+        msg.set(34, 12u32);
+        let (data, offset) = msg.done();
+        assert_eq!(&data[offset..], b"8=FIX.4.4|9=00000011|35=0|34=12|10=228|");
+    }
+
+    #[cfg(feature = "utils-bytes")]
+    #[test]
+    fn frozen_messages_share_memory_with_decoded_values() {
+        let mut encoder = encoder();
+        let mut buffer = bytes::BytesMut::from(&b"previous contents"[..]);
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"D");
+        msg.set(11, "ORDER-1");
+        msg.set(55, "EUR/USD");
+        let frozen = msg.freeze();
+        assert_eq!(&buffer[..], b"previous contents");
+
+        let mut decoder = decoder();
+        let message = decoder.decode_owned(frozen.clone()).unwrap();
+        let symbol = message.fv_bytes(55).unwrap();
+        assert_eq!(&symbol[..], b"EUR/USD");
+        let frozen_range = frozen.as_ptr_range();
+        assert!(frozen_range.contains(&symbol.as_ptr()));
+        let tags: Vec<u16> = message.fields_bytes().map(|(tag, _)| tag.get()).collect();
+        assert_eq!(tags, [8, 35, 11, 55]);
+    }
 }
@@ -8,13 +8,13 @@ use crate::TagU16;
 /// a FIX message is a tree rather than an associative array. [`FieldLocator`]
 /// generates unique identifiers for tags both outside and within groups, which
 /// allows for random (i.e. non-sequential) reads on a FIX message.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct FieldLocator {
     pub tag: TagU16,
     pub context: FieldLocatorContext,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum FieldLocatorContext {
     TopLevel,
     WithinGroup {
@@ -0,0 +1,370 @@
+use super::{Config, Configure, DecodeError, RawDecoder};
+use crate::dict::{FlatGroup, IsFieldDefinition};
+use crate::{Dictionary, FixValue, GetConfig, RandomFieldAccess, RepeatingGroup, TagU16};
+use std::ops::Range;
+
+/// A fast FIX decoder that only parses the standard header of FIX messages and
+/// leaves the body untouched.
+///
+/// This is useful for routing and forwarding, where only a handful of header
+/// fields (e.g. `MsgType <35>`, `SenderCompID <49>`, `MsgSeqNum <34>`) are
+/// ever read. Unlike [`Decoder`](super::Decoder), body fields are never
+/// indexed: header fields are stored in order and looked up linearly, and the
+/// body is only available as raw bytes.
+///
+/// Parsing stops at the first field that doesn't belong to the standard
+/// header, as per [`FieldLocation::Header`](crate::dict::FieldLocation::Header).
+///
+/// # Examples
+///
+/// ```
+/// use fefix::tagvalue::{Config, HeaderDecoder};
+/// use fefix::prelude::*;
+///
+/// let mut decoder = HeaderDecoder::<Config>::new(Dictionary::fix44());
+/// decoder.config_mut().set_separator(b'|');
+/// let data = b"8=FIX.4.4|9=50|35=D|49=A|56=B|34=12|52=20100304-07:59:30|55=AAPL|10=206|";
+/// let header = decoder.decode(data).unwrap();
+///
+/// assert_eq!(header.fv::<&str>(35), Ok("D"));
+/// assert_eq!(header.fv::<u32>(34), Ok(12));
+/// assert_eq!(header.fv_raw(55), None);
+/// assert_eq!(header.body(), b"55=AAPL|");
+/// ```
+#[derive(Debug, Clone)]
+pub struct HeaderDecoder<C = Config> {
+    raw_decoder: RawDecoder<C>,
+    dict: Dictionary,
+    state: HeaderState,
+}
+
+impl<C> HeaderDecoder<C>
+where
+    C: Configure,
+{
+    /// Creates a new [`HeaderDecoder`]. `dict` is used to tell which fields
+    /// belong to the standard header.
+    pub fn new(dict: Dictionary) -> Self {
+        Self {
+            raw_decoder: RawDecoder::default(),
+            dict,
+            state: HeaderState::default(),
+        }
+    }
+
+    /// Decodes the standard header of `data` and returns a [`HeaderFrame`]
+    /// with access to header fields and to the raw contents of the body.
+    ///
+    /// `BodyLength <9>` and (if enabled) `CheckSum <10>` are verified just like
+    /// [`RawDecoder::decode`] does.
+    pub fn decode<'a>(&'a mut self, data: &'a [u8]) -> Result<HeaderFrame<'a>, DecodeError> {
+        let frame = self.raw_decoder.decode(data)?;
+        let separator = self.config().separator();
+        let payload = frame.payload.clone();
+        let tables = self.dict.lookup_tables();
+        let state = &mut self.state;
+        state.clear();
+        state
+            .fields
+            .push((TagU16::new(8).unwrap(), frame.begin_string.clone()));
+        state.fields.push((
+            TagU16::new(9).unwrap(),
+            frame.begin_string.end + 3..payload.start - 1,
+        ));
+
+        let mut i = payload.start;
+        let mut data_field = None;
+        let mut current_group: Option<(&FlatGroup, Option<TagU16>)> = None;
+        while i < payload.end {
+            let i_eq = memchr::memchr(b'=', &data[i..payload.end])
+                .map(|pos| pos + i)
+                .ok_or(DecodeError::Invalid)?;
+            let tag = parse_tag(&data[i..i_eq]).ok_or(DecodeError::Invalid)?;
+            if !tables.header_tags().contains(tag.get().into()) {
+                break;
+            }
+            let value_start = i_eq + 1;
+            // A `Length` field only applies to its data field right after it.
+            let value_len = match data_field.take() {
+                Some((data_tag, len)) if data_tag == tag => {
+                    if value_start + len >= payload.end {
+                        return Err(DecodeError::Invalid);
+                    }
+                    len
+                }
+                _ => memchr::memchr(separator, &data[value_start..payload.end])
+                    .ok_or(DecodeError::Invalid)?,
+            };
+            let value = value_start..value_start + value_len;
+            if let Some(data_tag) = tables.data_field_tag(tag.get().into()) {
+                let len =
+                    usize::deserialize(&data[value.clone()]).map_err(|_| DecodeError::Invalid)?;
+                data_field = Some((data_tag, len));
+            }
+
+            let field_i = state.fields.len();
+            state.fields.push((tag, value.clone()));
+            if let Some((group, delimiter)) = current_group.as_mut() {
+                if group.member_tags().contains(tag.get().into()) {
+                    let delimiter = *delimiter.get_or_insert(tag);
+                    if delimiter == tag {
+                        state.entries.push(field_i..field_i + 1);
+                        state.groups.last_mut().unwrap().entries.end += 1;
+                    } else {
+                        state.entries.last_mut().unwrap().end = field_i + 1;
+                    }
+                } else {
+                    current_group = None;
+                }
+            }
+            if let Some(group) = tables.group(tag.get().into()) {
+                current_group = Some((group, None));
+                state.groups.push(GroupData {
+                    len_field_i: field_i,
+                    entries: state.entries.len()..state.entries.len(),
+                });
+            }
+            i = value.end + 1;
+        }
+        state.body = i.min(payload.end)..payload.end;
+
+        Ok(HeaderFrame {
+            data,
+            state: &self.state,
+            fields: 0..self.state.fields.len(),
+            is_group_entry: false,
+        })
+    }
+}
+
+impl<C> GetConfig for HeaderDecoder<C> {
+    type Config = C;
+
+    fn config(&self) -> &Self::Config {
+        self.raw_decoder.config()
+    }
+
+    fn config_mut(&mut self) -> &mut Self::Config {
+        self.raw_decoder.config_mut()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct HeaderState {
+    fields: Vec<(TagU16, Range<usize>)>,
+    groups: Vec<GroupData>,
+    // Each entry is a range of indices into `fields`.
+    entries: Vec<Range<usize>>,
+    body: Range<usize>,
+}
+
+impl HeaderState {
+    fn clear(&mut self) {
+        self.fields.clear();
+        self.groups.clear();
+        self.entries.clear();
+        self.body = 0..0;
+    }
+
+    fn is_group_member(&self, field_i: usize) -> bool {
+        self.entries.iter().any(|entry| entry.contains(&field_i))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GroupData {
+    len_field_i: usize,
+    // A range of indices into `HeaderState::entries`.
+    entries: Range<usize>,
+}
+
+/// The standard header of a FIX message, as returned by [`HeaderDecoder`].
+#[derive(Debug, Clone)]
+pub struct HeaderFrame<'a> {
+    data: &'a [u8],
+    state: &'a HeaderState,
+    fields: Range<usize>,
+    // Top-level lookups must skip the fields of repeating groups.
+    is_group_entry: bool,
+}
+
+impl<'a> HeaderFrame<'a> {
+    /// Returns the raw contents of the whole FIX message.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the raw contents of the message body, i.e. everything in
+    /// between the standard header and `CheckSum <10>`.
+    pub fn body(&self) -> &'a [u8] {
+        &self.data[self.state.body.clone()]
+    }
+
+    /// Returns an [`Iterator`] over all header fields in `self`, in
+    /// sequential order, including `BeginString <8>` and `BodyLength <9>`.
+    pub fn fields(&self) -> impl Iterator<Item = (TagU16, &'a [u8])> + '_ {
+        let data = self.data;
+        self.state.fields[self.fields.clone()]
+            .iter()
+            .map(move |(tag, range)| (*tag, &data[range.clone()]))
+    }
+}
+
+impl<'a> RandomFieldAccess<u32> for HeaderFrame<'a> {
+    type Group = HeaderGroup<'a>;
+
+    fn group_opt(&self, tag: u32) -> Option<Result<Self::Group, <usize as FixValue>::Error>> {
+        let group = self.state.groups.iter().find(|group| {
+            self.fields.contains(&group.len_field_i)
+                && u32::from(self.state.fields[group.len_field_i].0.get()) == tag
+        })?;
+        let num_in_group = &self.data[self.state.fields[group.len_field_i].1.clone()];
+        Some(usize::deserialize(num_in_group).map(|len| HeaderGroup {
+            frame: self.clone(),
+            entries: group.entries.start..group.entries.start + len.min(group.entries.len()),
+        }))
+    }
+
+    fn fv_raw(&self, tag: u32) -> Option<&[u8]> {
+        self.fields
+            .clone()
+            .filter(|i| self.is_group_entry || !self.state.is_group_member(*i))
+            .map(|i| &self.state.fields[i])
+            .find(|(t, _)| u32::from(t.get()) == tag)
+            .map(|(_, range)| &self.data[range.clone()])
+    }
+}
+
+impl<'a, F> RandomFieldAccess<&F> for HeaderFrame<'a>
+where
+    F: IsFieldDefinition,
+{
+    type Group = HeaderGroup<'a>;
+
+    fn group_opt(&self, field: &F) -> Option<Result<Self::Group, <usize as FixValue<'a>>::Error>> {
+        self.group_opt(u32::from(field.tag().get()))
+    }
+
+    fn fv_raw(&self, field: &F) -> Option<&[u8]> {
+        self.fv_raw(u32::from(field.tag().get()))
+    }
+}
+
+/// A repeating group within a [`HeaderFrame`], e.g. `NoHops <627>`.
+#[derive(Debug, Clone)]
+pub struct HeaderGroup<'a> {
+    frame: HeaderFrame<'a>,
+    entries: Range<usize>,
+}
+
+impl<'a> RepeatingGroup for HeaderGroup<'a> {
+    type Entry = HeaderFrame<'a>;
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn entry_opt(&self, i: usize) -> Option<Self::Entry> {
+        if i < self.len() {
+            Some(HeaderFrame {
+                fields: self.frame.state.entries[self.entries.start + i].clone(),
+                is_group_entry: true,
+                ..self.frame.clone()
+            })
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_tag(digits: &[u8]) -> Option<TagU16> {
+    if digits.is_empty() || digits.len() > 5 || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let tag = digits
+        .iter()
+        .fold(0u32, |tag, digit| tag * 10 + u32::from(digit - b'0'));
+    TagU16::new(u16::try_from(tag).ok()?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decoder() -> HeaderDecoder {
+        let mut decoder = HeaderDecoder::<Config>::new(Dictionary::fix44());
+        decoder.config_mut().set_separator(b'|');
+        decoder
+    }
+
+    #[test]
+    fn header_fields_and_body() {
+        let mut decoder = decoder();
+        let data = b"8=FIX.4.4|9=50|35=D|49=A|56=B|34=12|52=20100304-07:59:30|55=AAPL|10=000|";
+        let header = decoder.decode(data).unwrap();
+        assert_eq!(header.fv_raw(8), Some(&b"FIX.4.4"[..]));
+        assert_eq!(header.fv::<usize>(9), Ok(50));
+        assert_eq!(header.fv::<&str>(49), Ok("A"));
+        assert_eq!(header.fv::<&str>(56), Ok("B"));
+        assert_eq!(header.fv_raw(55), None);
+        assert_eq!(header.fields().count(), 7);
+        assert_eq!(header.body(), b"55=AAPL|");
+    }
+
+    #[test]
+    fn message_without_body() {
+        let mut decoder = decoder();
+        let data = b"8=FIX.4.4|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=185|";
+        let header = decoder.decode(data).unwrap();
+        assert_eq!(header.fv::<u32>(34), Ok(12));
+        assert_eq!(header.body(), b"");
+    }
+
+    #[test]
+    fn header_groups() {
+        let mut decoder = decoder();
+        let data =
+            b"8=FIX.4.4|9=58|35=0|49=A|56=B|627=2|628=X|630=1|628=Y|629=20100304|34=12|10=000|";
+        let header = decoder.decode(data).unwrap();
+        let hops = header.group(627).unwrap();
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops.entry(0).fv::<&str>(628), Ok("X"));
+        assert_eq!(hops.entry(0).fv::<&str>(630), Ok("1"));
+        assert_eq!(hops.entry(0).fv_raw(629), None);
+        assert_eq!(hops.entry(1).fv::<&str>(628), Ok("Y"));
+        assert_eq!(hops.entry(1).fv_raw(34), None);
+        assert_eq!(header.fv_raw(628), None);
+        assert_eq!(header.fv_raw(630), None);
+        assert_eq!(header.fv::<u32>(34), Ok(12));
+        assert_eq!(header.body(), b"");
+    }
+
+    #[test]
+    fn data_fields_may_contain_separators() {
+        let mut decoder = decoder();
+        let data = b"8=FIX.4.4|9=35|35=0|49=A|90=5|91=a|b|c|56=B|58=hi|10=000|";
+        let header = decoder.decode(data).unwrap();
+        assert_eq!(header.fv_raw(91), Some(&b"a|b|c"[..]));
+        assert_eq!(header.fv::<&str>(56), Ok("B"));
+        assert_eq!(header.body(), b"58=hi|");
+    }
+
+    #[test]
+    fn lengths_only_apply_to_their_data_fields() {
+        let mut decoder = decoder();
+        // `SecureDataLen <90>` is followed by `SenderCompID <49>` rather than
+        // `SecureData <91>`.
+        let data = b"8=FIX.4.4|9=26|35=0|90=5|49=A|56=B|58=hi|10=000|";
+        let header = decoder.decode(data).unwrap();
+        assert_eq!(header.fv::<&str>(49), Ok("A"));
+        assert_eq!(header.fv::<&str>(56), Ok("B"));
+        assert_eq!(header.body(), b"58=hi|");
+    }
+
+    #[test]
+    fn garbled_header_is_invalid() {
+        let mut decoder = decoder();
+        let data = b"8=FIX.4.4|9=16|35=0|49=A|5x6=B|10=000|";
+        assert!(matches!(decoder.decode(data), Err(DecodeError::Invalid)));
+    }
+}
@@ -0,0 +1,328 @@
+use super::{Configure, EncoderHandle};
+use crate::dict::{LayoutItem, LayoutItemKind};
+use crate::{Buffer, Dictionary, FixValue, SetField};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use std::io::Write;
+
+const MSG_SEQ_NUM: u32 = 34;
+const SENDING_TIME: u32 = 52;
+
+/// The precision of `UTCTimestamp` field values, e.g. `SendingTime <52>`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum TimestampPrecision {
+    /// `YYYYMMDD-HH:MM:SS`.
+    Seconds,
+    /// `YYYYMMDD-HH:MM:SS.sss`.
+    #[default]
+    Milliseconds,
+    /// `YYYYMMDD-HH:MM:SS.ssssss`.
+    Microseconds,
+    /// `YYYYMMDD-HH:MM:SS.sssssssss`.
+    Nanoseconds,
+}
+
+impl TimestampPrecision {
+    fn num_fractional_digits(&self) -> u32 {
+        match self {
+            Self::Seconds => 0,
+            Self::Milliseconds => 3,
+            Self::Microseconds => 6,
+            Self::Nanoseconds => 9,
+        }
+    }
+
+    // `chrono`'s formatting utilities allocate, so we roll our own.
+    fn write(&self, mut w: impl Write, timestamp: DateTime<Utc>) -> std::io::Result<()> {
+        write!(
+            w,
+            "{:04}{:02}{:02}-{:02}:{:02}:{:02}",
+            timestamp.year(),
+            timestamp.month(),
+            timestamp.day(),
+            timestamp.hour(),
+            timestamp.minute(),
+            timestamp.second(),
+        )?;
+        let digits = self.num_fractional_digits();
+        if digits > 0 {
+            // Leap seconds are represented with nanoseconds above 10^9.
+            let nanos = timestamp.nanosecond() % 1_000_000_000;
+            let fraction = nanos / 10u32.pow(9 - digits);
+            write!(w, ".{:0width$}", fraction, width = digits as usize)?;
+        }
+        Ok(())
+    }
+}
+
+/// The standard header fields that are common to all messages of a FIX
+/// session, used by [`Encoder::start_message_with_header`](super::Encoder::start_message_with_header).
+///
+/// [`HeaderTemplate`] holds `BeginString <8>`, the static header fields of a
+/// session (CompIDs, SubIDs, LocationIDs, etc.), and the precision of
+/// `SendingTime <52>`. `MsgSeqNum <34>` and `SendingTime <52>` are filled in
+/// for every message. All fields are written in the order given by the
+/// `StandardHeader` of the [`Dictionary`], so that the body can be appended
+/// right after.
+///
+/// Other header fields can be added with [`SetField`].
+///
+/// # Examples
+///
+/// ```
+/// use fefix::tagvalue::{Config, Encoder, HeaderTemplate};
+/// use fefix::prelude::*;
+///
+/// let mut header = HeaderTemplate::new(b"FIX.4.4", &Dictionary::fix44());
+/// header.set_sender_comp_id(b"SENDER");
+/// header.set_target_comp_id(b"TARGET");
+/// header.set_sender_sub_id(b"DESK");
+///
+/// let mut buffer = Vec::new();
+/// let mut encoder = Encoder::<Config>::default();
+/// encoder.config_mut().set_separator(b'|');
+/// let mut msg = encoder.start_message_with_header(&header, &mut buffer, b"0", 1);
+/// msg.set(112, "TEST");
+/// let (data, _) = msg.done();
+///
+/// let data = std::str::from_utf8(data).unwrap();
+/// assert!(data.starts_with("8=FIX.4.4|9=00000072|35=0|49=SENDER|56=TARGET|34=1|50=DESK|52="));
+/// assert!(data.contains("|112=TEST|10="));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HeaderTemplate {
+    begin_string: Vec<u8>,
+    // All standard header tags, in dictionary order.
+    header_tags: Vec<u32>,
+    fields: Vec<(u32, HeaderValue)>,
+    sending_time_precision: TimestampPrecision,
+}
+
+#[derive(Debug, Clone)]
+enum HeaderValue {
+    Static(Vec<u8>),
+    MsgSeqNum,
+    SendingTime,
+}
+
+impl HeaderTemplate {
+    /// Creates a new [`HeaderTemplate`] with the given `begin_string` and the
+    /// field order of the `StandardHeader` of `dict`.
+    pub fn new(begin_string: &[u8], dict: &Dictionary) -> Self {
+        let mut header_tags = Vec::new();
+        if let Some(std_header) = dict.component_by_name("StandardHeader") {
+            collect_tags(std_header.items(), &mut header_tags);
+        }
+        let mut header = Self {
+            begin_string: begin_string.to_vec(),
+            header_tags,
+            fields: Vec::new(),
+            sending_time_precision: TimestampPrecision::default(),
+        };
+        header.insert(MSG_SEQ_NUM, HeaderValue::MsgSeqNum);
+        header.insert(SENDING_TIME, HeaderValue::SendingTime);
+        header
+    }
+
+    /// Returns the `BeginString <8>` of `self`.
+    pub fn begin_string(&self) -> &[u8] {
+        &self.begin_string[..]
+    }
+
+    /// Sets `SenderCompID <49>`.
+    pub fn set_sender_comp_id(&mut self, sender_comp_id: &[u8]) {
+        self.set(49, sender_comp_id);
+    }
+
+    /// Sets `TargetCompID <56>`.
+    pub fn set_target_comp_id(&mut self, target_comp_id: &[u8]) {
+        self.set(56, target_comp_id);
+    }
+
+    /// Sets `SenderSubID <50>`.
+    pub fn set_sender_sub_id(&mut self, sender_sub_id: &[u8]) {
+        self.set(50, sender_sub_id);
+    }
+
+    /// Sets `TargetSubID <57>`.
+    pub fn set_target_sub_id(&mut self, target_sub_id: &[u8]) {
+        self.set(57, target_sub_id);
+    }
+
+    /// Sets `SenderLocationID <142>`.
+    pub fn set_sender_location_id(&mut self, sender_location_id: &[u8]) {
+        self.set(142, sender_location_id);
+    }
+
+    /// Sets `TargetLocationID <143>`.
+    pub fn set_target_location_id(&mut self, target_location_id: &[u8]) {
+        self.set(143, target_location_id);
+    }
+
+    /// Returns the precision of `SendingTime <52>`. Milliseconds by default.
+    pub fn sending_time_precision(&self) -> TimestampPrecision {
+        self.sending_time_precision
+    }
+
+    /// Sets the precision of `SendingTime <52>`.
+    pub fn set_sending_time_precision(&mut self, precision: TimestampPrecision) {
+        self.sending_time_precision = precision;
+    }
+
+    /// Removes the header field `tag` from `self`. `MsgSeqNum <34>` and
+    /// `SendingTime <52>` can't be removed.
+    pub fn remove(&mut self, tag: u32) {
+        self.fields.retain(|(t, value)| {
+            *t != tag || matches!(value, HeaderValue::MsgSeqNum | HeaderValue::SendingTime)
+        });
+    }
+
+    pub(crate) fn write_fields<B, C>(
+        &self,
+        msg: &mut EncoderHandle<B, C>,
+        msg_seq_num: u64,
+        sending_time: DateTime<Utc>,
+    ) where
+        B: Buffer,
+        C: Configure,
+    {
+        for (tag, value) in self.fields.iter() {
+            match value {
+                HeaderValue::Static(bytes) => msg.set(*tag, &bytes[..]),
+                HeaderValue::MsgSeqNum => msg.set(*tag, msg_seq_num),
+                HeaderValue::SendingTime => {
+                    let mut timestamp = [0u8; 32];
+                    let len = {
+                        let mut cursor = &mut timestamp[..];
+                        self.sending_time_precision
+                            .write(&mut cursor, sending_time)
+                            .unwrap();
+                        32 - cursor.len()
+                    };
+                    msg.set(*tag, &timestamp[..len]);
+                }
+            }
+        }
+    }
+
+    fn position(&self, tag: u32) -> usize {
+        self.header_tags
+            .iter()
+            .position(|t| *t == tag)
+            .unwrap_or(usize::MAX)
+    }
+
+    fn insert(&mut self, tag: u32, value: HeaderValue) {
+        if let Some(field) = self.fields.iter_mut().find(|(t, _)| *t == tag) {
+            field.1 = value;
+            return;
+        }
+        let position = self.position(tag);
+        let i = self
+            .fields
+            .iter()
+            .position(|(t, _)| self.position(*t) > position)
+            .unwrap_or(self.fields.len());
+        self.fields.insert(i, (tag, value));
+    }
+}
+
+impl SetField<u32> for HeaderTemplate {
+    fn set_with<'a, V>(&'a mut self, tag: u32, value: V, settings: V::SerializeSettings)
+    where
+        V: FixValue<'a>,
+    {
+        // `MsgSeqNum <34>` and `SendingTime <52>` are always dynamic.
+        if tag == MSG_SEQ_NUM || tag == SENDING_TIME {
+            return;
+        }
+        let mut bytes = Vec::new();
+        value.serialize_with(&mut bytes, settings);
+        self.insert(tag, HeaderValue::Static(bytes));
+    }
+}
+
+fn collect_tags<'a>(items: impl Iterator<Item = LayoutItem<'a>>, tags: &mut Vec<u32>) {
+    for item in items {
+        match item.kind() {
+            LayoutItemKind::Component(component) => collect_tags(component.items(), tags),
+            LayoutItemKind::Group(len_field, _) => tags.push(len_field.tag().get() as u32),
+            LayoutItemKind::Field(field) => tags.push(field.tag().get() as u32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tagvalue::{Config, Encoder};
+    use crate::GetConfig;
+
+    fn header() -> HeaderTemplate {
+        let mut header = HeaderTemplate::new(b"FIX.4.4", &Dictionary::fix44());
+        header.set_target_location_id(b"TL");
+        header.set_target_sub_id(b"TS");
+        header.set_target_comp_id(b"T");
+        header.set_sender_location_id(b"SL");
+        header.set_sender_sub_id(b"SS");
+        header.set_sender_comp_id(b"S");
+        header
+    }
+
+    fn encode(header: &HeaderTemplate) -> String {
+        let mut encoder = Encoder::<Config>::default();
+        encoder.config_mut().set_separator(b'|');
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(header.begin_string(), &mut buffer, b"0");
+        let sending_time = DateTime::parse_from_rfc3339("2022-06-01T09:30:00.123456789Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        header.write_fields(&mut msg, 42, sending_time);
+        msg.set(112, "X");
+        let data = std::str::from_utf8(msg.done().0).unwrap();
+        data[data.find("|35=").unwrap() + 1..data.rfind("10=").unwrap()].to_string()
+    }
+
+    #[test]
+    fn fields_are_in_dictionary_order() {
+        assert_eq!(
+            encode(&header()),
+            "35=0|49=S|56=T|34=42|50=SS|142=SL|57=TS|143=TL|52=20220601-09:30:00.123|112=X|"
+        );
+    }
+
+    #[test]
+    fn sending_time_precision() {
+        let mut header = HeaderTemplate::new(b"FIX.4.4", &Dictionary::fix44());
+        for (precision, sending_time) in [
+            (TimestampPrecision::Seconds, "20220601-09:30:00"),
+            (TimestampPrecision::Milliseconds, "20220601-09:30:00.123"),
+            (TimestampPrecision::Microseconds, "20220601-09:30:00.123456"),
+            (
+                TimestampPrecision::Nanoseconds,
+                "20220601-09:30:00.123456789",
+            ),
+        ] {
+            header.set_sending_time_precision(precision);
+            assert_eq!(
+                encode(&header),
+                format!("35=0|34=42|52={}|112=X|", sending_time)
+            );
+        }
+    }
+
+    #[test]
+    fn fields_can_be_replaced_and_removed() {
+        let mut header = header();
+        header.set_sender_comp_id(b"S2");
+        header.set(SENDING_TIME, "ignored");
+        header.set(369, 41u64);
+        header.set(9999, "custom");
+        header.remove(143);
+        header.remove(142);
+        header.remove(MSG_SEQ_NUM);
+        assert_eq!(
+            encode(&header),
+            "35=0|49=S2|56=T|34=42|50=SS|57=TS|52=20220601-09:30:00.123|369=41|9999=custom|112=X|"
+        );
+    }
+}
@@ -31,6 +31,25 @@
 //! - You want to delegate FIX message framing logic.
 //! - You need pre-build field parsing logic and basic features.
 //!
+//! ## Use a [`HeaderDecoder`] when:
+//!
+//! - You operate on byte slices.
+//! - You only care about the standard header, e.g. for routing, and want to
+//! forward the message body untouched.
+//!
+//! ## Use a [`DecoderReader`] or a [`DecoderAsyncReader`] when:
+//!
+//! - You read messages from a [`std::io::Read`] or [`futures::AsyncRead`]
+//! source, e.g. files or sockets.
+//! - You want framing, buffering, and decoding all taken care of.
+//!
+//! ## Use a [`TokioDecoder`] when:
+//!
+//! - You use [`tokio_util::codec::Framed`] and friends (requires the
+//! `utils-tokio` feature).
+//! - You want decoded messages that don't borrow from the decoder, i.e.
+//! [`OwnedMessage`]. [`TokioEncoder`] is its counterpart for writing.
+//!
 //! ## Summary
 //!
 //! |**Decoder type**      |Operates on              |Produces    |
@@ -39,6 +58,10 @@
 //! |[`RawDecoderBuffered`]|byte streams             |[`RawFrame`]|
 //! |[`Decoder`]           |`&[u8]`                  |[`Message`] |
 //! |[`DecoderBuffered`]   |data streams             |[`Message`] |
+//! |[`HeaderDecoder`]     |`&[u8]`                  |[`HeaderFrame`]|
+//! |[`DecoderReader`]     |[`std::io::Read`]        |[`Message`] |
+//! |[`DecoderAsyncReader`]|[`futures::AsyncRead`]   |[`Message`] |
+//! |[`TokioDecoder`]      |[`bytes::BytesMut`]      |[`OwnedMessage`]|
 
 use crate::dict::IsFieldDefinition;
 use crate::FixValue;
@@ -48,19 +71,33 @@ mod config;
 mod decoder;
 mod encoder;
 mod field_locator;
+mod header_decoder;
+mod header_template;
+mod pretty;
 mod raw_decoder;
+mod reader;
+mod template;
 mod utils;
+mod validator;
 
 pub use config::{Config, Configure};
+#[cfg(feature = "utils-bytes")]
+pub use decoder::OwnedMessage;
 pub use decoder::{CowMessage, Decoder, DecoderBuffered, Fields, Message, MessageGroup};
-pub use encoder::{Encoder, EncoderHandle};
+pub use encoder::{Encoder, EncoderHandle, GroupEncoder, GroupError, UnknownDataField};
 pub use field_locator::{FieldLocator, FieldLocatorContext};
+pub use header_decoder::{HeaderDecoder, HeaderFrame, HeaderGroup};
+pub use header_template::{HeaderTemplate, TimestampPrecision};
+pub use pretty::{PrettyError, PrettyPrinter};
 pub use raw_decoder::{RawDecoder, RawDecoderBuffered, RawFrame};
+pub use reader::{DecoderAsyncReader, DecoderReader};
+pub use template::{MessageTemplate, Slot, SlotPadding, TemplateBuilder, TemplateError};
+pub use validator::{ValidationError, Validator};
 
 #[cfg(feature = "utils-tokio")]
 mod tokio_decoder;
 #[cfg(feature = "utils-tokio")]
-pub use tokio_decoder::TokioDecoder;
+pub use tokio_decoder::{TokioDecoder, TokioEncoder, TokioRawDecoder};
 
 /// The type returned in the event of an error during message decoding.
 #[derive(Debug, thiserror::Error)]
@@ -74,6 +111,10 @@ pub enum DecodeError {
     /// Invalid `CheckSum <10>` FIX field value.
     #[error("Invalid `CheckSum <10>` FIX field value.")]
     CheckSum,
+    /// The message didn't pass the [`Validator`] of the decoder (see
+    /// [`Decoder::set_validator`]).
+    #[error("Invalid FIX message: {0}")]
+    Validation(#[from] ValidationError),
     /// I/O error.
     #[error("I/O error: {0}")]
     IO(#[from] std::io::Error),
@@ -81,6 +122,10 @@ pub enum DecodeError {
 
 pub trait FvWrite<'a> {
     type Key;
+    /// The type returned by [`FvWrite::start_group`].
+    type Group<'b>: FvWrite<'b, Key = Self::Key>
+    where
+        Self: 'b;
 
     fn set_fv_with_key<'b, T>(&'b mut self, key: &Self::Key, value: T)
     where
@@ -90,6 +135,14 @@ pub trait FvWrite<'a> {
     where
         V: FixValue<'b>,
         F: IsFieldDefinition;
+
+    /// Starts writing a repeating group with the `NumInGroup` field
+    /// `num_in_group`, with `delimiter` as the first field of every entry.
+    fn start_group<'b>(
+        &'b mut self,
+        num_in_group: &Self::Key,
+        delimiter: &Self::Key,
+    ) -> Self::Group<'b>;
 }
 
 pub trait RandomFieldAccess {}
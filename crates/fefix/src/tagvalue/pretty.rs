@@ -0,0 +1,396 @@
+use super::{Configure, Encoder, Message};
+use crate::dict::FlatGroup;
+use crate::{Dictionary, SetField, TagU16};
+use std::fmt::{self, Write};
+
+const INDENT: &str = "  ";
+
+/// Renders FIX messages in a human-readable, line-oriented format, and parses
+/// them back.
+///
+/// Every field takes up one line, with its name, tag, value, and (for
+/// enumerated values) the description of the value according to the
+/// [`Dictionary`]. Repeating group entries are indented below their
+/// `NumInGroup` field. E.g.:
+///
+/// ```text
+/// BeginString <8> = FIX.4.4
+/// MsgType <35> = D (ORDER_SINGLE)
+/// NoPartyIDs <453> = 2
+///   PartyID <448> = BROKER
+///   PartyRole <452> = 1 (EXECUTING_FIRM)
+///   PartyID <448> = CLIENT
+///   PartyRole <452> = 3 (CLIENT_ID)
+/// Side <54> = 1 (BUY)
+/// ```
+///
+/// Fields which are not in the [`Dictionary`] have no name, e.g.
+/// `<9999> = foo`. Backslashes and non-printable bytes within values (e.g. the
+/// separator in `RawData <96>`) are escaped as `\\` and `\xHH` respectively.
+///
+/// [`PrettyPrinter::parse`] is the inverse of [`PrettyPrinter::format`]:
+/// indentation and descriptions are ignored, and `BodyLength <9>` and
+/// `CheckSum <10>` are computed anew, which makes the human-readable format
+/// well suited for hand-written test fixtures.
+///
+/// # Examples
+///
+/// ```
+/// use fefix::tagvalue::{Config, Configure, Decoder, PrettyPrinter};
+/// use fefix::prelude::*;
+///
+/// const DATA: &[u8] = b"8=FIX.4.4|9=28|35=D|11=ORD-1|54=1|9999=foo|10=239|";
+///
+/// let mut config = Config::default();
+/// config.set_separator(b'|');
+/// config.set_zero_padded_body_length(false);
+/// let mut decoder = Decoder::new(Dictionary::fix44());
+/// *decoder.config_mut() = config;
+/// let message = decoder.decode(DATA).unwrap();
+///
+/// let printer = PrettyPrinter::new(Dictionary::fix44());
+/// let text = printer.format(&message);
+/// assert_eq!(
+///     text,
+///     "BeginString <8> = FIX.4.4\n\
+///      MsgType <35> = D (ORDER_SINGLE)\n\
+///      ClOrdID <11> = ORD-1\n\
+///      Side <54> = 1 (BUY)\n\
+///      <9999> = foo\n"
+/// );
+/// assert_eq!(printer.parse(&text, config).unwrap(), DATA);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrettyPrinter {
+    dict: Dictionary,
+}
+
+struct GroupState<'a> {
+    definition: &'a FlatGroup,
+    entries_left: usize,
+}
+
+impl PrettyPrinter {
+    /// Creates a new [`PrettyPrinter`] which takes field names, enum value
+    /// descriptions, and repeating groups from `dict`.
+    pub fn new(dict: Dictionary) -> Self {
+        Self { dict }
+    }
+
+    /// Renders `message` in the human-readable format.
+    pub fn format<T>(&self, message: &Message<T>) -> String {
+        let mut s = String::new();
+        self.write_fields(&mut s, message.fields()).unwrap();
+        s
+    }
+
+    /// Renders a sequence of fields (e.g. [`Message::fields`]) to `w` in the
+    /// human-readable format.
+    pub fn write_fields<'a, W, I>(&self, w: &mut W, fields: I) -> fmt::Result
+    where
+        W: Write,
+        I: IntoIterator<Item = (TagU16, &'a [u8])>,
+    {
+        let mut groups: Vec<GroupState> = Vec::new();
+        for (tag, value) in fields {
+            let tag = tag.get() as u32;
+            // Leave all groups which `tag` doesn't belong to.
+            while let Some(group) = groups.last() {
+                let is_new_entry = tag == group.definition.delimiter_tag().get() as u32;
+                if is_new_entry && group.entries_left > 0 {
+                    groups.last_mut().unwrap().entries_left -= 1;
+                    break;
+                } else if !is_new_entry && group.definition.member_tags().contains(tag) {
+                    break;
+                }
+                groups.pop();
+            }
+            for _ in 0..groups.len() {
+                w.write_str(INDENT)?;
+            }
+            self.write_field(w, tag, value)?;
+            if let Some(definition) = self.dict.lookup_tables().group(tag) {
+                let num_entries = std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0);
+                if num_entries > 0 {
+                    groups.push(GroupState {
+                        definition,
+                        entries_left: num_entries,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses `text` in the human-readable format and encodes the FIX message
+    /// it represents according to `config`. `BeginString <8>` and
+    /// `MsgType <35>` must be the first fields; `BodyLength <9>` and
+    /// `CheckSum <10>` are ignored and computed anew.
+    pub fn parse<C>(&self, text: &str, config: C) -> Result<Vec<u8>, PrettyError>
+    where
+        C: Configure,
+    {
+        let fields = self.parse_fields(text)?;
+        let mut fields = fields
+            .iter()
+            .filter(|(tag, _)| tag.get() != 9 && tag.get() != 10);
+        let (begin_string, msg_type) = match (fields.next(), fields.next()) {
+            (Some((tag_8, begin_string)), Some((tag_35, msg_type)))
+                if tag_8.get() == 8 && tag_35.get() == 35 =>
+            {
+                (begin_string, msg_type)
+            }
+            _ => return Err(PrettyError::MissingHeader),
+        };
+        let mut encoder = Encoder::new(config);
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(begin_string, &mut buffer, msg_type);
+        for (tag, value) in fields {
+            msg.set(*tag, &value[..]);
+        }
+        msg.done();
+        Ok(buffer)
+    }
+
+    /// Like [`PrettyPrinter::parse`], but it returns the individual fields
+    /// of the message.
+    pub fn parse_fields(&self, text: &str) -> Result<Vec<(TagU16, Vec<u8>)>, PrettyError> {
+        let mut fields = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            // Trailing whitespace is part of the value, if any.
+            let line = line.trim_start();
+            if !line.is_empty() {
+                fields.push(self.parse_line(line, i + 1)?);
+            }
+        }
+        Ok(fields)
+    }
+
+    fn write_field<W>(&self, w: &mut W, tag: u32, value: &[u8]) -> fmt::Result
+    where
+        W: Write,
+    {
+        let field = self.dict.field_by_tag(tag);
+        if let Some(field) = field {
+            write!(w, "{} ", field.name())?;
+        }
+        write!(w, "<{}> = ", tag)?;
+        write_escaped(w, value)?;
+        let description = field.and_then(|field| {
+            field
+                .enums()?
+                .find(|e| e.value().as_bytes() == value)
+                .map(|e| e.description().to_string())
+        });
+        if let Some(description) = description {
+            write!(w, " ({})", description)?;
+        }
+        w.write_char('\n')
+    }
+
+    fn parse_line(&self, line: &str, line_number: usize) -> Result<(TagU16, Vec<u8>), PrettyError> {
+        let syntax_error = PrettyError::Syntax { line: line_number };
+        let tag_start = line.find('<').ok_or(syntax_error)?;
+        let tag_end = tag_start + line[tag_start..].find("> = ").ok_or(syntax_error)?;
+        let name = line[..tag_start].trim_end();
+        let tag: u32 = line[tag_start + 1..tag_end]
+            .parse()
+            .map_err(|_| syntax_error)?;
+        let tag_u16 = u16::try_from(tag)
+            .ok()
+            .and_then(TagU16::new)
+            .ok_or(syntax_error)?;
+        let field = self.dict.field_by_tag(tag);
+        match field {
+            Some(field) if !name.is_empty() && name != field.name() => {
+                return Err(PrettyError::NameMismatch {
+                    line: line_number,
+                    tag,
+                });
+            }
+            None if !name.is_empty() => {
+                return Err(PrettyError::NameMismatch {
+                    line: line_number,
+                    tag,
+                });
+            }
+            _ => {}
+        }
+        let mut value = &line[tag_end + 4..];
+        // Strip the description of enumerated values, if any.
+        if let (Some(field), Some(without_closing)) = (field, value.strip_suffix(')')) {
+            if let Some(i) = without_closing.rfind(" (") {
+                let (enum_value, description) = (&value[..i], &without_closing[i + 2..]);
+                let is_description = field.enums().map_or(false, |mut enums| {
+                    enums.any(|e| e.value() == enum_value && e.description() == description)
+                });
+                if is_description {
+                    value = enum_value;
+                }
+            }
+        }
+        let value = unescape(value).ok_or(syntax_error)?;
+        Ok((tag_u16, value))
+    }
+}
+
+fn write_escaped<W>(w: &mut W, value: &[u8]) -> fmt::Result
+where
+    W: Write,
+{
+    for byte in value.iter().copied() {
+        match byte {
+            b'\\' => w.write_str("\\\\")?,
+            0x20..=0x7e => w.write_char(byte as char)?,
+            _ => write!(w, "\\x{:02X}", byte)?,
+        }
+    }
+    Ok(())
+}
+
+fn unescape(s: &str) -> Option<Vec<u8>> {
+    let mut value = Vec::with_capacity(s.len());
+    let mut bytes = s.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            value.push(byte);
+            continue;
+        }
+        match bytes.next()? {
+            b'\\' => value.push(b'\\'),
+            b'x' => {
+                let hex = [bytes.next()?, bytes.next()?];
+                let hex = std::str::from_utf8(&hex).ok()?;
+                value.push(u8::from_str_radix(hex, 16).ok()?);
+            }
+            _ => return None,
+        }
+    }
+    Some(value)
+}
+
+/// The type returned in the event of an error while parsing the human-readable
+/// format of [`PrettyPrinter`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PrettyError {
+    /// The line is not a valid `Name <tag> = value` field.
+    #[error("Invalid syntax at line {line}.")]
+    Syntax { line: usize },
+    /// The field name doesn't match the name of the tag in the dictionary.
+    #[error("Field name at line {line} doesn't match tag {tag}.")]
+    NameMismatch { line: usize, tag: u32 },
+    /// The message doesn't start with `BeginString <8>` and `MsgType <35>`.
+    #[error("The message doesn't start with `BeginString <8>` and `MsgType <35>`.")]
+    MissingHeader,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tagvalue::{Config, Decoder};
+    use crate::GetConfig;
+
+    fn printer() -> PrettyPrinter {
+        PrettyPrinter::new(Dictionary::fix44())
+    }
+
+    fn decoder() -> Decoder {
+        let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+        decoder.config_mut().set_separator(b'|');
+        decoder
+    }
+
+    #[test]
+    fn nested_groups_are_indented() {
+        let mut config = Config::default();
+        config.set_separator(b'|');
+        let mut encoder = Encoder::new(config);
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"D");
+        let mut parties = msg.start_group(453, 448);
+        parties.set(448, "A");
+        let mut sub_ids = parties.start_group(802, 523);
+        sub_ids.set(523, "A1");
+        sub_ids.set(803, "1");
+        sub_ids.done().unwrap();
+        parties.set(448, "B");
+        parties.set(452, "3");
+        parties.done().unwrap();
+        msg.set(54, "2");
+        let (data, _) = msg.done();
+
+        let mut decoder = decoder();
+        let text = printer().format(&decoder.decode(data).unwrap());
+        let lines: Vec<&str> = text.lines().skip(2).collect();
+        assert_eq!(
+            lines,
+            [
+                "NoPartyIDs <453> = 2",
+                "  PartyID <448> = A",
+                "  NoPartySubIDs <802> = 1",
+                "    PartySubID <523> = A1",
+                "    PartySubIDType <803> = 1 (FIRM)",
+                "  PartyID <448> = B",
+                "  PartyRole <452> = 3 (CLIENT_ID)",
+                "Side <54> = 2 (SELL)",
+            ]
+        );
+        assert_eq!(printer().parse(&text, config).unwrap(), data);
+    }
+
+    #[test]
+    fn values_are_escaped() {
+        let mut encoder = Encoder::with_dictionary(Config::default(), &Dictionary::fix44());
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"A");
+        msg.set_data(96, b"a\x01b\\c\n\xff").unwrap();
+        msg.set(58, "1 (BUY)");
+        let (data, _) = msg.done();
+
+        let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+        let text = printer().format(&decoder.decode(data).unwrap());
+        assert!(text.contains("\nRawData <96> = a\\x01b\\\\c\\x0A\\xFF\n"));
+        assert!(text.contains("\nText <58> = 1 (BUY)\n"));
+        assert_eq!(printer().parse(&text, Config::default()).unwrap(), data);
+    }
+
+    #[test]
+    fn descriptions_are_optional() {
+        let fields = printer()
+            .parse_fields("  Side <54> = 1\n\nSide <54> = 1 (SELL)\n<54> = 2 (SELL)")
+            .unwrap();
+        let values: Vec<&[u8]> = fields.iter().map(|(_, value)| &value[..]).collect();
+        assert_eq!(values, [&b"1"[..], b"1 (SELL)", b"2"]);
+    }
+
+    #[test]
+    fn invalid_lines() {
+        let printer = printer();
+        assert_eq!(
+            printer.parse_fields("Side <54> = 1\nSide 54 = 1"),
+            Err(PrettyError::Syntax { line: 2 })
+        );
+        assert_eq!(
+            printer.parse_fields("Side <0> = 1"),
+            Err(PrettyError::Syntax { line: 1 })
+        );
+        assert_eq!(
+            printer.parse_fields("Text <58> = \\q"),
+            Err(PrettyError::Syntax { line: 1 })
+        );
+        assert_eq!(
+            printer.parse_fields("Price <54> = 1"),
+            Err(PrettyError::NameMismatch { line: 1, tag: 54 })
+        );
+        assert_eq!(
+            printer.parse(
+                "MsgType <35> = 0\nBeginString <8> = FIX.4.4",
+                Config::default()
+            ),
+            Err(PrettyError::MissingHeader)
+        );
+    }
+}
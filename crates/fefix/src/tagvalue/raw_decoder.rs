@@ -106,6 +106,9 @@ where
         RawDecoderBuffered {
             config: self.config,
             buffer: Vec::new(),
+            filled_len: 0,
+            pending: Vec::new(),
+            discarded: Vec::new(),
             last_parser_state: ParserState::Empty,
         }
     }
@@ -162,10 +165,20 @@ enum ParserState {
 }
 
 /// A [`RawDecoder`] that can buffer incoming data and read a stream of messages.
+///
+/// After a decoding error, [`RawDecoderBuffered::resync`] skips the garbled
+/// data and decoding can go on with the next message in the stream.
 #[derive(Debug)]
 pub struct RawDecoderBuffered<C = Config> {
     config: C,
     buffer: Vec<u8>,
+    // How many leading bytes of `buffer` hold actual data, as opposed to
+    // room that was supplied to the user.
+    filled_len: usize,
+    // Bytes that have already been received, but come after the current frame
+    // (this only happens after resynchronization).
+    pending: Vec<u8>,
+    discarded: Vec<u8>,
     last_parser_state: ParserState,
 }
 
@@ -176,53 +189,131 @@ where
     /// Empties all contents of the internal buffer of `self`.
     pub fn clear(&mut self) {
         self.buffer.clear();
+        self.filled_len = 0;
+        self.pending.clear();
         self.last_parser_state = ParserState::Empty;
     }
 
     /// Provides a buffer that must be filled before re-attempting to deserialize
-    /// the next [`RawFrame`].
+    /// the next [`RawFrame`]. If the internal buffer already contains a
+    /// complete frame, it gets discarded to make room for the next one.
     ///
-    /// # Panics
+    /// The provided buffer is empty if `self` already holds enough data
+    /// (e.g. after [`RawDecoderBuffered::resync`]), in which case you should
+    /// call [`RawDecoderBuffered::parse`] right away.
     ///
-    /// Panics if the last call to [`RawDecoderBuffered::raw_frame`]
-    /// returned an [`Err`].
+    /// If the last call to [`RawDecoderBuffered::raw_frame`] returned an
+    /// [`Err`], this calls [`RawDecoderBuffered::resync`] first.
     pub fn supply_buffer(&mut self) -> &mut [u8] {
-        match self.last_parser_state {
-            ParserState::Empty => {
-                // There's no point in validating a FIX message that is too short to
-                // ever be valid.
-                self.buffer.resize(utils::MIN_FIX_MESSAGE_LEN_IN_BYTES, 0);
-                self.buffer.as_mut_slice()
-            }
-            ParserState::Header(_, expected_len) => {
-                let old_len = self.buffer.as_slice().len();
-                self.buffer.resize(expected_len, 0);
-                &mut self.buffer.as_mut_slice()[old_len..]
-            }
-            ParserState::Err(_) => {
-                panic!("This decoder is not valid anymore and it shouldn't have been used.")
-            }
+        if self.is_frame_complete() {
+            self.buffer.clear();
+            self.filled_len = 0;
+            self.last_parser_state = ParserState::Empty;
+        } else if let ParserState::Err(_) = self.last_parser_state {
+            self.resync();
         }
+        let expected_len = match self.last_parser_state {
+            // There's no point in validating a FIX message that is too short to
+            // ever be valid.
+            ParserState::Empty => utils::MIN_FIX_MESSAGE_LEN_IN_BYTES,
+            ParserState::Header(_, expected_len) => expected_len,
+            ParserState::Err(_) => unreachable!("Resynchronization resets the parser state."),
+        };
+        self.buffer.truncate(self.filled_len);
+        let from_pending = self
+            .pending
+            .len()
+            .min(expected_len.saturating_sub(self.buffer.len()));
+        self.buffer.extend(self.pending.drain(..from_pending));
+        self.filled_len = self.buffer.len();
+        self.buffer.resize(expected_len.max(self.filled_len), 0);
+        &mut self.buffer.as_mut_slice()[self.filled_len..]
     }
 
+    /// Discards all buffered data up to the next `8=FIX` start sequence, so
+    /// that decoding can go on after an error. Returns the discarded bytes,
+    /// e.g. for logging purposes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fefix::tagvalue::{Config, RawDecoder};
+    /// use fefix::prelude::*;
+    ///
+    /// let mut decoder = RawDecoder::<Config>::new().buffered();
+    /// decoder.config_mut().set_separator(b'|');
+    /// let stream = b"garbage|garbage|garbage|8=FIX.4.2|9=5|35=0|10=123|";
+    /// let mut i = 0;
+    /// let mut discarded = Vec::new();
+    /// loop {
+    ///     let buffer = decoder.supply_buffer();
+    ///     buffer.copy_from_slice(&stream[i..i + buffer.len()]);
+    ///     i += buffer.len();
+    ///     decoder.parse();
+    ///     match decoder.raw_frame() {
+    ///         Ok(Some(frame)) => {
+    ///             assert_eq!(frame.payload(), b"35=0|");
+    ///             break;
+    ///         }
+    ///         Ok(None) => {}
+    ///         Err(_) => discarded.extend_from_slice(decoder.resync()),
+    ///     }
+    /// }
+    /// assert_eq!(discarded, b"garbage|garbage|garbage|");
+    /// ```
+    pub fn resync(&mut self) -> &[u8] {
+        // Data that follows the current buffer must be examined as well.
+        self.buffer.truncate(self.filled_len);
+        self.buffer.append(&mut self.pending);
+        let offset = utils::resync_offset(self.buffer.as_slice());
+        self.discarded.clear();
+        self.discarded.extend(self.buffer.drain(..offset));
+        std::mem::swap(&mut self.buffer, &mut self.pending);
+        self.filled_len = 0;
+        self.last_parser_state = ParserState::Empty;
+        self.discarded.as_slice()
+    }
+
+    /// Returns the bytes that were discarded by the last call to
+    /// [`RawDecoderBuffered::resync`].
+    pub fn discarded(&self) -> &[u8] {
+        self.discarded.as_slice()
+    }
+
+    /// After filling the buffer provided by
+    /// [`RawDecoderBuffered::supply_buffer`], parses the `BeginString <8>` and
+    /// `BodyLength <9>` fields as soon as they're available and verifies
+    /// `CheckSum <10>` once the whole frame has been received.
     pub fn parse(&mut self) {
+        self.filled_len = self.buffer.len();
         match self.last_parser_state {
             ParserState::Empty => {
-                let header_info =
-                    HeaderInfo::parse(self.buffer.as_slice(), self.config().separator());
+                let header_info = Some(self.buffer.as_slice())
+                    .filter(|data| data.starts_with(b"8="))
+                    .and_then(|data| HeaderInfo::parse(data, self.config().separator()));
                 if let Some(header_info) = header_info {
                     let expected_len_of_frame = header_info.field_1.end
                         + 1
                         + header_info.nominal_body_len
                         + utils::FIELD_CHECKSUM_LEN_IN_BYTES;
 
-                    self.last_parser_state =
-                        ParserState::Header(header_info, expected_len_of_frame);
+                    if expected_len_of_frame < self.buffer.len()
+                        || self
+                            .config()
+                            .max_message_size()
+                            .map_or(false, |max| expected_len_of_frame > max)
+                    {
+                        self.last_parser_state = ParserState::Err(DecodeError::Invalid);
+                    } else {
+                        self.last_parser_state =
+                            ParserState::Header(header_info, expected_len_of_frame);
+                        self.verify_complete_frame();
+                    }
                 } else {
                     self.last_parser_state = ParserState::Err(DecodeError::Invalid);
                 }
             }
-            ParserState::Header(_, _) => {}
+            ParserState::Header(_, _) => self.verify_complete_frame(),
             ParserState::Err(_) => {}
         }
     }
@@ -238,7 +329,9 @@ where
                 DecodeError::Invalid => Err(DecodeError::Invalid),
                 DecodeError::FieldPresence => Err(DecodeError::FieldPresence),
                 DecodeError::IO(_) => unreachable!("Can't have an I/O error here."),
+                DecodeError::Validation(_) => unreachable!("Raw frames are never validated."),
             },
+            ParserState::Header(_, len) if self.filled_len < *len => Ok(None),
             ParserState::Header(header_info, _len) => {
                 let data = &self.buffer.as_slice();
 
@@ -253,6 +346,23 @@ where
     }
 }
 
+impl<C> RawDecoderBuffered<C>
+where
+    C: Configure,
+{
+    fn is_frame_complete(&self) -> bool {
+        matches!(self.last_parser_state, ParserState::Header(_, len) if self.filled_len == len)
+    }
+
+    fn verify_complete_frame(&mut self) {
+        if self.is_frame_complete() && self.config().verify_checksum() {
+            if let Err(err) = utils::verify_checksum(self.buffer.as_slice()) {
+                self.last_parser_state = ParserState::Err(err);
+            }
+        }
+    }
+}
+
 impl<C> GetConfig for RawDecoderBuffered<C> {
     type Config = C;
 
@@ -265,6 +375,42 @@ impl<C> GetConfig for RawDecoderBuffered<C> {
     }
 }
 
+/// Reads `BeginString <8>` and `BodyLength <9>` at the start of `data` and
+/// calculates the length of the whole message, which might extend past the end
+/// of `data`. Returns [`Ok(None)`] if more data is needed to tell.
+pub(super) fn frame_len<C>(data: &[u8], config: &C) -> Result<Option<usize>, DecodeError>
+where
+    C: Configure,
+{
+    if data.is_empty() {
+        return Ok(None);
+    } else if !b"8=".starts_with(&data[..data.len().min(2)]) {
+        return Err(DecodeError::Invalid);
+    }
+    let header_info = match HeaderInfo::parse(data, config.separator()) {
+        Some(header_info) => header_info,
+        None if data.len() < utils::MAX_HEADER_LEN_IN_BYTES => return Ok(None),
+        None => return Err(DecodeError::Invalid),
+    };
+    let body_length = &data[header_info.field_1.clone()];
+    if &data[header_info.field_0.end + 1..header_info.field_1.start] != b"9="
+        || body_length.is_empty()
+        || !body_length.iter().all(u8::is_ascii_digit)
+    {
+        return Err(DecodeError::Invalid);
+    }
+    let frame_len = header_info
+        .field_1
+        .end
+        .checked_add(1 + utils::FIELD_CHECKSUM_LEN_IN_BYTES)
+        .and_then(|len| len.checked_add(header_info.nominal_body_len))
+        .ok_or(DecodeError::Invalid)?;
+    match config.max_message_size() {
+        Some(max) if frame_len > max => Err(DecodeError::Invalid),
+        _ => Ok(Some(frame_len)),
+    }
+}
+
 #[derive(Debug, Clone)]
 struct HeaderInfo {
     field_0: Range<usize>,
@@ -405,4 +551,37 @@ mod test {
         }
         assert!(frame.is_some());
     }
+
+    #[test]
+    fn buffered_decoder_supplies_empty_buffer_when_data_is_pending() {
+        let msg = b"8=FIX.4.2|9=40|35=D|49=AFUNDMGR|56=ABROKER|15=USD|59=0|10=091|";
+        let mut stream = b"8=FIX.4.2|9=9999|35=D|".to_vec();
+        stream.resize(9999 + 16, b'x');
+        stream.extend_from_slice(msg);
+        stream.extend_from_slice(msg);
+        let mut decoder = new_decoder().buffered();
+        decoder.config_mut().set_max_message_size(Some(128));
+        let mut i = 0;
+        let mut frames = 0;
+        let mut discarded = 0;
+        loop {
+            let buf = decoder.supply_buffer();
+            if i + buf.len() > stream.len() {
+                break;
+            }
+            buf.clone_from_slice(&stream[i..i + buf.len()]);
+            i += buf.len();
+            decoder.parse();
+            match decoder.raw_frame() {
+                Ok(Some(frame)) => {
+                    assert_eq!(frame.as_bytes(), msg);
+                    frames += 1;
+                }
+                Ok(None) => {}
+                Err(_) => discarded += decoder.resync().len(),
+            }
+        }
+        assert_eq!(frames, 2);
+        assert_eq!(discarded, 9999 + 16);
+    }
 }
@@ -0,0 +1,539 @@
+use super::raw_decoder::frame_len;
+use super::{utils, Config, Configure, DecodeError, Decoder, Message};
+use crate::GetConfig;
+use futures::{AsyncRead, Stream, StreamExt};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// The initial size of the internal buffer. It grows on demand to fit the
+// largest message seen so far and it's never shrunk.
+const INITIAL_BUFFER_SIZE: usize = 4096;
+
+/// A [`Decoder`] that reads FIX messages from a [`std::io::Read`] source.
+///
+/// [`DecoderReader`] takes care of buffering and framing: it reads as much data
+/// as available at once, handles messages split across multiple reads as well
+/// as multiple messages within a single read, and decodes them in place. The
+/// internal buffer is reused across messages, so no allocations happen once it
+/// has grown enough to fit the largest message in the stream.
+///
+/// Decoded messages borrow from `self`, so they can't be returned by
+/// [`Iterator::next`]. Use [`DecoderReader::next_message`] or, alternatively,
+/// iterate over `self` and then access each message via
+/// [`DecoderReader::message`].
+///
+/// # Examples
+///
+/// ```
+/// use fefix::tagvalue::{Config, Decoder};
+/// use fefix::prelude::*;
+///
+/// let data: &[u8] = b"8=FIX.4.4|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=185|\
+///                     8=FIX.4.4|9=42|35=0|49=A|56=B|34=13|52=20100304-07:59:31|10=188|";
+///
+/// let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+/// decoder.config_mut().set_separator(b'|');
+/// let mut reader = decoder.reader(data);
+///
+/// let msg = reader.next_message().unwrap().unwrap();
+/// assert_eq!(msg.fv(34), Ok(12));
+/// let msg = reader.next_message().unwrap().unwrap();
+/// assert_eq!(msg.fv(34), Ok(13));
+/// assert!(reader.next_message().is_none());
+/// ```
+#[derive(Debug)]
+pub struct DecoderReader<R, C = Config> {
+    stream: StreamBuffer<C>,
+    reader: R,
+}
+
+impl<R, C> DecoderReader<R, C>
+where
+    R: io::Read,
+    C: Configure,
+{
+    /// Creates a new [`DecoderReader`] that uses `decoder` to decode messages
+    /// from `reader`.
+    pub fn new(decoder: Decoder<C>, reader: R) -> Self {
+        Self {
+            stream: StreamBuffer::new(decoder),
+            reader,
+        }
+    }
+
+    /// Reads and decodes the next message. Returns [`None`] once `reader`
+    /// reaches EOF.
+    ///
+    /// Messages which fail validation (e.g. because of a bad `CheckSum <10>`)
+    /// result in an [`Err`] and are skipped, so it's possible to keep reading
+    /// afterwards.
+    pub fn next_message(&mut self) -> Option<Result<Message<&[u8]>, DecodeError>> {
+        match self.next()? {
+            Ok(()) => Some(Ok(self.message())),
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    /// Returns the last message decoded by `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the last call to [`Iterator::next`] didn't return
+    /// [`Some(Ok(()))`].
+    pub fn message(&self) -> Message<&[u8]> {
+        self.stream.message()
+    }
+
+    /// Returns an immutable reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+}
+
+impl<R, C> Iterator for DecoderReader<R, C>
+where
+    R: io::Read,
+    C: Configure,
+{
+    type Item = Result<(), DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stream.consume_last();
+        loop {
+            if let Some(result) = self.stream.try_decode() {
+                return Some(result);
+            }
+            match self.reader.read(self.stream.read_buffer()) {
+                Ok(0) => return self.stream.on_eof(),
+                Ok(num_bytes) => self.stream.on_read(num_bytes),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+    }
+}
+
+impl<R, C> GetConfig for DecoderReader<R, C> {
+    type Config = C;
+
+    fn config(&self) -> &C {
+        self.stream.decoder.config()
+    }
+
+    fn config_mut(&mut self) -> &mut C {
+        self.stream.decoder.config_mut()
+    }
+}
+
+/// A [`Decoder`] that reads FIX messages from a [`futures::AsyncRead`] source.
+///
+/// This is the asynchronous counterpart of [`DecoderReader`] and it has the
+/// same buffering and framing semantics. It implements [`Stream`], with every
+/// [`Ok(())`] item signaling that a new message can be accessed via
+/// [`DecoderAsyncReader::message`].
+///
+/// # Examples
+///
+/// ```
+/// use fefix::tagvalue::{Config, Decoder};
+/// use fefix::prelude::*;
+///
+/// # futures::executor::block_on(async {
+/// let data: &[u8] = b"8=FIX.4.4|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=185|";
+///
+/// let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+/// decoder.config_mut().set_separator(b'|');
+/// let mut reader = decoder.async_reader(data);
+///
+/// let msg = reader.next_message().await.unwrap().unwrap();
+/// assert_eq!(msg.fv(35), Ok("0"));
+/// assert!(reader.next_message().await.is_none());
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct DecoderAsyncReader<R, C = Config> {
+    stream: StreamBuffer<C>,
+    reader: R,
+}
+
+impl<R, C> DecoderAsyncReader<R, C>
+where
+    R: AsyncRead + Unpin,
+    C: Configure,
+{
+    /// Creates a new [`DecoderAsyncReader`] that uses `decoder` to decode
+    /// messages from `reader`.
+    pub fn new(decoder: Decoder<C>, reader: R) -> Self {
+        Self {
+            stream: StreamBuffer::new(decoder),
+            reader,
+        }
+    }
+
+    /// Reads and decodes the next message. Returns [`None`] once `reader`
+    /// reaches EOF.
+    ///
+    /// See [`DecoderReader::next_message`].
+    pub async fn next_message(&mut self) -> Option<Result<Message<'_, &[u8]>, DecodeError>> {
+        match self.next().await? {
+            Ok(()) => Some(Ok(self.message())),
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    /// Returns the last message decoded by `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the last item yielded by `self` wasn't [`Ok(())`].
+    pub fn message(&self) -> Message<&[u8]> {
+        self.stream.message()
+    }
+
+    /// Returns an immutable reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+}
+
+// Nothing is ever pinned structurally besides `reader`.
+impl<R, C> Unpin for DecoderAsyncReader<R, C> where R: Unpin {}
+
+impl<R, C> Stream for DecoderAsyncReader<R, C>
+where
+    R: AsyncRead + Unpin,
+    C: Configure,
+{
+    type Item = Result<(), DecodeError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        // This is a no-op if we've already been polled for this item.
+        this.stream.consume_last();
+        loop {
+            if let Some(result) = this.stream.try_decode() {
+                return Poll::Ready(Some(result));
+            }
+            let buffer = this.stream.read_buffer();
+            match futures::ready!(Pin::new(&mut this.reader).poll_read(cx, buffer)) {
+                Ok(0) => return Poll::Ready(this.stream.on_eof()),
+                Ok(num_bytes) => this.stream.on_read(num_bytes),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Poll::Ready(Some(Err(err.into()))),
+            }
+        }
+    }
+}
+
+impl<R, C> GetConfig for DecoderAsyncReader<R, C> {
+    type Config = C;
+
+    fn config(&self) -> &C {
+        self.stream.decoder.config()
+    }
+
+    fn config_mut(&mut self) -> &mut C {
+        self.stream.decoder.config_mut()
+    }
+}
+
+/// Buffering and framing logic shared by [`DecoderReader`] and
+/// [`DecoderAsyncReader`].
+///
+/// Unconsumed data lives in `buffer[start..end]`. The last decoded message
+/// occupies `buffer[start..start + consumed]` and it's only discarded when the
+/// next message is requested, so that it can be borrowed in the meantime.
+#[derive(Debug)]
+struct StreamBuffer<C> {
+    decoder: Decoder<C>,
+    buffer: Vec<u8>,
+    start: usize,
+    end: usize,
+    consumed: usize,
+    expected_len: Option<usize>,
+    is_ready: bool,
+}
+
+impl<C> StreamBuffer<C>
+where
+    C: Configure,
+{
+    fn new(decoder: Decoder<C>) -> Self {
+        Self {
+            decoder,
+            buffer: vec![0; INITIAL_BUFFER_SIZE],
+            start: 0,
+            end: 0,
+            consumed: 0,
+            expected_len: None,
+            is_ready: false,
+        }
+    }
+
+    fn message(&self) -> Message<&[u8]> {
+        assert!(self.is_ready);
+        self.decoder.message()
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.buffer[self.start..self.end]
+    }
+
+    fn consume_last(&mut self) {
+        self.start += self.consumed;
+        self.consumed = 0;
+        self.is_ready = false;
+        if self.start == self.end {
+            self.start = 0;
+            self.end = 0;
+        }
+    }
+
+    fn discard_all(&mut self) {
+        self.start = 0;
+        self.end = 0;
+        self.consumed = 0;
+        self.expected_len = None;
+    }
+
+    /// Attempts to decode the next message from buffered data. Returns [`None`]
+    /// if more data is needed.
+    fn try_decode(&mut self) -> Option<Result<(), DecodeError>> {
+        let frame_len = match frame_len(self.data(), self.decoder.config()) {
+            Ok(Some(len)) => len,
+            Ok(None) => return None,
+            Err(err) => {
+                // Skip the garbled data and go on with the next message.
+                self.expected_len = None;
+                self.consumed = utils::resync_offset(self.data());
+                return Some(Err(err));
+            }
+        };
+        if self.end - self.start < frame_len {
+            self.expected_len = Some(frame_len);
+            return None;
+        }
+        self.expected_len = None;
+        self.consumed = frame_len;
+        let frame = &self.buffer[self.start..self.start + frame_len];
+        match self.decoder.decode(frame) {
+            Ok(_) => {
+                self.is_ready = true;
+                Some(Ok(()))
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    /// Returns the spare room at the end of the internal buffer, making sure
+    /// there's enough of it for the message that's currently being read.
+    fn read_buffer(&mut self) -> &mut [u8] {
+        let required_len = self
+            .expected_len
+            .unwrap_or(0)
+            .max(self.end - self.start + 1);
+        if self.start > 0 && self.start + required_len > self.buffer.len() {
+            self.buffer.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+        if required_len > self.buffer.len() {
+            self.buffer.resize(required_len, 0);
+        }
+        &mut self.buffer[self.end..]
+    }
+
+    fn on_read(&mut self, num_bytes: usize) {
+        self.end += num_bytes;
+    }
+
+    fn on_eof(&mut self) -> Option<Result<(), DecodeError>> {
+        if self.start == self.end {
+            None
+        } else {
+            self.discard_all();
+            Some(Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+
+    const MESSAGES: &[&[u8]] = &[
+        b"8=FIX.4.2|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=185|",
+        b"8=FIX.4.2|9=97|35=6|49=BKR|56=IM|34=14|52=20100204-09:18:42|23=115685|28=N|55=SPMI.MI|54=2|44=2200.75|27=S|25=H|10=248|",
+        b"8=FIX.4.4|9=70|35=4|49=A|56=XYZ|34=129|52=20100302-19:38:21|43=Y|57=LOL|123=Y|36=175|10=192|",
+    ];
+
+    fn decoder() -> Decoder {
+        let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+        decoder.config_mut().set_separator(b'|');
+        decoder
+    }
+
+    fn stream(n: usize) -> Vec<u8> {
+        MESSAGES
+            .iter()
+            .cycle()
+            .take(n)
+            .copied()
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Yields at most `chunk_size` bytes per `read` call.
+    struct ChunkedReader<'a> {
+        data: &'a [u8],
+        chunk_size: usize,
+    }
+
+    impl<'a> io::Read for ChunkedReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.chunk_size).min(self.data.len());
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn empty_reader_yields_nothing() {
+        let mut reader = decoder().reader(&b""[..]);
+        assert!(reader.next_message().is_none());
+    }
+
+    #[test]
+    fn many_messages_in_a_single_read() {
+        let data = stream(300);
+        let mut reader = decoder().reader(&data[..]);
+        let mut count = 0;
+        while let Some(msg) = reader.next_message() {
+            assert_eq!(msg.unwrap().as_bytes(), MESSAGES[count % MESSAGES.len()]);
+            count += 1;
+        }
+        assert_eq!(count, 300);
+    }
+
+    #[test]
+    fn messages_split_across_reads() {
+        let data = stream(30);
+        for chunk_size in [1, 2, 3, 7, 19, 64, 100, 1000] {
+            let mut reader = decoder().reader(ChunkedReader {
+                data: &data[..],
+                chunk_size,
+            });
+            let mut count = 0;
+            while let Some(msg) = reader.next_message() {
+                assert_eq!(msg.unwrap().as_bytes(), MESSAGES[count % MESSAGES.len()]);
+                count += 1;
+            }
+            assert_eq!(count, 30);
+        }
+    }
+
+    #[test]
+    fn messages_larger_than_initial_buffer() {
+        let text = "x".repeat(INITIAL_BUFFER_SIZE * 2);
+        let body = format!("35=B|148={}|", text);
+        let mut data = format!("8=FIX.4.4|9={}|{}10=000|", body.len(), body).into_bytes();
+        data.extend_from_slice(MESSAGES[0]);
+        let mut decoder = decoder();
+        decoder.config_mut().set_max_message_size(None);
+        let mut reader = decoder.reader(&data[..]);
+        let msg = reader.next_message().unwrap().unwrap();
+        assert_eq!(msg.fv_raw(148), Some(text.as_bytes()));
+        let msg = reader.next_message().unwrap().unwrap();
+        assert_eq!(msg.as_bytes(), MESSAGES[0]);
+    }
+
+    #[test]
+    fn truncated_stream_is_an_error() {
+        let data = &MESSAGES[0][..30];
+        let mut reader = decoder().reader(data);
+        assert!(matches!(
+            reader.next_message(),
+            Some(Err(DecodeError::IO(_)))
+        ));
+        assert!(reader.next_message().is_none());
+    }
+
+    #[test]
+    fn bad_checksum_is_skipped() {
+        let mut data = b"8=FIX.4.2|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=000|".to_vec();
+        data.extend_from_slice(MESSAGES[0]);
+        for byte in data.iter_mut().filter(|byte| **byte == b'|') {
+            *byte = 0x1;
+        }
+        let mut decoder = decoder();
+        decoder.config_mut().set_separator(0x1);
+        let mut reader = decoder.reader(&data[..]);
+        assert!(matches!(
+            reader.next_message(),
+            Some(Err(DecodeError::CheckSum))
+        ));
+        assert!(reader.next_message().unwrap().is_ok());
+        assert!(reader.next_message().is_none());
+    }
+
+    #[test]
+    fn garbled_data_is_skipped() {
+        let mut data = b"garbage|9=garbage|".to_vec();
+        data.extend_from_slice(MESSAGES[0]);
+        let mut reader = decoder().reader(&data[..]);
+        assert!(matches!(
+            reader.next_message(),
+            Some(Err(DecodeError::Invalid))
+        ));
+        assert_eq!(
+            reader.next_message().unwrap().unwrap().as_bytes(),
+            MESSAGES[0]
+        );
+        assert!(reader.next_message().is_none());
+    }
+
+    #[test]
+    fn iterator_and_message_accessor() {
+        let data = stream(5);
+        let mut reader = decoder().reader(&data[..]);
+        let mut msg_types = Vec::new();
+        while let Some(result) = reader.next() {
+            result.unwrap();
+            msg_types.push(reader.message().fv::<&str>(35).unwrap().to_string());
+        }
+        assert_eq!(msg_types, vec!["0", "6", "4", "0", "6"]);
+    }
+
+    #[tokio::test]
+    async fn async_reader_decodes_stream() {
+        let data = stream(100);
+        let mut reader = decoder().async_reader(&data[..]);
+        let mut count = 0;
+        while let Some(msg) = reader.next_message().await {
+            assert_eq!(msg.unwrap().as_bytes(), MESSAGES[count % MESSAGES.len()]);
+            count += 1;
+        }
+        assert_eq!(count, 100);
+    }
+
+    #[tokio::test]
+    async fn async_reader_is_a_stream() {
+        let data = stream(10);
+        let reader = decoder().async_reader(&data[..]);
+        assert_eq!(reader.count().await, 10);
+    }
+}
@@ -0,0 +1,412 @@
+use super::Configure;
+use crate::fix_value::CheckSum;
+use crate::{BufferWriter, FixValue, SetField};
+use std::fmt::Write;
+
+/// A handle to a fixed-width field value of a [`MessageTemplate`], as
+/// returned by [`TemplateBuilder::slot`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Slot(usize);
+
+/// How [`MessageTemplate`] handles values shorter than their [`Slot`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SlotPadding {
+    /// Values are left-padded with zeros, after the sign of negative numbers.
+    /// FIX allows this for `int` and `float` values (and their derived types)
+    /// only, e.g. `MsgSeqNum <34>` and `Price <44>`.
+    Zeros,
+    /// Values must have the same width as their slot, e.g. `ClOrdID <11>`
+    /// and `SendingTime <52>`.
+    Exact,
+}
+
+/// A type returned by [`Encoder::start_template`](super::Encoder::start_template)
+/// to define the contents of a [`MessageTemplate`].
+///
+/// Static fields are written once with [`SetField`], just like with
+/// [`EncoderHandle`](super::EncoderHandle). Variable fields are declared with
+/// [`TemplateBuilder::slot`] instead.
+#[derive(Debug, Clone)]
+pub struct TemplateBuilder {
+    buffer: Vec<u8>,
+    separator: u8,
+    zero_padded_body_length: bool,
+    body_start_i: usize,
+    slots: Vec<SlotData>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct SlotData {
+    tag: u32,
+    start_i: usize,
+    width: usize,
+    padding: SlotPadding,
+}
+
+impl TemplateBuilder {
+    pub(crate) fn new<C>(config: &C, begin_string: &[u8], msg_type: &[u8]) -> Self
+    where
+        C: Configure,
+    {
+        let mut builder = Self {
+            buffer: Vec::new(),
+            separator: config.separator(),
+            zero_padded_body_length: config.zero_padded_body_length(),
+            body_start_i: 0,
+            slots: Vec::new(),
+        };
+        builder.set(8, begin_string);
+        // The body length of a template never changes, so we can write it
+        // once all fields are known.
+        builder.set(9, b"00000000" as &[u8]);
+        builder.body_start_i = builder.buffer.len();
+        builder.set(35, msg_type);
+        builder
+    }
+
+    /// Writes the field `tag` with a placeholder value of exactly `width`
+    /// bytes, to be filled in later with [`MessageTemplate::set`]. `padding`
+    /// tells what to do with shorter values, depending on the data type of
+    /// `tag`.
+    pub fn slot(&mut self, tag: u32, width: usize, padding: SlotPadding) -> Slot {
+        write!(BufferWriter(&mut self.buffer), "{}=", tag).unwrap();
+        let start_i = self.buffer.len();
+        self.buffer.resize(start_i + width, b'0');
+        self.buffer.push(self.separator);
+        self.slots.push(SlotData {
+            tag,
+            start_i,
+            width,
+            padding,
+        });
+        Slot(self.slots.len() - 1)
+    }
+
+    /// Writes `BodyLength <9>` and `CheckSum <10>`, and returns the finished
+    /// [`MessageTemplate`].
+    pub fn done(mut self) -> MessageTemplate {
+        let body_length = self.buffer.len() - self.body_start_i;
+        let body_length_start_i = self.body_start_i - 9;
+        let num_digits = if self.zero_padded_body_length {
+            8
+        } else {
+            num_digits(body_length)
+        };
+        // Unlike `EncoderHandle`, this shift happens once per template rather
+        // than once per message.
+        let shift = 8 - num_digits;
+        self.buffer
+            .drain(body_length_start_i..body_length_start_i + shift);
+        for slot in self.slots.iter_mut() {
+            slot.start_i -= shift;
+        }
+        write_zero_padded(
+            &mut self.buffer[body_length_start_i..][..num_digits],
+            body_length,
+        );
+        let checksum = CheckSum::compute(&self.buffer[..]).0;
+        self.buffer.extend_from_slice(b"10=");
+        let checksum_i = self.buffer.len();
+        self.buffer.extend_from_slice(b"000");
+        self.buffer.push(self.separator);
+        let mut template = MessageTemplate {
+            buffer: self.buffer,
+            slots: self.slots,
+            checksum_i,
+            checksum,
+            scratch: Vec::new(),
+        };
+        template.write_checksum();
+        template
+    }
+}
+
+impl SetField<u32> for TemplateBuilder {
+    fn set_with<'a, V>(&'a mut self, tag: u32, value: V, settings: V::SerializeSettings)
+    where
+        V: FixValue<'a>,
+    {
+        write!(BufferWriter(&mut self.buffer), "{}=", tag).unwrap();
+        value.serialize_with(&mut self.buffer, settings);
+        self.buffer.push(self.separator);
+    }
+}
+
+/// A pre-serialized FIX message with fixed-width slots for variable field
+/// values.
+///
+/// Messages that are sent over and over with only a few different field values
+/// (e.g. `NewOrderSingle <D>` with a new `ClOrdID <11>`, `Price <44>`, and
+/// `OrderQty <38>`) don't need to be encoded from scratch every time. Since
+/// slot widths never change, neither does `BodyLength <9>`; and `CheckSum <10>`
+/// is updated incrementally from the bytes of the changed slot only. The cost
+/// of [`MessageTemplate::set`] is thus proportional to the width of the slot
+/// rather than to the length of the message.
+///
+/// Slot values shorter than their width are left-padded with zeros only for
+/// [`SlotPadding::Zeros`] slots. Values of [`SlotPadding::Exact`] slots must
+/// have the same width as their slot.
+///
+/// # Examples
+///
+/// ```
+/// use fefix::tagvalue::{Config, Encoder, SlotPadding};
+/// use fefix::prelude::*;
+///
+/// let mut encoder = Encoder::<Config>::default();
+/// encoder.config_mut().set_separator(b'|');
+/// let mut builder = encoder.start_template(b"FIX.4.4", b"D");
+/// builder.set(49, "SENDER");
+/// builder.set(56, "TARGET");
+/// let seq_num = builder.slot(34, 6, SlotPadding::Zeros);
+/// let cl_ord_id = builder.slot(11, 4, SlotPadding::Exact);
+/// builder.set(55, "AAPL");
+/// builder.set(54, "1");
+/// let price = builder.slot(44, 8, SlotPadding::Zeros);
+/// let mut template = builder.done();
+///
+/// template.set(seq_num, 42u64).unwrap();
+/// template.set(cl_ord_id, "A001").unwrap();
+/// template.set(price, "150.25").unwrap();
+/// assert_eq!(
+///     template.as_bytes(),
+///     b"8=FIX.4.4|9=00000068|35=D|49=SENDER|56=TARGET|34=000042|11=A001|55=AAPL|54=1|44=00150.25|10=198|"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageTemplate {
+    buffer: Vec<u8>,
+    slots: Vec<SlotData>,
+    checksum_i: usize,
+    // The sum of all bytes before `CheckSum <10>`, modulo 256.
+    checksum: u8,
+    scratch: Vec<u8>,
+}
+
+impl MessageTemplate {
+    /// Serializes `value` into `slot`.
+    pub fn set<'a, V>(&mut self, slot: Slot, value: V) -> Result<(), TemplateError>
+    where
+        V: FixValue<'a>,
+    {
+        let mut scratch = std::mem::take(&mut self.scratch);
+        scratch.clear();
+        value.serialize(&mut scratch);
+        let result = self.set_raw(slot, &scratch[..]);
+        self.scratch = scratch;
+        result
+    }
+
+    /// Writes the raw bytes `value` into `slot`.
+    pub fn set_raw(&mut self, slot: Slot, value: &[u8]) -> Result<(), TemplateError> {
+        let SlotData {
+            tag,
+            start_i,
+            width,
+            padding,
+        } = self.slots[slot.0];
+        if value.len() > width {
+            return Err(TemplateError::ValueTooLong {
+                tag,
+                width,
+                len: value.len(),
+            });
+        } else if value.len() < width && padding == SlotPadding::Exact {
+            return Err(TemplateError::ValueTooShort {
+                tag,
+                width,
+                len: value.len(),
+            });
+        }
+        let field = &mut self.buffer[start_i..start_i + width];
+        let old_sum = byte_sum(field);
+        // Zeros go after the sign of negative numbers.
+        let (sign, digits) = match value.split_first() {
+            Some((b'-', digits)) if value.len() < width => (1, digits),
+            _ => (0, value),
+        };
+        let padding = width - value.len();
+        field[..sign].copy_from_slice(&value[..sign]);
+        field[sign..sign + padding].fill(b'0');
+        field[sign + padding..].copy_from_slice(digits);
+        let new_sum = byte_sum(field);
+        self.checksum = self.checksum.wrapping_sub(old_sum).wrapping_add(new_sum);
+        self.write_checksum();
+        Ok(())
+    }
+
+    /// Returns the current value of `slot`, including any padding.
+    pub fn get_raw(&self, slot: Slot) -> &[u8] {
+        let slot = self.slots[slot.0];
+        &self.buffer[slot.start_i..slot.start_i + slot.width]
+    }
+
+    /// Returns the byte representation of the whole message.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..]
+    }
+
+    fn write_checksum(&mut self) {
+        let checksum_i = self.checksum_i;
+        write_zero_padded(
+            &mut self.buffer[checksum_i..checksum_i + 3],
+            self.checksum as usize,
+        );
+    }
+}
+
+/// The type returned in the event of an error while writing a slot of a
+/// [`MessageTemplate`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TemplateError {
+    /// The value doesn't fit in the slot.
+    #[error("Value of tag {tag} is {len} bytes long, but its slot is only {width} bytes wide.")]
+    ValueTooLong { tag: u32, width: usize, len: usize },
+    /// The value is shorter than its [`SlotPadding::Exact`] slot.
+    #[error("Value of tag {tag} is {len} bytes long, but its slot is {width} bytes wide.")]
+    ValueTooShort { tag: u32, width: usize, len: usize },
+}
+
+fn byte_sum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+fn num_digits(mut value: usize) -> usize {
+    let mut num_digits = 1;
+    while value >= 10 {
+        value /= 10;
+        num_digits += 1;
+    }
+    num_digits
+}
+
+fn write_zero_padded(digits: &mut [u8], mut value: usize) {
+    for digit in digits.iter_mut().rev() {
+        *digit = b'0' + (value % 10) as u8;
+        value /= 10;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tagvalue::{Config, Decoder, Encoder};
+    use crate::{Dictionary, GetConfig, RandomFieldAccess};
+
+    fn encoder() -> Encoder {
+        let mut encoder = Encoder::<Config>::default();
+        encoder.config_mut().set_separator(b'|');
+        encoder
+    }
+
+    fn template() -> (MessageTemplate, [Slot; 4]) {
+        let encoder = encoder();
+        let mut builder = encoder.start_template(b"FIX.4.4", b"D");
+        let seq_num = builder.slot(34, 8, SlotPadding::Zeros);
+        let sending_time = builder.slot(52, 21, SlotPadding::Exact);
+        let cl_ord_id = builder.slot(11, 8, SlotPadding::Exact);
+        builder.set(55, "EUR/USD");
+        let price = builder.slot(44, 10, SlotPadding::Zeros);
+        (builder.done(), [seq_num, sending_time, cl_ord_id, price])
+    }
+
+    #[test]
+    fn same_as_encoder_output() {
+        let (mut template, [seq_num, sending_time, cl_ord_id, price]) = template();
+        template.set(seq_num, 1234u64).unwrap();
+        template.set(sending_time, "20220601-09:30:00.123").unwrap();
+        template.set(cl_ord_id, "ORDER-01").unwrap();
+        template.set(price, "-1.5").unwrap();
+
+        let mut encoder = encoder();
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"D");
+        msg.set(34, "00001234");
+        msg.set(52, "20220601-09:30:00.123");
+        msg.set(11, "ORDER-01");
+        msg.set(55, "EUR/USD");
+        msg.set(44, "-0000001.5");
+        assert_eq!(template.as_bytes(), msg.done().0);
+    }
+
+    #[test]
+    fn checksum_is_updated_incrementally() {
+        let (mut template, [seq_num, _, cl_ord_id, price]) = template();
+        for i in 0..300u64 {
+            template.set(seq_num, i * 7919).unwrap();
+            template
+                .set(cl_ord_id, format!("ID{:06}", i).as_str())
+                .unwrap();
+            template
+                .set(price, format!("{:.3}", i as f64 / 8.0).as_str())
+                .unwrap();
+            let data = template.as_bytes();
+            let expected = CheckSum::compute(&data[..data.len() - 7]);
+            assert_eq!(
+                &data[data.len() - 4..data.len() - 1],
+                &expected.to_bytes()[..]
+            );
+        }
+        assert_eq!(template.get_raw(cl_ord_id), b"ID000299");
+
+        let mut decoder = Decoder::<Config>::new(Dictionary::fix44());
+        decoder.config_mut().set_separator(b'|');
+        decoder.config_mut().set_verify_checksum(true);
+        let message = decoder.decode(template.as_bytes()).unwrap();
+        assert_eq!(message.fv::<u64>(34), Ok(299 * 7919));
+        assert_eq!(message.fv::<&str>(44), Ok("000037.375"));
+    }
+
+    #[test]
+    fn canonical_body_length() {
+        let mut config = Config::default();
+        config.set_separator(b'|');
+        config.set_zero_padded_body_length(false);
+        let mut encoder = Encoder::new(config);
+        let mut builder = encoder.start_template(b"FIX.4.4", b"D");
+        let cl_ord_id = builder.slot(11, 4, SlotPadding::Exact);
+        builder.set(55, "AAPL");
+        let mut template = builder.done();
+        template.set(cl_ord_id, "A001").unwrap();
+
+        let mut buffer = Vec::new();
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"D");
+        msg.set(11, "A001");
+        msg.set(55, "AAPL");
+        let (data, _) = msg.done();
+        assert!(data.starts_with(b"8=FIX.4.4|9=21|35=D|"));
+        assert_eq!(template.as_bytes(), data);
+    }
+
+    #[test]
+    fn values_must_fit_in_slots() {
+        let (mut template, [seq_num, ..]) = template();
+        let before = template.as_bytes().to_vec();
+        assert_eq!(
+            template.set(seq_num, 123456789u64),
+            Err(TemplateError::ValueTooLong {
+                tag: 34,
+                width: 8,
+                len: 9
+            })
+        );
+        assert_eq!(template.as_bytes(), &before[..]);
+    }
+
+    #[test]
+    fn only_numeric_slots_are_zero_padded() {
+        let (mut template, [seq_num, _, cl_ord_id, _]) = template();
+        template.set(seq_num, 42u64).unwrap();
+        assert_eq!(template.get_raw(seq_num), b"00000042");
+        let before = template.as_bytes().to_vec();
+        assert_eq!(
+            template.set(cl_ord_id, "A1"),
+            Err(TemplateError::ValueTooShort {
+                tag: 11,
+                width: 8,
+                len: 2
+            })
+        );
+        assert_eq!(template.as_bytes(), &before[..]);
+    }
+}
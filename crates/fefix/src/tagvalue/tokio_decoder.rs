@@ -1,30 +1,52 @@
-use super::{Config, Configure, DecodeError, Decoder, RawDecoder, RawFrame};
+use super::raw_decoder::frame_len;
+use super::utils::resync_offset;
+use super::{Config, Configure, DecodeError, Decoder, Encoder, OwnedMessage, RawDecoder, RawFrame};
 use crate::{Dictionary, GetConfig};
-use bytes::{Bytes, BytesMut};
+use bytes::{Buf, Bytes, BytesMut};
+use std::io;
 use tokio_util::codec;
 
-#[derive(Debug)]
+/// A [`tokio_util::codec::Decoder`] that splits a byte stream into
+/// [`RawFrame`]s.
+///
+/// Frames longer than [`Configure::max_message_size`] are rejected with
+/// [`DecodeError::Invalid`].
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "utils-tokio")))]
 pub struct TokioRawDecoder<C = Config> {
-    dict: Dictionary,
     raw_decoder: RawDecoder<C>,
 }
 
-impl codec::Decoder for TokioRawDecoder {
+impl<C> TokioRawDecoder<C>
+where
+    C: Configure,
+{
+    /// Creates a new [`TokioRawDecoder`] with default configuration options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C> codec::Decoder for TokioRawDecoder<C>
+where
+    C: Configure,
+{
     type Item = RawFrame<Bytes>;
     type Error = DecodeError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let split = src.split();
-        let result = self.raw_decoder.decode(split);
-        match result {
-            Ok(raw_frame) => Ok(Some(RawFrame {
-                data: raw_frame.data.freeze(),
-                begin_string: raw_frame.begin_string,
-                payload: raw_frame.payload,
-            })),
-            Err(DecodeError::Invalid) => Ok(None),
-            Err(e) => Err(e),
-        }
+        let data = match split_frame(src, self.config())? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+        let raw_frame = self.raw_decoder.decode(&data[..])?;
+        let begin_string = raw_frame.begin_string;
+        let payload = raw_frame.payload;
+        Ok(Some(RawFrame {
+            data,
+            begin_string,
+            payload,
+        }))
     }
 }
 
@@ -40,27 +62,363 @@ impl<C> GetConfig for TokioRawDecoder<C> {
     }
 }
 
+/// A [`tokio_util::codec::Decoder`] that decodes a byte stream into
+/// [`OwnedMessage`]s, with full field and group access.
+///
+/// # Examples
+///
+/// ```
+/// use fefix::tagvalue::{Config, TokioDecoder};
+/// use fefix::prelude::*;
+/// use bytes::BytesMut;
+/// use tokio_util::codec::Decoder;
+///
+/// let mut codec = TokioDecoder::<Config>::new(Dictionary::fix44());
+/// codec.config_mut().set_separator(b'|');
+/// let mut src = BytesMut::from(&b"8=FIX.4.4|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=185|"[..]);
+/// let message = codec.decode(&mut src).unwrap().unwrap();
+///
+/// assert_eq!(message.message().fv(34), Ok(12));
+/// assert!(src.is_empty());
+/// ```
 #[derive(Debug)]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "utils-tokio")))]
 pub struct TokioDecoder<C = Config>
 where
     C: Configure,
 {
-    dict: Dictionary,
-    raw_decoder: Decoder<C>,
-}
-
-//impl codec::Decoder for TokioDecoder {
-//    type Item = Message;
-//    type Error = DecodeError;
-//
-//    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-//        let split = src.split();
-//        let result = self.raw_decoder.decode(split);
-//        match result {
-//            Ok(raw_frame) => Ok(Some(raw_frame.map_data(|bytes_mut| bytes_mut.freeze()))),
-//            Err(DecodeError::Invalid) => Ok(None),
-//            Err(e) => Err(e),
-//        }
-//    }
-//}
+    decoder: Decoder<C>,
+}
+
+impl<C> TokioDecoder<C>
+where
+    C: Configure,
+{
+    /// Creates a new [`TokioDecoder`]. `dict` is used to parse messages.
+    pub fn new(dict: Dictionary) -> Self {
+        Self {
+            decoder: Decoder::new(dict),
+        }
+    }
+}
+
+impl<C> From<Decoder<C>> for TokioDecoder<C>
+where
+    C: Configure,
+{
+    /// Creates a new [`TokioDecoder`] from `decoder`, e.g. one with multiple
+    /// dictionaries (see [`Decoder::add_dictionary`]).
+    fn from(decoder: Decoder<C>) -> Self {
+        Self { decoder }
+    }
+}
+
+impl<C> codec::Decoder for TokioDecoder<C>
+where
+    C: Configure,
+{
+    type Item = OwnedMessage;
+    type Error = DecodeError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match split_frame(src, self.config())? {
+            Some(data) => self.decoder.decode_owned(data).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<C> GetConfig for TokioDecoder<C>
+where
+    C: Configure,
+{
+    type Config = C;
+
+    fn config(&self) -> &Self::Config {
+        self.decoder.config()
+    }
+
+    fn config_mut(&mut self) -> &mut Self::Config {
+        self.decoder.config_mut()
+    }
+}
+
+/// A [`tokio_util::codec::Encoder`] for FIX messages.
+///
+/// [`TokioEncoder`] accepts already encoded [`OwnedMessage`]s and [`Bytes`]
+/// (e.g. from [`EncoderHandle::freeze`](super::EncoderHandle::freeze), to
+/// send the same message to multiple sessions), as well as closures of type
+/// `FnOnce(&mut Encoder<C>, &mut BytesMut)`, which can use
+/// [`Encoder::start_message`] to serialize a new message straight into the
+/// output buffer.
+///
+/// # Examples
+///
+/// ```
+/// use fefix::tagvalue::{Config, Encoder, TokioEncoder};
+/// use fefix::prelude::*;
+/// use bytes::BytesMut;
+/// use tokio_util::codec::Encoder as _;
+///
+/// let mut codec = TokioEncoder::<Config>::default();
+/// codec.config_mut().set_separator(b'|');
+/// let mut dst = BytesMut::new();
+/// codec
+///     .encode(
+///         |encoder: &mut Encoder, buffer: &mut BytesMut| {
+///             let mut msg = encoder.start_message(b"FIX.4.4", buffer, b"0");
+///             msg.set(34, 12u32);
+///             msg.done();
+///         },
+///         &mut dst,
+///     )
+///     .unwrap();
+///
+/// assert_eq!(&dst[..], b"8=FIX.4.4|9=00000011|35=0|34=12|10=228|");
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "utils-tokio")))]
+pub struct TokioEncoder<C = Config> {
+    encoder: Encoder<C>,
+}
+
+impl<C> TokioEncoder<C>
+where
+    C: Configure,
+{
+    /// Creates a new [`TokioEncoder`] from the given `config` options.
+    pub fn new(config: C) -> Self {
+        Self {
+            encoder: Encoder::new(config),
+        }
+    }
+}
+
+impl<C, F> codec::Encoder<F> for TokioEncoder<C>
+where
+    C: Configure,
+    F: FnOnce(&mut Encoder<C>, &mut BytesMut),
+{
+    type Error = io::Error;
+
+    fn encode(&mut self, item: F, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        item(&mut self.encoder, dst);
+        Ok(())
+    }
+}
+
+impl<C> codec::Encoder<OwnedMessage> for TokioEncoder<C>
+where
+    C: Configure,
+{
+    type Error = io::Error;
+
+    fn encode(&mut self, item: OwnedMessage, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(item.as_bytes());
+        Ok(())
+    }
+}
+
+impl<C> codec::Encoder<Bytes> for TokioEncoder<C>
+where
+    C: Configure,
+{
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&item[..]);
+        Ok(())
+    }
+}
+
+impl<C> GetConfig for TokioEncoder<C> {
+    type Config = C;
+
+    fn config(&self) -> &Self::Config {
+        self.encoder.config()
+    }
+
+    fn config_mut(&mut self) -> &mut Self::Config {
+        self.encoder.config_mut()
+    }
+}
+
+/// Splits the first complete frame off `src`, reserving enough space for it if
+/// it's still incomplete.
+///
+/// Garbled bytes are discarded up to the next `BeginString <8>` before
+/// returning the error, so that decoding can resume from there.
+fn split_frame<C>(src: &mut BytesMut, config: &C) -> Result<Option<Bytes>, DecodeError>
+where
+    C: Configure,
+{
+    let frame_len = match frame_len(&src[..], config) {
+        Ok(frame_len) => frame_len,
+        Err(err) => {
+            src.advance(resync_offset(&src[..]));
+            return Err(err);
+        }
+    };
+    match frame_len {
+        Some(len) if src.len() >= len => Ok(Some(src.split_to(len).freeze())),
+        Some(len) => {
+            src.reserve(len - src.len());
+            Ok(None)
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+    use crate::utils::counting_allocator::allocations;
+    use futures::{SinkExt, StreamExt};
+    use tokio_util::codec::{Decoder as _, FramedRead, FramedWrite};
+
+    const MSG: &[u8] = b"8=FIX.4.4|9=42|35=0|49=A|56=B|34=12|52=20100304-07:59:30|10=185|";
+
+    fn decoder() -> TokioDecoder {
+        let mut decoder = TokioDecoder::<Config>::new(Dictionary::fix44());
+        decoder.config_mut().set_separator(b'|');
+        decoder
+    }
+
+    #[test]
+    fn partial_frame_is_kept_in_buffer() {
+        let mut codec = decoder();
+        let mut src = BytesMut::from(&MSG[..20]);
+        assert!(codec.decode(&mut src).unwrap().is_none());
+        assert_eq!(&src[..], &MSG[..20]);
+        src.extend_from_slice(&MSG[20..]);
+        src.extend_from_slice(&MSG[..5]);
+        let message = codec.decode(&mut src).unwrap().unwrap();
+        assert_eq!(message.as_bytes(), MSG);
+        assert_eq!(message.message().fv::<&str>(49), Ok("A"));
+        assert_eq!(&src[..], &MSG[..5]);
+    }
+
+    #[test]
+    fn frames_above_max_message_size_are_rejected() {
+        let mut codec = decoder();
+        codec.config_mut().set_max_message_size(Some(32));
+        let mut src = BytesMut::from(MSG);
+        assert!(codec.decode(&mut src).is_err());
+    }
+
+    #[test]
+    fn valid_frame_after_garbage_is_decoded() {
+        let mut codec = decoder();
+        let mut src = BytesMut::from(&b"garbage"[..]);
+        src.extend_from_slice(MSG);
+        assert!(codec.decode(&mut src).is_err());
+        assert_eq!(&src[..], MSG);
+        let message = codec.decode(&mut src).unwrap().unwrap();
+        assert_eq!(message.as_bytes(), MSG);
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn decoding_does_not_allocate_in_steady_state() {
+        let mut codec = decoder();
+        let mut src = BytesMut::with_capacity(1024);
+        let mut decode_all = |codec: &mut TokioDecoder| {
+            for _ in 0..10 {
+                src.extend_from_slice(MSG);
+            }
+            while let Some(message) = codec.decode(&mut src).unwrap() {
+                assert_eq!(message.message().fv::<u32>(34), Ok(12));
+            }
+        };
+        decode_all(&mut codec);
+        decode_all(&mut codec);
+        let allocations = allocations(|| {
+            for _ in 0..100 {
+                decode_all(&mut codec);
+            }
+        });
+        assert_eq!(allocations, 0);
+    }
+
+    #[test]
+    fn decoder_with_multiple_dictionaries() {
+        let mut decoder = Decoder::<Config>::new(Dictionary::empty());
+        decoder.add_dictionary(Dictionary::fix44());
+        decoder.config_mut().set_separator(b'|');
+        let mut codec = TokioDecoder::from(decoder);
+        let mut src = BytesMut::from(
+            &b"8=FIXT.1.1|9=46|35=V|1128=6|262=1|146=2|55=AAPL|55=MSFT|263=0|10=081|"[..],
+        );
+        let message = codec.decode(&mut src).unwrap().unwrap();
+        let group = message.message().group(146).unwrap();
+        assert_eq!(group.entry(1).fv::<&str>(55), Ok("MSFT"));
+    }
+
+    #[test]
+    fn raw_decoder_yields_frames() {
+        let mut codec = TokioRawDecoder::<Config>::new();
+        codec.config_mut().set_separator(b'|');
+        let mut src = BytesMut::from(MSG);
+        src.extend_from_slice(MSG);
+        let frame = codec.decode(&mut src).unwrap().unwrap();
+        assert_eq!(frame.as_bytes(), MSG);
+        assert_eq!(frame.begin_string(), b"FIX.4.4");
+        assert_eq!(src.len(), MSG.len());
+    }
+
+    #[test]
+    fn groups_are_accessible() {
+        let mut codec = decoder();
+        let mut src =
+            BytesMut::from(&b"8=FIX.4.4|9=39|35=V|262=1|146=2|55=AAPL|55=MSFT|263=0|10=023|"[..]);
+        codec.config_mut().set_verify_checksum(false);
+        let message = codec.decode(&mut src).unwrap().unwrap();
+        let message = message.message();
+        let group = message.group(146).unwrap();
+        assert_eq!(group.len(), 2);
+        assert_eq!(group.entry(1).fv::<&str>(55), Ok("MSFT"));
+    }
+
+    #[tokio::test]
+    async fn framed_round_trip() {
+        let (client, server) = tokio::io::duplex(64);
+        let mut encoder = TokioEncoder::<Config>::default();
+        encoder.config_mut().set_separator(b'|');
+        let mut sink = FramedWrite::new(client, encoder);
+        let mut stream = FramedRead::new(server, decoder());
+
+        let messages = tokio::spawn(async move {
+            let mut seq_nums = Vec::new();
+            while let Some(message) = stream.next().await {
+                let message = message.unwrap();
+                seq_nums.push(message.message().fv::<u32>(34).unwrap());
+            }
+            seq_nums
+        });
+        for seq_num in 1u32..=10 {
+            sink.send(move |encoder: &mut Encoder, buffer: &mut BytesMut| {
+                let mut msg = encoder.start_message(b"FIX.4.4", buffer, b"0");
+                msg.set(34, seq_num);
+                msg.done();
+            })
+            .await
+            .unwrap();
+        }
+        let owned = decoder().decode(&mut BytesMut::from(MSG)).unwrap().unwrap();
+        sink.send(owned).await.unwrap();
+        let mut buffer = BytesMut::new();
+        let mut encoder = Encoder::<Config>::default();
+        encoder.config_mut().set_separator(b'|');
+        let mut msg = encoder.start_message(b"FIX.4.4", &mut buffer, b"0");
+        msg.set(34, 13u32);
+        let frozen = msg.freeze();
+        sink.send(frozen.clone()).await.unwrap();
+        sink.send(frozen).await.unwrap();
+        drop(sink);
+
+        let mut expected: Vec<u32> = (1..=10).collect();
+        expected.extend([12, 13, 13]);
+        assert_eq!(messages.await.unwrap(), expected);
+    }
+}
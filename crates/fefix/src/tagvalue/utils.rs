@@ -7,6 +7,10 @@ use std::convert::TryInto;
 //   8=?|9=?|35=?|10=???|
 pub const MIN_FIX_MESSAGE_LEN_IN_BYTES: usize = 20;
 
+/// `BeginString <8>` and `BodyLength <9>` can't possibly take up more than this
+/// many bytes. Anything longer is garbage.
+pub const MAX_HEADER_LEN_IN_BYTES: usize = 64;
+
 /// The checksum field is composed of:
 ///  - `10=`       (3 characters)
 ///  - `XYZ`       (checksum value, always 3 characters)
@@ -14,6 +18,31 @@ pub const MIN_FIX_MESSAGE_LEN_IN_BYTES: usize = 20;
 /// Total: 7 characters.
 pub const FIELD_CHECKSUM_LEN_IN_BYTES: usize = 7;
 
+/// Every tag-value message starts with this byte sequence, regardless of the
+/// FIX version.
+pub const BEGIN_STRING_START_SEQUENCE: &[u8] = b"8=FIX";
+
+/// Returns how many leading bytes of `data` must be discarded to get to the
+/// next [`BEGIN_STRING_START_SEQUENCE`], skipping the one at the very start of
+/// `data` (if any). When there's no such sequence, all bytes are discarded
+/// except for a trailing partial match, which might be completed by more data.
+pub fn resync_offset(data: &[u8]) -> usize {
+    let seq = BEGIN_STRING_START_SEQUENCE;
+    if let Some(i) = data
+        .get(1..)
+        .unwrap_or_default()
+        .windows(seq.len())
+        .position(|window| window == seq)
+    {
+        return i + 1;
+    }
+    let partial_match_len = (1..seq.len())
+        .rev()
+        .find(|len| data.len() > *len && data.ends_with(&seq[..*len]))
+        .unwrap_or(0);
+    data.len() - partial_match_len
+}
+
 /// Returns a copy of the `CheckSum <10>` digits of `message`.
 pub fn checksum_digits(message: &[u8]) -> [u8; 3] {
     debug_assert!(message.len() >= MIN_FIX_MESSAGE_LEN_IN_BYTES);
@@ -68,4 +97,14 @@ mod test {
             b"ABC"
         );
     }
+
+    #[test]
+    fn resync_offset_skips_to_next_begin_string() {
+        assert_eq!(resync_offset(b""), 0);
+        assert_eq!(resync_offset(b"8=FIX.4.4|9=garbage"), 19);
+        assert_eq!(resync_offset(b"8=FIX.4.4|9=garbage8=FIX.4.4|"), 19);
+        assert_eq!(resync_offset(b"garbage8=FIX"), 7);
+        assert_eq!(resync_offset(b"garbage8=F"), 7);
+        assert_eq!(resync_offset(b"8=F"), 3);
+    }
 }
@@ -0,0 +1,521 @@
+use crate::dict::{FieldConstraints, FixDatatype, FlatLayout, Presence};
+use crate::{Dictionary, RandomFieldAccess, RepeatingGroup, TagU16};
+use fnv::{FnvHashMap, FnvHashSet};
+use regex::Regex;
+
+/// Checks the presence and values of fields within FIX messages against the
+/// layouts and field definitions of a [`Dictionary`].
+///
+/// Besides the `required` flag of layout items, [`Validator`] evaluates
+/// conditional presence rules (see [`crate::dict::FlatLayoutItem::presence`]),
+/// e.g. `Price <44>` is required if `OrdType <40>` is `Limit`. Conditions
+/// within repeating group entries may refer to fields of the entry itself as
+/// well as to those of the enclosing entries and message.
+///
+/// The values of all fields within the layouts must be among the enumerated
+/// values of their field, if any (every member of `MultipleCharValue` and
+/// `MultipleStringValue` fields, separately), and they must satisfy its
+/// [`FieldConstraints`]. See [`Validator::check_value`].
+///
+/// `BodyLength <9>` and `CheckSum <10>` are not checked, as they're verified
+/// (and then left out of messages) by decoders.
+///
+/// Validation is opt-in at decode time: see
+/// [`Decoder::set_validator`](super::Decoder::set_validator).
+///
+/// # Examples
+///
+/// ```
+/// use fefix::dict::{LayoutTarget, Presence, PresenceRuleSpec};
+/// use fefix::tagvalue::{Config, Decoder, ValidationError, Validator};
+/// use fefix::prelude::*;
+///
+/// let mut editor = Dictionary::fix44().edit();
+/// editor.add_presence_rule(
+///     LayoutTarget::Message("D"),
+///     "Price",
+///     PresenceRuleSpec::new(Presence::Required, "OrdType == ^Limit")
+///         .with_name("PriceRequiredForLimitOrders"),
+/// )?;
+/// let dict = editor.build();
+///
+/// let mut decoder = Decoder::<Config>::new(dict.clone());
+/// decoder.config_mut().set_separator(b'|');
+/// let message = decoder
+///     .decode(b"8=FIX.4.4|9=87|35=D|49=A|56=B|34=2|52=20220101-00:00:00|11=1|21=1|55=X|54=1|60=20220101-00:00:00|40=2|10=008|")
+///     .unwrap();
+///
+/// let validator = Validator::new(dict);
+/// assert_eq!(
+///     validator.validate(&message),
+///     Err(ValidationError::RequiredFieldMissing {
+///         tag: TagU16::new(44).unwrap(),
+///         rule: Some("PriceRequiredForLimitOrders".to_string()),
+///     })
+/// );
+/// # Ok::<(), fefix::dict::EditDictionaryError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Validator {
+    dict: Dictionary,
+    value_checks: FnvHashMap<u16, ValueCheck>,
+}
+
+/// The checks of the values of a single field.
+#[derive(Debug, Clone)]
+struct ValueCheck {
+    enums: Option<FnvHashSet<String>>,
+    is_multiple_value: bool,
+    constraints: FieldConstraints,
+    // Anchored, so that it matches whole values.
+    pattern: Option<Regex>,
+}
+
+/// The error type returned by [`Validator`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    /// `MsgType <35>` is not defined by the [`Dictionary`].
+    #[error("Unknown message type '{0}'.")]
+    UnknownMsgType(String),
+    /// A required field is missing. `rule` is the name of the presence rule
+    /// which requires it, if any.
+    #[error("Required field <{tag}> is missing{}.", rule_suffix(.rule))]
+    RequiredFieldMissing { tag: TagU16, rule: Option<String> },
+    /// A forbidden field is present. `rule` is the name of the presence rule
+    /// which forbids it, if any.
+    #[error("Field <{tag}> is forbidden{}.", rule_suffix(.rule))]
+    ForbiddenField { tag: TagU16, rule: Option<String> },
+    /// The value of a `NumInGroup` field is not a valid number of entries.
+    #[error("Invalid number of entries in repeating group <{0}>.")]
+    InvalidGroup(TagU16),
+    /// The value of a field is not among its enumerated values.
+    #[error("'{value}' is not a valid value of field <{tag}>.")]
+    UnknownEnumValue { tag: TagU16, value: String },
+    /// The value of a field is longer than [`FieldConstraints::max_length`].
+    #[error("The value of field <{tag}> is longer than {max_length} bytes.")]
+    ValueTooLong { tag: TagU16, max_length: usize },
+    /// The value of a field doesn't match [`FieldConstraints::pattern`].
+    #[error("'{value}' doesn't match the pattern of field <{tag}>.")]
+    PatternMismatch { tag: TagU16, value: String },
+    /// The value of a field is not a number between [`FieldConstraints::min`]
+    /// and [`FieldConstraints::max`].
+    #[error("'{value}' is out of the range of field <{tag}>.")]
+    ValueOutOfRange { tag: TagU16, value: String },
+}
+
+fn rule_suffix(rule: &Option<String>) -> String {
+    match rule {
+        Some(name) => format!(" (rule '{}')", name),
+        None => String::new(),
+    }
+}
+
+impl Validator {
+    /// Creates a new [`Validator`] which takes message layouts, presence
+    /// rules, enumerated values and [`FieldConstraints`] from `dict`.
+    pub fn new(dict: Dictionary) -> Self {
+        let mut value_checks = FnvHashMap::default();
+        for field in dict.iter_fields() {
+            let enums = field
+                .enums()
+                .map(|enums| {
+                    enums
+                        .map(|e| e.value().to_string())
+                        .collect::<FnvHashSet<_>>()
+                })
+                .filter(|enums| !enums.is_empty());
+            let constraints = field.constraints();
+            if enums.is_none() && constraints.is_empty() {
+                continue;
+            }
+            // Dictionaries reject invalid patterns, so compiling them can't
+            // fail.
+            let pattern = constraints.pattern_regex().and_then(Result::ok);
+            let check = ValueCheck {
+                enums,
+                is_multiple_value: matches!(
+                    field.fix_datatype(),
+                    FixDatatype::MultipleCharValue | FixDatatype::MultipleStringValue
+                ),
+                constraints: constraints.clone(),
+                pattern,
+            };
+            value_checks.insert(field.tag().get(), check);
+        }
+        Self { dict, value_checks }
+    }
+
+    /// Returns the [`Dictionary`] used by `self`.
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dict
+    }
+
+    /// Validates `message`, returning the first error found, if any.
+    pub fn validate<M>(&self, message: &M) -> Result<(), ValidationError>
+    where
+        M: RandomFieldAccess<u32>,
+    {
+        match self.errors(message).into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Validates `message`, returning all errors found in the standard header,
+    /// message body, and standard trailer, in this order.
+    pub fn errors<M>(&self, message: &M) -> Vec<ValidationError>
+    where
+        M: RandomFieldAccess<u32>,
+    {
+        let tables = self.dict.lookup_tables();
+        let mut errors = Vec::new();
+        let scopes = [message];
+        self.validate_layout(tables.header_layout(), &scopes, &mut errors);
+        if let Some(msg_type) = message.fv_raw(35) {
+            let msg_type = String::from_utf8_lossy(msg_type);
+            match tables.message_layout(&msg_type) {
+                Some(layout) => self.validate_layout(layout, &scopes, &mut errors),
+                None => errors.push(ValidationError::UnknownMsgType(msg_type.into_owned())),
+            }
+        }
+        self.validate_layout(tables.trailer_layout(), &scopes, &mut errors);
+        errors
+    }
+
+    /// Checks `value` against the enumerated values and [`FieldConstraints`]
+    /// of the field with the given `tag`, e.g. before encoding it. Unknown
+    /// fields accept any value.
+    ///
+    /// ```
+    /// use fefix::tagvalue::{ValidationError, Validator};
+    /// use fefix::{Dictionary, TagU16};
+    ///
+    /// let validator = Validator::new(Dictionary::fix44());
+    /// let side = TagU16::new(54).unwrap();
+    /// assert!(validator.check_value(side, b"1").is_ok());
+    /// assert_eq!(
+    ///     validator.check_value(side, b"Z"),
+    ///     Err(ValidationError::UnknownEnumValue { tag: side, value: "Z".to_string() })
+    /// );
+    /// ```
+    pub fn check_value(&self, tag: TagU16, value: &[u8]) -> Result<(), ValidationError> {
+        let check = match self.value_checks.get(&tag.get()) {
+            Some(check) => check,
+            None => return Ok(()),
+        };
+        let text = String::from_utf8_lossy(value);
+        if let Some(enums) = &check.enums {
+            let is_valid = if check.is_multiple_value {
+                text.split(' ').all(|member| enums.contains(member))
+            } else {
+                enums.contains(text.as_ref())
+            };
+            if !is_valid {
+                return Err(ValidationError::UnknownEnumValue {
+                    tag,
+                    value: text.into_owned(),
+                });
+            }
+        }
+        let constraints = &check.constraints;
+        if let Some(max_length) = constraints.max_length.filter(|max| value.len() > *max) {
+            return Err(ValidationError::ValueTooLong { tag, max_length });
+        }
+        if let Some(false) = check.pattern.as_ref().map(|p| p.is_match(&text)) {
+            return Err(ValidationError::PatternMismatch {
+                tag,
+                value: text.into_owned(),
+            });
+        }
+        if constraints.min.is_some() || constraints.max.is_some() {
+            let is_in_range = text.parse::<f64>().map_or(false, |number| {
+                constraints.min.map_or(true, |min| number >= min)
+                    && constraints.max.map_or(true, |max| number <= max)
+            });
+            if !is_in_range {
+                return Err(ValidationError::ValueOutOfRange {
+                    tag,
+                    value: text.into_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the innermost of `scopes` (i.e. the last one) against
+    /// `layout`. Outer scopes are only used to evaluate conditions.
+    fn validate_layout<M>(
+        &self,
+        layout: &FlatLayout,
+        scopes: &[&M],
+        errors: &mut Vec<ValidationError>,
+    ) where
+        M: RandomFieldAccess<u32>,
+    {
+        let current = scopes[scopes.len() - 1];
+        for item in layout.items() {
+            let tag = item.tag();
+            if FRAMING_TAGS.contains(&tag.get()) {
+                continue;
+            }
+            let (presence, rule) = item.presence(|tag| {
+                scopes
+                    .iter()
+                    .rev()
+                    .find_map(|scope| scope.fv_raw(u32::from(tag.get())))
+            });
+            let rule = rule.and_then(|rule| rule.name()).map(str::to_string);
+            let value = current.fv_raw(u32::from(tag.get()));
+            match presence {
+                Presence::Required if value.is_none() => {
+                    errors.push(ValidationError::RequiredFieldMissing { tag, rule })
+                }
+                Presence::Forbidden if value.is_some() => {
+                    errors.push(ValidationError::ForbiddenField { tag, rule })
+                }
+                _ => {}
+            }
+            if let Some(Err(err)) = value.map(|value| self.check_value(tag, value)) {
+                errors.push(err);
+            }
+            let definition = match item.group().filter(|_| value.is_some()) {
+                Some(definition) => definition,
+                None => continue,
+            };
+            let group = match current.group_opt(u32::from(tag.get())) {
+                Some(Ok(group)) => group,
+                _ => {
+                    errors.push(ValidationError::InvalidGroup(tag));
+                    continue;
+                }
+            };
+            for entry in group.entries() {
+                let mut entry_scopes = scopes.to_vec();
+                entry_scopes.push(&entry);
+                self.validate_layout(definition.layout(), &entry_scopes, errors);
+            }
+        }
+    }
+}
+
+/// `BodyLength <9>` and `CheckSum <10>`.
+const FRAMING_TAGS: &[u16] = &[9, 10];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dict::{LayoutTarget, PresenceRuleSpec};
+    use crate::tagvalue::{Config, Decoder};
+    use crate::GetConfig;
+
+    const RULES: &str = r#"
+        <fix>
+            <rules>
+                <message msgtype='D'>
+                    <rule item='Price' name='PriceForLimitOrders'>
+                        <when>OrdType == ^Limit || OrdType == ^StopLimit</when>
+                    </rule>
+                    <rule item='Price' presence='forbidden'>
+                        <when>OrdType == ^Market</when>
+                    </rule>
+                    <rule item='StopPx'>
+                        <when>OrdType in {^Stop, ^StopLimit}</when>
+                    </rule>
+                </message>
+                <group name='NoPartyIDs'>
+                    <rule item='PartyIDSource'>
+                        <when>exists PartyID &amp;&amp; OrdType != ^Market</when>
+                    </rule>
+                </group>
+            </rules>
+        </fix>
+    "#;
+
+    const HEADER: &str = "35=D|49=A|56=B|34=2|52=20220101-00:00:00|";
+    const BODY: &str = "11=1|21=1|55=X|54=1|60=20220101-00:00:00|";
+
+    fn dict() -> Dictionary {
+        Dictionary::fix44().with_overlay(RULES).unwrap()
+    }
+
+    /// Decodes `fields` (everything but `BeginString <8>`, `BodyLength <9>`
+    /// and `CheckSum <10>`) and validates the resulting message.
+    fn validate_fields(dict: &Dictionary, fields: &str) -> Vec<ValidationError> {
+        let mut decoder = Decoder::<Config>::new(dict.clone());
+        decoder.config_mut().set_separator(b'|');
+        decoder.config_mut().set_verify_checksum(false);
+        let data = format!("8=FIX.4.4|9={}|{}10=000|", fields.len(), fields);
+        let message = decoder.decode(data.as_bytes()).unwrap();
+        Validator::new(dict.clone()).errors(&message)
+    }
+
+    fn errors(dict: &Dictionary, fields: &str) -> Vec<ValidationError> {
+        validate_fields(dict, &format!("{}{}{}", HEADER, BODY, fields))
+    }
+
+    fn missing(tag: u16, rule: Option<&str>) -> ValidationError {
+        ValidationError::RequiredFieldMissing {
+            tag: TagU16::new(tag).unwrap(),
+            rule: rule.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn required_fields() {
+        let dict = dict();
+        assert_eq!(errors(&dict, "40=1|"), vec![]);
+        assert_eq!(
+            validate_fields(&dict, "35=D|49=A|56=B|52=20220101-00:00:00|11=1|"),
+            [34, 54, 60, 40]
+                .iter()
+                .map(|tag| missing(*tag, None))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn conditionally_required_fields() {
+        let dict = dict();
+        assert_eq!(
+            errors(&dict, "40=2|"),
+            vec![missing(44, Some("PriceForLimitOrders"))]
+        );
+        assert_eq!(errors(&dict, "40=2|44=10|"), vec![]);
+        assert_eq!(errors(&dict, "40=3|"), vec![missing(99, None)]);
+        assert_eq!(
+            errors(&dict, "40=4|"),
+            vec![missing(44, Some("PriceForLimitOrders")), missing(99, None)]
+        );
+        assert_eq!(errors(&dict, "40=4|44=10|99=9|"), vec![]);
+    }
+
+    #[test]
+    fn conditionally_forbidden_fields() {
+        let dict = dict();
+        let err = errors(&dict, "40=1|44=10|").pop().unwrap();
+        assert_eq!(
+            err,
+            ValidationError::ForbiddenField {
+                tag: TagU16::new(44).unwrap(),
+                rule: None,
+            }
+        );
+        assert_eq!(err.to_string(), "Field <44> is forbidden.");
+        assert_eq!(
+            missing(44, Some("PriceForLimitOrders")).to_string(),
+            "Required field <44> is missing (rule 'PriceForLimitOrders')."
+        );
+    }
+
+    #[test]
+    fn rules_within_groups_see_enclosing_fields() {
+        let dict = dict();
+        assert_eq!(
+            errors(&dict, "40=2|44=1|453=2|448=X|447=D|448=Y|"),
+            vec![missing(447, None)]
+        );
+        assert_eq!(errors(&dict, "40=1|453=2|448=X|448=Y|"), vec![]);
+    }
+
+    #[test]
+    fn rules_added_with_editor() {
+        let mut editor = Dictionary::fix44().edit();
+        editor
+            .add_presence_rule(
+                LayoutTarget::Message("D"),
+                "Price",
+                PresenceRuleSpec::new(Presence::Required, "exists StopPx || OrderQty > 100"),
+            )
+            .unwrap();
+        let dict = editor.build();
+        assert_eq!(errors(&dict, "40=1|38=100|"), vec![]);
+        assert_eq!(errors(&dict, "40=1|38=100.5|"), vec![missing(44, None)]);
+        assert_eq!(errors(&dict, "40=1|99=1|"), vec![missing(44, None)]);
+    }
+
+    #[test]
+    fn enumerated_values() {
+        let dict = Dictionary::fix44();
+        assert_eq!(errors(&dict, "40=1|18=1 2|"), vec![]);
+        let unknown = |tag, value: &str| ValidationError::UnknownEnumValue {
+            tag: TagU16::new(tag).unwrap(),
+            value: value.to_string(),
+        };
+        assert_eq!(errors(&dict, "40=~|"), vec![unknown(40, "~")]);
+        assert_eq!(errors(&dict, "40=1|18=1 ~|"), vec![unknown(18, "1 ~")]);
+        assert_eq!(
+            unknown(40, "~").to_string(),
+            "'~' is not a valid value of field <40>."
+        );
+    }
+
+    #[test]
+    fn field_constraints() {
+        let overlay = r#"
+            <fix>
+                <fields>
+                    <field number='11' maxlength='6' pattern='[A-Z]+-[0-9]+' />
+                    <field number='44' min='0.01' max='1000' />
+                </fields>
+            </fix>
+        "#;
+        let dict = Dictionary::fix44().with_overlay(overlay).unwrap();
+        let validator = Validator::new(dict);
+        let tag = |tag| TagU16::new(tag).unwrap();
+        assert_eq!(validator.check_value(tag(11), b"AB-12"), Ok(()));
+        assert_eq!(
+            validator.check_value(tag(11), b"ABC-123"),
+            Err(ValidationError::ValueTooLong {
+                tag: tag(11),
+                max_length: 6
+            })
+        );
+        assert_eq!(
+            validator.check_value(tag(11), b"AB-12a"),
+            Err(ValidationError::PatternMismatch {
+                tag: tag(11),
+                value: "AB-12a".to_string()
+            })
+        );
+        assert_eq!(validator.check_value(tag(44), b"0.01"), Ok(()));
+        assert_eq!(validator.check_value(tag(44), b"1000"), Ok(()));
+        for value in ["0", "1000.5", "abc", ""] {
+            assert_eq!(
+                validator.check_value(tag(44), value.as_bytes()),
+                Err(ValidationError::ValueOutOfRange {
+                    tag: tag(44),
+                    value: value.to_string()
+                })
+            );
+        }
+        assert_eq!(validator.check_value(tag(9999), b"foo"), Ok(()));
+        assert_eq!(
+            errors(validator.dictionary(), "40=2|44=0|"),
+            vec![
+                ValidationError::PatternMismatch {
+                    tag: tag(11),
+                    value: "1".to_string()
+                },
+                ValidationError::ValueOutOfRange {
+                    tag: tag(44),
+                    value: "0".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_msg_type() {
+        let dict = Dictionary::fix44();
+        assert_eq!(
+            validate_fields(&dict, "35=ZZ|49=A|56=B|34=2|52=20220101-00:00:00|"),
+            vec![
+                ValidationError::UnknownEnumValue {
+                    tag: TagU16::new(35).unwrap(),
+                    value: "ZZ".to_string()
+                },
+                ValidationError::UnknownMsgType("ZZ".to_string())
+            ]
+        );
+    }
+}
@@ -10,3 +10,58 @@ macro_rules! dbglog {
         }
     }}
 }
+
+/// A global allocator for tests that counts heap allocations, to verify that
+/// hot paths don't allocate.
+#[cfg(test)]
+pub(crate) mod counting_allocator {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    struct CountingAllocator;
+
+    thread_local! {
+        // Only allocations of the current thread are counted, as tests run in
+        // parallel.
+        static ALLOCATIONS: Cell<Option<usize>> = Cell::new(None);
+    }
+
+    fn count() {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get().map(|n| n + 1)));
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            count();
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            count();
+            unsafe { System.alloc_zeroed(layout) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            count();
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// Runs `f` and returns the number of heap allocations it performed on
+    /// the current thread.
+    pub fn allocations<F>(f: F) -> usize
+    where
+        F: FnOnce(),
+    {
+        ALLOCATIONS.with(|n| n.set(Some(0)));
+        f();
+        ALLOCATIONS.with(|n| n.replace(None)).unwrap()
+    }
+}